@@ -3,7 +3,10 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use merkle_drop::msg::{ExecuteMsg, GetRootResponse, GetSubdenomResponse, InstantiateMsg, QueryMsg};
+use merkle_drop::msg::{
+    ExecuteMsg, ExpectedTotalResponse, GetRootResponse, GetSubdenomResponse, InstantiateMsg,
+    IsOwnerResponse, LeafPreimageResponse, QueryMsg, RemainingResponse, SudoMsg,
+};
 use merkle_drop::state::Config;
 
 fn main() {
@@ -15,7 +18,12 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(GetRootResponse), &out_dir);
     export_schema(&schema_for!(GetSubdenomResponse), &out_dir);
+    export_schema(&schema_for!(IsOwnerResponse), &out_dir);
+    export_schema(&schema_for!(RemainingResponse), &out_dir);
+    export_schema(&schema_for!(ExpectedTotalResponse), &out_dir);
+    export_schema(&schema_for!(LeafPreimageResponse), &out_dir);
 }
@@ -1,39 +1,156 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    to_binary, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdError,
+    StdResult, Uint128,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use merkle::leaf::LeafEncoding;
+use merkle::proof::Proof;
 
 use crate::error::ContractError;
-use crate::execute::claim::claim;
+use crate::execute::amounts::set_amounts;
+use crate::execute::claim::{
+    claim, claim_entry, claim_status, claim_typed, claimable_entry_amount, leaf_data,
+    verify_typed_proof,
+};
+use crate::execute::claim_signed::claim_signed;
+use crate::execute::deadline::extend_deadline;
+use crate::execute::denom_admin::verify_denom_admin;
+use crate::execute::refund::withdraw_refund;
+use crate::execute::revoke_claim::revoke_claim;
+use crate::execute::root::{freeze_root, update_root};
 use crate::execute::set_subdenom::set_subdenom;
-use crate::msg::{ExecuteMsg, GetRootResponse, GetSubdenomResponse, InstantiateMsg, QueryMsg};
+use crate::execute::sweep::sweep;
+use crate::ibc::ibc_lifecycle_complete;
+use crate::msg::{
+    ClaimableResponse, DenomAdminResponse, DropInfoResponse, ExecuteMsg, ExpectedTotalResponse,
+    ExportClaimsResponse, ExportedClaim, GetAmountResponse, GetRootResponse, GetSubdenomResponse,
+    HasClaimedBatchResponse, InstantiateMsg, IsOwnerResponse, LeafPreimageResponse,
+    ListEntriesResponse, ProofAmountResponse, ProofInfoResponse, QueryMsg, RecentFailuresResponse,
+    RefundsResponse, RemainingResponse, SudoMsg, VersionResponse, Vesting,
+};
 use crate::reply::{
-    handle_mint_reply, handle_send_reply, AUTHZ_EXEC_MINT_MSG_ID, AUTHZ_EXEC_SEND_MSG_ID,
+    handle_mint_reply, handle_send_reply, handle_transfer_reply, AUTHZ_EXEC_MINT_MSG_ID,
+    AUTHZ_EXEC_SEND_MSG_ID, IBC_TRANSFER_MSG_ID, MAX_RECENT_FAILURES,
+};
+use crate::state::{
+    Config, AMOUNTS, CLAIMED_AMOUNTS, CLAIM_RECORDS, CONFIG, FAILED_MINTS, REFUNDS, SUBDENOM,
 };
-use crate::state::{Config, CONFIG, SUBDENOM};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:merkle-drop";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// MAX_HAS_CLAIMED_BATCH_SIZE is the maximum number of entries of a HasClaimedBatch query.
+pub const MAX_HAS_CLAIMED_BATCH_SIZE: usize = 100;
+
+// DEFAULT_EXPORT_CLAIMS_LIMIT is the number of claims of an ExportClaims page if no limit is given.
+pub const DEFAULT_EXPORT_CLAIMS_LIMIT: u32 = 30;
+
+// MAX_EXPORT_CLAIMS_LIMIT is the maximum number of claims of an ExportClaims page.
+pub const MAX_EXPORT_CLAIMS_LIMIT: u32 = 100;
+
+// DEFAULT_LIST_ENTRIES_LIMIT is the number of entries of a ListEntries page if no limit is given.
+pub const DEFAULT_LIST_ENTRIES_LIMIT: u32 = 30;
+
+// MAX_LIST_ENTRIES_LIMIT is the maximum number of entries of a ListEntries page.
+pub const MAX_LIST_ENTRIES_LIMIT: u32 = 100;
+
+// HASH_FUNCTION is the hash function of the trees the contract verifies.
+const HASH_FUNCTION: &str = "sha3-256";
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    if msg.total_amount.is_zero() {
+        return Err(ContractError::InvalidTotalAmount {});
+    }
+
+    if let (Some(start), Some(deadline)) = (msg.claim_start, msg.claim_deadline) {
+        if start >= deadline {
+            return Err(ContractError::InvalidClaimWindow { start, deadline });
+        }
+    }
+
+    if let Some(hash) = msg.hash {
+        if hash != HASH_FUNCTION {
+            return Err(ContractError::UnsupportedHash {
+                hash,
+                supported: String::from(HASH_FUNCTION),
+            });
+        }
+    }
+
+    for (i, cap) in msg.denom_caps.iter().enumerate() {
+        let is_duplicate = msg.denom_caps[..i]
+            .iter()
+            .any(|other| other.denom == cap.denom);
+        if cap.amount.is_zero() || is_duplicate {
+            return Err(ContractError::InvalidDenomCap {
+                denom: cap.denom.clone(),
+            });
+        }
+    }
+
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+
+    // the root is stored in the encoding printed by the CLI, whatever its given encoding.
+    let merkle_root = merkle::parse_root(&msg.merkle_root)
+        .map_err(|e| ContractError::FailedToDecodeRoot {
+            root: e.to_string(),
+        })?
+        .to_string();
+
+    let salt = match msg.salt {
+        Some(salt) => match hex::decode(salt) {
+            Ok(salt) => Some(Binary::from(salt)),
+            Err(e) => {
+                return Err(ContractError::FailedToDecodeSalt {
+                    reason: e.to_string(),
+                })
+            }
+        },
+        None => None,
+    };
+
     let config = Config {
-        merkle_root: msg.merkle_root,
-        owner: info.sender.clone(),
+        merkle_root,
+        owner: owner.clone(),
+        total_amount: msg.total_amount,
+        salt,
+        root_frozen: false,
+        claim_start: msg.claim_start,
+        claim_deadline: msg.claim_deadline,
+        claim_fee: msg.claim_fee,
+        address_only: msg.address_only,
+        proof_version: u32::from(merkle::proof::VERSION),
+        denom_admin_ok: false,
+        denom_caps: msg.denom_caps,
+        eligibility_height: msg.eligibility_height,
+        leaf_count: msg.leaf_count,
+        max_proof_depth: msg.max_proof_depth,
+        require_self_claim: msg.require_self_claim,
+        leaf_encoding: msg.leaf_encoding,
+        vesting_start: msg.claim_start.unwrap_or(env.block.time),
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_attribute("method", "instantiate")
-        .add_attribute("owner", info.sender))
+        .add_attribute("owner", owner);
+    if let Some(eligibility_height) = config.eligibility_height {
+        response = response.add_attribute("eligibility_height", eligibility_height.to_string());
+    }
+    Ok(response)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -47,9 +164,94 @@ pub fn execute(
         ExecuteMsg::SetSubDenom { subdenom } => set_subdenom(deps, env, info, subdenom),
         ExecuteMsg::Claim {
             proof,
+            proof_version,
+            amount,
+            denom,
+            claimer_addr,
+            note,
+            vesting,
+            requested,
+            ibc_destination,
+        } => claim(
+            deps,
+            env,
+            info,
+            proof,
+            proof_version,
+            amount,
+            denom,
+            claimer_addr,
+            note,
+            vesting,
+            requested,
+            ibc_destination,
+        ),
+        ExecuteMsg::ClaimTyped {
+            proof,
+            proof_version,
+            amount,
+            denom,
+            claimer_addr,
+            note,
+            vesting,
+            requested,
+            ibc_destination,
+        } => claim_typed(
+            deps,
+            env,
+            info,
+            proof,
+            proof_version,
+            amount,
+            denom,
+            claimer_addr,
+            note,
+            vesting,
+            requested,
+            ibc_destination,
+        ),
+        ExecuteMsg::ClaimSigned {
+            proof,
+            proof_version,
+            amount,
+            denom,
+            claimer_addr,
+            note,
+            vesting,
+            requested,
+            signature,
+            pubkey,
+        } => claim_signed(
+            deps,
+            env,
+            info,
+            proof,
+            proof_version,
             amount,
+            denom,
             claimer_addr,
-        } => claim(deps, env, proof, amount, claimer_addr),
+            note,
+            vesting,
+            requested,
+            signature,
+            pubkey,
+        ),
+        ExecuteMsg::RevokeClaim {
+            address,
+            amount,
+            denom,
+            vesting,
+        } => revoke_claim(deps, info, address, amount, denom, vesting),
+        ExecuteMsg::UpdateRoot { merkle_root } => update_root(deps, info, merkle_root),
+        ExecuteMsg::FreezeRoot {} => freeze_root(deps, info),
+        ExecuteMsg::SetAmounts { amounts } => set_amounts(deps, info, amounts),
+        ExecuteMsg::VerifyDenomAdmin {} => verify_denom_admin(deps, info),
+        ExecuteMsg::Sweep { denom, recipient } => sweep(deps, env, info, denom, recipient),
+        ExecuteMsg::ExtendDeadline { new_deadline } => extend_deadline(deps, info, new_deadline),
+        ExecuteMsg::WithdrawRefund {
+            denom,
+            ibc_destination,
+        } => withdraw_refund(deps, env, info, denom, ibc_destination),
     }
 }
 
@@ -59,18 +261,87 @@ pub fn execute(
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     deps.api.debug("reply reached");
     if msg.id == AUTHZ_EXEC_MINT_MSG_ID {
-        return handle_mint_reply(deps, msg, env.contract.address.to_string());
+        return handle_mint_reply(
+            deps,
+            msg,
+            env.contract.address.to_string(),
+            env.block.height,
+        );
     } else if msg.id == AUTHZ_EXEC_SEND_MSG_ID {
-        return handle_send_reply(deps, msg);
+        return handle_send_reply(deps, msg, env.contract.address.to_string(), env.block.time);
+    } else if msg.id == IBC_TRANSFER_MSG_ID {
+        return handle_transfer_reply(deps, msg);
     }
     Err(ContractError::UnknownReplyId { reply_id: msg.id })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::IbcLifecycleComplete(msg) => ibc_lifecycle_complete(deps, msg),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetRoot {} => to_binary(&query_root(deps)?),
         QueryMsg::GetSubdenom {} => to_binary(&query_subdenom(deps)?),
+        QueryMsg::IsOwner { address } => to_binary(&query_is_owner(deps, address)?),
+        QueryMsg::Remaining {
+            claimer_addr,
+            amount,
+            denom,
+            vesting,
+        } => to_binary(&query_remaining(
+            deps,
+            env,
+            claimer_addr,
+            amount,
+            denom,
+            vesting,
+        )?),
+        QueryMsg::LeafPreimage {
+            address,
+            amount,
+            denom,
+            vesting,
+        } => to_binary(&query_leaf_preimage(deps, address, amount, denom, vesting)?),
+        QueryMsg::ExpectedTotal {} => to_binary(&query_expected_total(deps)?),
+        QueryMsg::HasClaimedBatch { entries } => {
+            to_binary(&query_has_claimed_batch(deps, entries)?)
+        }
+        QueryMsg::GetAmount { address } => to_binary(&query_amount(deps, address)?),
+        QueryMsg::ListEntries { start_after, limit } => {
+            to_binary(&query_list_entries(deps, start_after, limit)?)
+        }
+        QueryMsg::ExportClaims { start_after, limit } => {
+            to_binary(&query_export_claims(deps, start_after, limit)?)
+        }
+        QueryMsg::ProofInfo {} => to_binary(&query_proof_info(deps)?),
+        QueryMsg::DenomAdmin {} => to_binary(&query_denom_admin(deps)?),
+        QueryMsg::DropInfo {} => to_binary(&query_drop_info(deps)?),
+        QueryMsg::ProofAmount {
+            address,
+            amount,
+            denom,
+            vesting,
+            proof,
+        } => to_binary(&query_proof_amount(
+            deps, address, amount, denom, vesting, proof,
+        )?),
+        QueryMsg::Claimable {
+            address,
+            amount,
+            denom,
+            vesting,
+            proof,
+        } => to_binary(&query_claimable(
+            deps, env, address, amount, denom, vesting, proof,
+        )?),
+        QueryMsg::Version {} => to_binary(&query_version(deps)?),
+        QueryMsg::RecentFailures {} => to_binary(&query_recent_failures(deps)?),
+        QueryMsg::Refunds { claimer_addr } => to_binary(&query_refunds(deps, claimer_addr)?),
     }
 }
 
@@ -90,22 +361,274 @@ fn query_subdenom(deps: Deps) -> StdResult<GetSubdenomResponse> {
     Ok(GetSubdenomResponse { subdenom })
 }
 
+fn query_is_owner(deps: Deps, address: String) -> StdResult<IsOwnerResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(IsOwnerResponse {
+        is_owner: config.owner.as_str() == address.as_str(),
+    })
+}
+
+fn query_remaining(
+    deps: Deps,
+    env: Env,
+    claimer_addr: String,
+    amount: Uint128,
+    denom: Option<String>,
+    vesting: Option<Vesting>,
+) -> StdResult<RemainingResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (amount, claim) = claim_entry(deps, &config, &claimer_addr, Some(amount), &denom, &vesting)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let remaining =
+        match claimable_entry_amount(deps.storage, &env, &config, &claim, amount, &vesting, None) {
+            Ok(remaining) => remaining,
+            Err(ContractError::Revoked { .. })
+            | Err(ContractError::AlreadyClaimed { .. })
+            | Err(ContractError::NotVested { .. }) => Uint128::zero(),
+            Err(err) => return Err(StdError::generic_err(err.to_string())),
+        };
+    Ok(RemainingResponse { remaining })
+}
+
+fn query_leaf_preimage(
+    deps: Deps,
+    address: String,
+    amount: Uint128,
+    denom: Option<String>,
+    vesting: Option<Vesting>,
+) -> StdResult<LeafPreimageResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let preimage = claim_entry(deps, &config, &address, Some(amount), &denom, &vesting)
+        .and_then(|(_, claim)| leaf_data(&config, &claim))
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(LeafPreimageResponse {
+        preimage: hex::encode(preimage),
+    })
+}
+
+fn query_expected_total(deps: Deps) -> StdResult<ExpectedTotalResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ExpectedTotalResponse {
+        total_amount: config.total_amount,
+    })
+}
+
+fn query_proof_info(deps: Deps) -> StdResult<ProofInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let leaf_encoding = if config.address_only {
+        "address_only"
+    } else if config.leaf_encoding == LeafEncoding::U128Bytes {
+        "address_amount_u128_bytes"
+    } else {
+        "address_amount"
+    };
+    Ok(ProofInfoResponse {
+        version: config.proof_version,
+        hash: String::from(HASH_FUNCTION),
+        arity: 2,
+        leaf_encoding: String::from(leaf_encoding),
+        salted: config.salt.is_some(),
+        proof_format: String::from("json"),
+    })
+}
+
+fn query_drop_info(deps: Deps) -> StdResult<DropInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(DropInfoResponse {
+        merkle_root: config.merkle_root,
+        total_amount: config.total_amount,
+        leaf_count: config.leaf_count,
+        eligibility_height: config.eligibility_height,
+    })
+}
+
+fn query_proof_amount(
+    deps: Deps,
+    address: String,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+    vesting: Option<Vesting>,
+    proof: Proof,
+) -> StdResult<ProofAmountResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (amount, claim) = claim_entry(deps, &config, &address, amount, &denom, &vesting)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let leaf = leaf_data(&config, &claim).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let valid = verify_typed_proof(&config.merkle_root, &proof, leaf).is_ok();
+    Ok(ProofAmountResponse { valid, amount })
+}
+
+fn query_claimable(
+    deps: Deps,
+    env: Env,
+    address: String,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+    vesting: Option<Vesting>,
+    proof: Proof,
+) -> StdResult<ClaimableResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let status = claim_status(
+        deps, &env, &config, &proof, &address, amount, &denom, &vesting,
+    )
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(ClaimableResponse { status })
+}
+
+fn query_version(deps: Deps) -> StdResult<VersionResponse> {
+    let version = get_contract_version(deps.storage)?;
+    Ok(VersionResponse {
+        contract: version.contract,
+        version: version.version,
+    })
+}
+
+fn query_denom_admin(deps: Deps) -> StdResult<DenomAdminResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(DenomAdminResponse {
+        denom_admin_ok: config.denom_admin_ok,
+    })
+}
+
+fn query_has_claimed_batch(
+    deps: Deps,
+    entries: Vec<(String, Coin)>,
+) -> StdResult<HasClaimedBatchResponse> {
+    if entries.len() > MAX_HAS_CLAIMED_BATCH_SIZE {
+        return Err(StdError::generic_err(format!(
+            "batch of {} entries exceeds the maximum of {}",
+            entries.len(),
+            MAX_HAS_CLAIMED_BATCH_SIZE
+        )));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let has_claimed = entries
+        .iter()
+        .map(|(address, coin)| {
+            // the denom is bound into the leaf unless it is empty.
+            let denom = Some(coin.denom.clone()).filter(|denom| !denom.is_empty());
+            // an address of an address-only drop without an amount has no entry.
+            claim_entry(deps, &config, address, Some(coin.amount), &denom, &None)
+                .map_or(false, |(_, claim)| {
+                    CLAIMED_AMOUNTS.has(deps.storage, &claim)
+                })
+        })
+        .collect();
+    Ok(HasClaimedBatchResponse { has_claimed })
+}
+
+fn query_amount(deps: Deps, address: String) -> StdResult<GetAmountResponse> {
+    let amount = AMOUNTS.may_load(deps.storage, &deps.api.addr_validate(&address)?)?;
+    Ok(GetAmountResponse { amount })
+}
+
+fn query_list_entries(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListEntriesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.address_only {
+        return Err(StdError::generic_err(
+            ContractError::NotAddressOnly {}.to_string(),
+        ));
+    }
+
+    let limit = limit
+        .unwrap_or(DEFAULT_LIST_ENTRIES_LIMIT)
+        .min(MAX_LIST_ENTRIES_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let entries = AMOUNTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(address, amount)| (address.into_string(), amount)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListEntriesResponse { entries })
+}
+
+fn query_export_claims(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ExportClaimsResponse> {
+    let limit = limit
+        .unwrap_or(DEFAULT_EXPORT_CLAIMS_LIMIT)
+        .min(MAX_EXPORT_CLAIMS_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let claims = CLAIMED_AMOUNTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (claim, claimed) = item?;
+            let record = CLAIM_RECORDS.may_load(deps.storage, &claim)?;
+            Ok(ExportedClaim {
+                claimer_addr: record.as_ref().map(|r| r.claimer_addr.clone()),
+                last_claimed_at: record.map(|r| r.last_claimed_at),
+                claim,
+                claimed,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ExportClaimsResponse { claims })
+}
+
+fn query_recent_failures(deps: Deps) -> StdResult<RecentFailuresResponse> {
+    let failures = FAILED_MINTS
+        .range(deps.storage, None, None, Order::Descending)
+        .take(MAX_RECENT_FAILURES as usize)
+        .map(|item| item.map(|(_, failed_mint)| failed_mint))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RecentFailuresResponse { failures })
+}
+
+fn query_refunds(deps: Deps, claimer_addr: String) -> StdResult<RefundsResponse> {
+    let refunds = REFUNDS
+        .prefix(&claimer_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RefundsResponse { refunds })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execute::claim::claim_leaf;
+    use crate::msg::{ClaimStatus, FailedMint, IbcDestination, IbcLifecycleComplete};
+    use crate::state::{ClaimRecord, MintReplyState, MINTED_AMOUNTS, REPLY_STATE, REVOKED_CLAIMS};
+    use crate::testing::mock_dependencies_with_denom_admin;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{
+        coins, from_binary, BankMsg, SubMsg, SubMsgResponse, SubMsgResult, Timestamp,
+    };
+    use osmosis_std::types::ibc::applications::transfer::v1::MsgTransferResponse;
 
     // TEST_ROOT test merkel root that was generated from "testdata/uosmo_only.csv" using merkle-drop-cli
     const TEST_ROOT: &str = "bd9c439f3903b3dbc92bad230df593d434aada80f26e8124d77d2f92fbaa6238";
+    // TEST_ROOT_BASE64 is TEST_ROOT as stored by the contract.
+    const TEST_ROOT_BASE64: &str = "vZxDnzkDs9vJK60jDfWT1DSq2oDyboEk130vkvuqYjg=";
+    const TEST_TOTAL_AMOUNT: u128 = 1_000_000;
+
+    fn default_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            merkle_root: String::from(TEST_ROOT),
+            total_amount: Uint128::new(TEST_TOTAL_AMOUNT),
+            ..InstantiateMsg::default()
+        }
+    }
 
     #[test]
     fn proper_initialization() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg {
-            merkle_root: String::from(TEST_ROOT),
-        };
+        let msg = default_instantiate_msg();
+        // the funds are unrelated to the drop denom, which is minted on claims.
         let info = mock_info("creator", &coins(1000, "earth"));
 
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -113,6 +636,1084 @@ mod tests {
 
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRoot {}).unwrap();
         let value: GetRootResponse = from_binary(&res).unwrap();
-        assert_eq!(TEST_ROOT, value.root);
+        assert_eq!(TEST_ROOT_BASE64, value.root);
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!("creator", config.owner.as_str());
+    }
+
+    #[test]
+    fn initialization_owner_override() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            owner: Some(String::from("owner")),
+            ..default_instantiate_msg()
+        };
+        let info = mock_info("factory", &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!("owner", config.owner.as_str());
+    }
+
+    #[test]
+    fn initialization_invalid_owner_override() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            owner: Some(String::from("x")),
+            ..default_instantiate_msg()
+        };
+        let info = mock_info("factory", &[]);
+
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn is_owner() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        let info = mock_info("creator", &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IsOwner {
+                address: String::from("creator"),
+            },
+        )
+        .unwrap();
+        let value: IsOwnerResponse = from_binary(&res).unwrap();
+        assert_eq!(true, value.is_owner);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IsOwner {
+                address: String::from("anyone"),
+            },
+        )
+        .unwrap();
+        let value: IsOwnerResponse = from_binary(&res).unwrap();
+        assert_eq!(false, value.is_owner);
+    }
+
+    #[test]
+    fn remaining() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        let info = mock_info("creator", &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let remaining_query = QueryMsg::Remaining {
+            claimer_addr: String::from("claimer"),
+            amount: Uint128::new(100),
+            denom: None,
+            vesting: None,
+        };
+
+        let res = query(deps.as_ref(), mock_env(), remaining_query.clone()).unwrap();
+        let value: RemainingResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(100), value.remaining);
+
+        CLAIMED_AMOUNTS
+            .save(
+                deps.as_mut().storage,
+                &claim_leaf("claimer", Uint128::new(100), &None, &None).unwrap(),
+                &Uint128::new(40),
+            )
+            .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), remaining_query.clone()).unwrap();
+        let value: RemainingResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(60), value.remaining);
+
+        let revoke_msg = ExecuteMsg::RevokeClaim {
+            address: String::from("claimer"),
+            amount: Uint128::new(100),
+            denom: None,
+            vesting: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            revoke_msg,
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), remaining_query).unwrap();
+        let value: RemainingResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::zero(), value.remaining);
+    }
+
+    #[test]
+    fn remaining_vesting() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let vesting = Some(Vesting {
+            cliff: 60,
+            duration: 400,
+        });
+        let remaining = |deps: Deps, seconds: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            let res = query(
+                deps,
+                env,
+                QueryMsg::Remaining {
+                    claimer_addr: String::from("claimer"),
+                    amount: Uint128::new(1000),
+                    denom: None,
+                    vesting: vesting.clone(),
+                },
+            )
+            .unwrap();
+            let value: RemainingResponse = from_binary(&res).unwrap();
+            value.remaining
+        };
+
+        // only the vested amount that is not claimed is remaining.
+        assert_eq!(Uint128::zero(), remaining(deps.as_ref(), 59));
+        assert_eq!(Uint128::new(500), remaining(deps.as_ref(), 200));
+        assert_eq!(Uint128::new(1000), remaining(deps.as_ref(), 400));
+
+        CLAIMED_AMOUNTS
+            .save(
+                deps.as_mut().storage,
+                &claim_leaf("claimer", Uint128::new(1000), &None, &vesting).unwrap(),
+                &Uint128::new(500),
+            )
+            .unwrap();
+        assert_eq!(Uint128::zero(), remaining(deps.as_ref(), 200));
+        assert_eq!(Uint128::new(100), remaining(deps.as_ref(), 240));
+        assert_eq!(Uint128::new(500), remaining(deps.as_ref(), 400));
+    }
+
+    #[test]
+    fn has_claimed_batch() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        let info = mock_info("creator", &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        CLAIMED_AMOUNTS
+            .save(
+                deps.as_mut().storage,
+                &claim_leaf("claimer", Uint128::new(100), &None, &None).unwrap(),
+                &Uint128::new(40),
+            )
+            .unwrap();
+        let uosmo = Some(String::from("uosmo"));
+        CLAIMED_AMOUNTS
+            .save(
+                deps.as_mut().storage,
+                &claim_leaf("denomclaimer", Uint128::new(100), &uosmo, &None).unwrap(),
+                &Uint128::new(100),
+            )
+            .unwrap();
+
+        let entries = vec![
+            (String::from("claimer"), Coin::new(100, "")),
+            (String::from("other"), Coin::new(100, "")),
+            (String::from("claimer"), Coin::new(200, "")),
+            (String::from("claimer"), Coin::new(100, "uosmo")),
+            (String::from("denomclaimer"), Coin::new(100, "uosmo")),
+            (String::from("denomclaimer"), Coin::new(100, "uion")),
+            (String::from("denomclaimer"), Coin::new(100, "")),
+        ];
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::HasClaimedBatch { entries },
+        )
+        .unwrap();
+        let value: HasClaimedBatchResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![true, false, false, false, true, false, false],
+            value.has_claimed
+        );
+
+        let entries = vec![
+            (String::from("claimer"), Coin::new(100, "uosmo"));
+            MAX_HAS_CLAIMED_BATCH_SIZE + 1
+        ];
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::HasClaimedBatch { entries },
+        )
+        .unwrap_err();
+        assert!(matches!(err, StdError::GenericErr { .. }));
+    }
+
+    #[test]
+    fn address_only_amounts() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            address_only: true,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let set_amounts_msg = ExecuteMsg::SetAmounts {
+            amounts: vec![(String::from("claimer"), Uint128::new(100))],
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_amounts_msg,
+        )
+        .unwrap();
+
+        let amount_query = |address: &str| QueryMsg::GetAmount {
+            address: String::from(address),
+        };
+        let res = query(deps.as_ref(), mock_env(), amount_query("claimer")).unwrap();
+        let value: GetAmountResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Uint128::new(100)), value.amount);
+
+        let res = query(deps.as_ref(), mock_env(), amount_query("other")).unwrap();
+        let value: GetAmountResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.amount);
+
+        // the leaf is the address and the remaining amount is
+        // that of the amount set by the owner, whatever the given amount.
+        CLAIMED_AMOUNTS
+            .save(deps.as_mut().storage, "claimer", &Uint128::new(40))
+            .unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Remaining {
+                claimer_addr: String::from("claimer"),
+                amount: Uint128::new(1_000_000),
+                denom: None,
+                vesting: None,
+            },
+        )
+        .unwrap();
+        let value: RemainingResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(60), value.remaining);
+    }
+
+    #[test]
+    fn list_entries_pages() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            address_only: true,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let amounts: Vec<(String, Uint128)> = (1..=5)
+            .map(|i| (format!("claimer{}", i), Uint128::new(i * 100)))
+            .collect();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetAmounts {
+                amounts: amounts.clone(),
+            },
+        )
+        .unwrap();
+
+        let mut listed: Vec<(String, Uint128)> = vec![];
+        let mut start_after = None;
+        loop {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListEntries {
+                    start_after: start_after.clone(),
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+            let page: ListEntriesResponse = from_binary(&res).unwrap();
+            let page_len = page.entries.len();
+
+            start_after = page.entries.last().map(|(address, _)| address.clone());
+            listed.extend(page.entries);
+            if page_len < 2 {
+                break;
+            }
+        }
+        assert_eq!(amounts, listed);
+    }
+
+    #[test]
+    fn export_claims_pages() {
+        let mut deps = mock_dependencies();
+
+        let claims = [
+            "claimer1100",
+            "claimer2200",
+            "claimer3300",
+            "claimer4400",
+            "claimer5500",
+        ];
+        for (i, claim) in claims.iter().enumerate() {
+            CLAIMED_AMOUNTS
+                .save(deps.as_mut().storage, claim, &Uint128::new(i as u128 + 1))
+                .unwrap();
+        }
+        // the first entry was claimed before claims were recorded.
+        for (i, claim) in claims.iter().enumerate().skip(1) {
+            let record = ClaimRecord {
+                claimer_addr: format!("claimer{}", i + 1),
+                last_claimed_at: Timestamp::from_seconds(i as u64),
+            };
+            CLAIM_RECORDS
+                .save(deps.as_mut().storage, claim, &record)
+                .unwrap();
+        }
+
+        let mut exported: Vec<ExportedClaim> = vec![];
+        let mut start_after = None;
+        loop {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ExportClaims {
+                    start_after: start_after.clone(),
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+            let page: ExportClaimsResponse = from_binary(&res).unwrap();
+            let page_len = page.claims.len();
+
+            start_after = page.claims.last().map(|c| c.claim.clone());
+            exported.extend(page.claims);
+            if page_len < 2 {
+                break;
+            }
+        }
+
+        let exported_claims: Vec<&str> = exported.iter().map(|c| c.claim.as_str()).collect();
+        assert_eq!(claims.to_vec(), exported_claims);
+
+        assert_eq!(Uint128::new(1), exported[0].claimed);
+        assert_eq!(None, exported[0].claimer_addr);
+        assert_eq!(None, exported[0].last_claimed_at);
+
+        assert_eq!(Uint128::new(5), exported[4].claimed);
+        assert_eq!(Some(String::from("claimer5")), exported[4].claimer_addr);
+        assert_eq!(
+            Some(Timestamp::from_seconds(4)),
+            exported[4].last_claimed_at
+        );
+    }
+
+    #[test]
+    fn initialization_zero_total_amount() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            total_amount: Uint128::zero(),
+            ..default_instantiate_msg()
+        };
+        let info = mock_info("creator", &[]);
+
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidTotalAmount {}),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn expected_total() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        let info = mock_info("creator", &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ExpectedTotal {}).unwrap();
+        let value: ExpectedTotalResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(TEST_TOTAL_AMOUNT), value.total_amount);
+    }
+
+    #[test]
+    fn drop_info() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            eligibility_height: Some(6_000_000),
+            leaf_count: Some(12),
+            ..default_instantiate_msg()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "eligibility_height" && attr.value == "6000000"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::DropInfo {}).unwrap();
+        let value: DropInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            DropInfoResponse {
+                merkle_root: String::from(TEST_ROOT_BASE64),
+                total_amount: Uint128::new(TEST_TOTAL_AMOUNT),
+                leaf_count: Some(12),
+                eligibility_height: Some(6_000_000),
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn denom_admin_unverified() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // the admin is not known until the first VerifyDenomAdmin.
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::DenomAdmin {}).unwrap();
+        let value: DenomAdminResponse = from_binary(&res).unwrap();
+        assert!(!value.denom_admin_ok);
+    }
+
+    #[test]
+    fn version() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Version {}).unwrap();
+        let value: VersionResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            VersionResponse {
+                contract: String::from(CONTRACT_NAME),
+                version: String::from(CONTRACT_VERSION),
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn recent_failures() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let recent_failures = |deps: Deps| {
+            let res = query(deps, mock_env(), QueryMsg::RecentFailures {}).unwrap();
+            let value: RecentFailuresResponse = from_binary(&res).unwrap();
+            value.failures
+        };
+        assert!(recent_failures(deps.as_ref()).is_empty());
+
+        for (height, reason) in [(10, "unauthorized account"), (20, "denom not found")] {
+            REPLY_STATE
+                .save(
+                    deps.as_mut().storage,
+                    AUTHZ_EXEC_MINT_MSG_ID,
+                    &MintReplyState {
+                        claimer_addr: String::from("claimer"),
+                        amount: Uint128::new(100),
+                        denom: String::from("factory/creator/mydenom"),
+                        claim: String::from("claimer|100"),
+                        ibc_destination: None,
+                        sender: String::from("claimer"),
+                        fee_msg: None,
+                    },
+                )
+                .unwrap();
+            let mut env = mock_env();
+            env.block.height = height;
+            let res = reply(
+                deps.as_mut(),
+                env,
+                Reply {
+                    id: AUTHZ_EXEC_MINT_MSG_ID,
+                    result: SubMsgResult::Err(String::from(reason)),
+                },
+            )
+            .unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "merkle-drop-failure" && attr.value == reason));
+        }
+
+        // newest first.
+        let failure = |height, reason: &str| FailedMint {
+            claimer_addr: String::from("claimer"),
+            denom: String::from("factory/creator/mydenom"),
+            reason: String::from(reason),
+            height,
+        };
+        assert_eq!(
+            vec![
+                failure(20, "denom not found"),
+                failure(10, "unauthorized account")
+            ],
+            recent_failures(deps.as_ref())
+        );
+    }
+
+    #[test]
+    fn ibc_transfer_timeout_refund() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let denom = "factory/creator/mydenom";
+        let ibc_destination = IbcDestination {
+            channel: String::from("channel-0"),
+            receiver: String::from("cosmos1receiver"),
+            timeout_seconds: None,
+        };
+        REPLY_STATE
+            .save(
+                deps.as_mut().storage,
+                AUTHZ_EXEC_MINT_MSG_ID,
+                &MintReplyState {
+                    claimer_addr: String::from("claimer"),
+                    amount: Uint128::new(100),
+                    denom: String::from(denom),
+                    claim: String::from("claimer|100"),
+                    ibc_destination: Some(ibc_destination.clone()),
+                    sender: String::from("claimer"),
+                    fee_msg: None,
+                },
+            )
+            .unwrap();
+
+        // the claim is recorded and the transfer sent.
+        let reply_ok = |id, data| Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data,
+            }),
+        };
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            reply_ok(AUTHZ_EXEC_SEND_MSG_ID, None),
+        )
+        .unwrap();
+        assert_eq!(1, res.messages.len());
+        let data: Binary = MsgTransferResponse { sequence: 7 }.into();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            reply_ok(IBC_TRANSFER_MSG_ID, Some(data)),
+        )
+        .unwrap();
+
+        // the transfer times out and its tokens are refunded to the claimer.
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+                channel: String::from("channel-0"),
+                sequence: 7,
+            }),
+        )
+        .unwrap();
+        let refunds = |deps: Deps| {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::Refunds {
+                    claimer_addr: String::from("claimer"),
+                },
+            )
+            .unwrap();
+            let value: RefundsResponse = from_binary(&res).unwrap();
+            value.refunds
+        };
+        assert_eq!(vec![Coin::new(100, denom)], refunds(deps.as_ref()));
+
+        // only the claimer can withdraw its refund.
+        let withdraw_msg = ExecuteMsg::WithdrawRefund {
+            denom: String::from(denom),
+            ibc_destination: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::NothingToRefund { .. }),
+            "{:?}",
+            err
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("claimer", &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: String::from("claimer"),
+                amount: coins(100, denom),
+            })],
+            res.messages
+        );
+        assert!(refunds(deps.as_ref()).is_empty());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("claimer", &[]),
+            withdraw_msg,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn proof_info() {
+        for (salt, address_only, encoding, leaf_encoding) in [
+            (None, false, LeafEncoding::DecimalString, "address_amount"),
+            (
+                None,
+                false,
+                LeafEncoding::U128Bytes,
+                "address_amount_u128_bytes",
+            ),
+            (
+                Some(String::from("deadbeef")),
+                true,
+                LeafEncoding::DecimalString,
+                "address_only",
+            ),
+        ] {
+            let mut deps = mock_dependencies();
+
+            let msg = InstantiateMsg {
+                salt: salt.clone(),
+                address_only,
+                leaf_encoding: encoding,
+                ..default_instantiate_msg()
+            };
+            let info = mock_info("creator", &[]);
+
+            instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::ProofInfo {}).unwrap();
+            let value: ProofInfoResponse = from_binary(&res).unwrap();
+            assert_eq!(
+                ProofInfoResponse {
+                    version: u32::from(merkle::proof::VERSION),
+                    hash: String::from("sha3-256"),
+                    arity: 2,
+                    leaf_encoding: String::from(leaf_encoding),
+                    salted: salt.is_some(),
+                    proof_format: String::from("json"),
+                },
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn leaf_preimage() {
+        let mut deps = mock_dependencies();
+
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let address = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+        let amount = Uint128::new(1421901);
+        let vesting = Some(Vesting {
+            cliff: 60,
+            duration: 3600,
+        });
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LeafPreimage {
+                address: String::from(address),
+                amount,
+                denom: None,
+                vesting: vesting.clone(),
+            },
+        )
+        .unwrap();
+        let value: LeafPreimageResponse = from_binary(&res).unwrap();
+        let preimage = String::from_utf8(hex::decode(value.preimage).unwrap()).unwrap();
+
+        // the preimage is what the proof is verified against.
+        let claim = claim_leaf(address, amount, &None, &vesting).unwrap();
+        assert_eq!(claim, preimage);
+
+        let items = vec![
+            claim.clone(),
+            String::from("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4|10"),
+        ];
+        let mt = merkle::Tree::new(&items);
+        let root = mt.get_root().unwrap().to_string();
+        let proof = mt.find_proof(&claim).unwrap();
+
+        verify_typed_proof(&root, &proof, preimage).unwrap();
+    }
+
+    #[test]
+    fn proof_amount() {
+        let address = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+        let amount = Uint128::new(1421901);
+        let claim = claim_leaf(address, amount, &None, &None).unwrap();
+        let items = vec![
+            claim.clone(),
+            String::from("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4|10"),
+        ];
+        let mt = merkle::Tree::new(&items);
+        let proof = mt.find_proof(&claim).unwrap();
+
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            merkle_root: mt.get_root().unwrap().to_string(),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let proof_amount = |amount: u128| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ProofAmount {
+                    address: String::from(address),
+                    amount: Some(Uint128::new(amount)),
+                    denom: None,
+                    vesting: None,
+                    proof: proof.clone(),
+                },
+            )
+            .unwrap();
+            from_binary::<ProofAmountResponse>(&res).unwrap()
+        };
+
+        assert_eq!(
+            ProofAmountResponse {
+                valid: true,
+                amount,
+            },
+            proof_amount(amount.u128())
+        );
+
+        // the proof of the entry does not verify another amount.
+        assert_eq!(
+            ProofAmountResponse {
+                valid: false,
+                amount: Uint128::new(1421902),
+            },
+            proof_amount(1421902)
+        );
+    }
+
+    #[test]
+    fn proof_amount_u128_bytes() {
+        let address = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+        let amount = Uint128::new(1421901);
+        let claim = claim_leaf(address, amount, &None, &None).unwrap();
+        let items: Vec<Vec<u8>> = [
+            claim.as_str(),
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4|10",
+        ]
+        .iter()
+        .map(|leaf| merkle::leaf::with_encoding(LeafEncoding::U128Bytes, leaf.as_bytes()).unwrap())
+        .collect();
+        let mt = merkle::Tree::new(&items);
+        let proof = mt.find_proof(&items[0]).unwrap();
+
+        let proof_amount = |encoding: LeafEncoding| {
+            let mut deps = mock_dependencies();
+            let msg = InstantiateMsg {
+                merkle_root: mt.get_root().unwrap().to_string(),
+                leaf_encoding: encoding,
+                ..default_instantiate_msg()
+            };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ProofAmount {
+                    address: String::from(address),
+                    amount: Some(amount),
+                    denom: None,
+                    vesting: None,
+                    proof: proof.clone(),
+                },
+            )
+            .unwrap();
+            from_binary::<ProofAmountResponse>(&res).unwrap().valid
+        };
+
+        // the tree of u128 bytes amounts verifies in a drop of the same encoding only.
+        assert!(proof_amount(LeafEncoding::U128Bytes));
+        assert!(!proof_amount(LeafEncoding::DecimalString));
+    }
+
+    #[test]
+    fn claimable() {
+        let address = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+        let amount = Uint128::new(1421901);
+        let claim = claim_leaf(address, amount, &None, &None).unwrap();
+        let other = String::from("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4|10");
+        let items = vec![claim.clone(), other.clone()];
+        let mt = merkle::Tree::new(&items);
+        let proof = mt.find_proof(&claim).unwrap();
+
+        let mut deps = mock_dependencies_with_denom_admin(Some("creator"));
+        let msg = InstantiateMsg {
+            merkle_root: mt.get_root().unwrap().to_string(),
+            claim_start: Some(Timestamp::from_seconds(100)),
+            claim_deadline: Some(Timestamp::from_seconds(200)),
+            denom_caps: vec![Coin::new(2_000_000, "mydenom")],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let status = |deps: Deps, seconds: u64, amount: u128, proof: &Proof| {
+            let mut env = mock_env();
+            env.block.time = Timestamp::from_seconds(seconds);
+            let res = query(
+                deps,
+                env,
+                QueryMsg::Claimable {
+                    address: String::from(address),
+                    amount: Some(Uint128::new(amount)),
+                    denom: None,
+                    vesting: None,
+                    proof: proof.clone(),
+                },
+            )
+            .unwrap();
+            from_binary::<ClaimableResponse>(&res).unwrap().status
+        };
+
+        // the entry has no denom and the subdenom of the drop is not set yet.
+        assert_eq!(
+            ClaimStatus::SubdenomNotSet,
+            status(deps.as_ref(), 150, amount.u128(), &proof)
+        );
+        SUBDENOM
+            .save(deps.as_mut().storage, &String::from("mydenom"))
+            .unwrap();
+
+        assert_eq!(
+            ClaimStatus::Claimable,
+            status(deps.as_ref(), 150, amount.u128(), &proof)
+        );
+        assert_eq!(
+            ClaimStatus::NotStarted,
+            status(deps.as_ref(), 50, amount.u128(), &proof)
+        );
+        assert_eq!(
+            ClaimStatus::Expired,
+            status(deps.as_ref(), 200, amount.u128(), &proof)
+        );
+
+        // the proof of the entry does not verify another amount, nor does the
+        // proof of another entry verify the entry.
+        assert_eq!(
+            ClaimStatus::InvalidProof,
+            status(deps.as_ref(), 150, 1421902, &proof)
+        );
+        let other_proof = mt.find_proof(&other).unwrap();
+        assert_eq!(
+            ClaimStatus::InvalidProof,
+            status(deps.as_ref(), 150, amount.u128(), &other_proof)
+        );
+
+        // the claims minted so far leave less than the amount under the cap.
+        MINTED_AMOUNTS
+            .save(
+                deps.as_mut().storage,
+                "factory/creator/mydenom",
+                &Uint128::new(1_000_000),
+            )
+            .unwrap();
+        assert_eq!(
+            ClaimStatus::CapReached,
+            status(deps.as_ref(), 150, amount.u128(), &proof)
+        );
+        MINTED_AMOUNTS.remove(deps.as_mut().storage, "factory/creator/mydenom");
+
+        // the denom of the entry is not created on the chain.
+        deps.querier.admin = None;
+        assert_eq!(
+            ClaimStatus::DenomNotFound,
+            status(deps.as_ref(), 150, amount.u128(), &proof)
+        );
+        deps.querier.admin = Some(String::from("creator"));
+
+        CLAIMED_AMOUNTS
+            .save(deps.as_mut().storage, &claim, &amount)
+            .unwrap();
+        assert_eq!(
+            ClaimStatus::AlreadyClaimed,
+            status(deps.as_ref(), 150, amount.u128(), &proof)
+        );
+
+        REVOKED_CLAIMS
+            .save(deps.as_mut().storage, &claim, &true)
+            .unwrap();
+        assert_eq!(
+            ClaimStatus::Revoked,
+            status(deps.as_ref(), 150, amount.u128(), &proof)
+        );
+    }
+
+    #[test]
+    fn initialization_root_encodings() {
+        let prefixed_root = format!("0x{}", TEST_ROOT);
+        for (root, expected) in [
+            (TEST_ROOT, Ok(TEST_ROOT_BASE64)),
+            (prefixed_root.as_str(), Ok(TEST_ROOT_BASE64)),
+            (TEST_ROOT_BASE64, Ok(TEST_ROOT_BASE64)),
+            (&TEST_ROOT[..62], Err(())),
+            ("not a root", Err(())),
+        ] {
+            let mut deps = mock_dependencies();
+
+            let msg = InstantiateMsg {
+                merkle_root: String::from(root),
+                ..default_instantiate_msg()
+            };
+            let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+
+            match expected {
+                Ok(expected) => {
+                    res.unwrap();
+                    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+                    assert_eq!(expected, config.merkle_root, "{}", root);
+                }
+                Err(()) => {
+                    let err = res.unwrap_err();
+                    assert!(
+                        matches!(err, ContractError::FailedToDecodeRoot { .. }),
+                        "{}: {:?}",
+                        root,
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn initialization_invalid_salt() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            salt: Some(String::from("not hex")),
+            ..default_instantiate_msg()
+        };
+        let info = mock_info("creator", &[]);
+
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(
+            matches!(err, ContractError::FailedToDecodeSalt { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn leaf_preimage_salted() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            salt: Some(String::from("deadbeef")),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LeafPreimage {
+                address: String::from("claimer"),
+                amount: Uint128::new(100),
+                denom: None,
+                vesting: None,
+            },
+        )
+        .unwrap();
+        let value: LeafPreimageResponse = from_binary(&res).unwrap();
+        // the salt is prefixed with its length.
+        assert_eq!(
+            format!("00000004deadbeef{}", hex::encode("claimer100")),
+            value.preimage
+        );
+    }
+
+    #[test]
+    fn initialization_invalid_claim_window() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            claim_start: Some(Timestamp::from_seconds(200)),
+            claim_deadline: Some(Timestamp::from_seconds(100)),
+            ..default_instantiate_msg()
+        };
+        let info = mock_info("creator", &[]);
+
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidClaimWindow { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn initialization_unsupported_hash() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            hash: Some(String::from("blake3")),
+            ..default_instantiate_msg()
+        };
+        let err =
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnsupportedHash { ref hash, .. } if hash == "blake3"),
+            "{:?}",
+            err
+        );
+
+        let msg = InstantiateMsg {
+            hash: Some(String::from("sha3-256")),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
     }
 }
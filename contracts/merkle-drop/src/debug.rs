@@ -0,0 +1,44 @@
+// debug! logs the formatted message with the given api in debug builds only.
+// Release builds, such as the optimized contract deployed on chain, have
+// debug assertions disabled so the message is neither formatted nor logged.
+macro_rules! debug {
+    ($api:expr, $($arg:tt)*) => {
+        if cfg!(debug_assertions) {
+            // messages without arguments are formatted too for
+            // inline captured identifiers, e.g. "{admin:?}".
+            #[allow(clippy::useless_format)]
+            let message = format!($($arg)*);
+            $api.debug(&message);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct RecordingApi {
+        messages: RefCell<Vec<String>>,
+    }
+
+    impl RecordingApi {
+        fn debug(&self, message: &str) {
+            self.messages.borrow_mut().push(message.to_string());
+        }
+    }
+
+    // Run with `cargo test --release` to check that nothing is logged in release builds.
+    #[test]
+    fn debug_gated_by_debug_assertions() {
+        let api = RecordingApi::default();
+
+        debug!(api, "claim {}", "osmo1");
+
+        if cfg!(debug_assertions) {
+            assert_eq!(vec![String::from("claim osmo1")], *api.messages.borrow());
+        } else {
+            assert!(api.messages.borrow().is_empty());
+        }
+    }
+}
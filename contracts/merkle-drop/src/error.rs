@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError, Timestamp, Uint128};
+use merkle::leaf::LeafEncoding;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,20 +13,162 @@ pub enum ContractError {
     #[error("Unauthorized sender: {sender:?}, owner: {owner:?} ")]
     UnauthorizedSender { sender: String, owner: String },
 
+    #[error("Total amount must be positive")]
+    InvalidTotalAmount {},
+
+    #[error("Failed to decode salt: {reason:?}")]
+    FailedToDecodeSalt { reason: String },
+
+    #[error("Root is frozen and can no longer be updated")]
+    RootFrozen {},
+
     #[error("Failed to decode root: {root:?}")]
     FailedToDecodeRoot { root: String },
 
     #[error("Failed to verify proof")]
     FailedVerifyProof {},
 
+    #[error("Invalid claim signature")]
+    InvalidSignature {},
+
+    #[error("Signer {signer:?} is not the claimer {claimer:?}")]
+    SignerMismatch { signer: String, claimer: String },
+
+    #[error("Invalid address {address:?}: {reason:?}")]
+    InvalidAddress { address: String, reason: String },
+
+    #[error("Invalid entry: {reason:?}")]
+    InvalidEntry { reason: String },
+
+    #[error("Claim window start {start} must be before the deadline {deadline}")]
+    InvalidClaimWindow {
+        start: Timestamp,
+        deadline: Timestamp,
+    },
+
+    #[error("Claims have not started yet")]
+    ClaimNotStarted {},
+
+    #[error("Claims have ended")]
+    ClaimExpired {},
+
+    #[error("The drop has no claim deadline to extend")]
+    NoClaimDeadline {},
+
+    #[error("New deadline {new_deadline} must be after the claim deadline {deadline}")]
+    DeadlineNotExtended {
+        deadline: Timestamp,
+        new_deadline: Timestamp,
+    },
+
     #[error("{claim:?} already claimed")]
     AlreadyClaimed { claim: String },
 
+    #[error("{claim:?} has no vested amount left to claim")]
+    NotVested { claim: String },
+
+    #[error("{claim:?} revoked")]
+    Revoked { claim: String },
+
+    #[error("Requested amount {requested} must be positive and at most the remaining {remaining}")]
+    InvalidRequestedAmount {
+        requested: Uint128,
+        remaining: Uint128,
+    },
+
     #[error("{reply_id:?} unknown reply id")]
     UnknownReplyId { reply_id: u64 },
 
-    #[error("Failed to mint")]
-    FailedToMint {},
+    #[error("Failed to send: {reason:?}")]
+    FailedToSend { reason: String },
+
+    #[error("Claim fee of {expected} required, received {received}")]
+    InsufficientClaimFee { expected: Coin, received: Coin },
+
+    #[error("Invalid IBC transfer channel {channel:?}")]
+    InvalidIbcChannel { channel: String },
+
+    #[error(
+        "IBC transfer timeout must be positive and at most {max_seconds} seconds, got {seconds}"
+    )]
+    InvalidIbcTimeout { seconds: u64, max_seconds: u64 },
+
+    #[error("Only the claimer can claim to an IBC destination")]
+    IbcDestinationNotClaimer {},
+
+    #[error("Failed to transfer: {reason:?}")]
+    FailedToTransfer { reason: String },
+
+    #[error("Unknown IBC transfer {sequence} of channel {channel:?}")]
+    UnknownIbcTransfer { channel: String, sequence: u64 },
+
+    #[error("No {denom:?} to refund")]
+    NothingToRefund { denom: String },
+
+    #[error("Hash function {hash:?} is not supported, only {supported:?} trees are verified")]
+    UnsupportedHash { hash: String, supported: String },
+
+    #[error("Cap of denom {denom:?} must be positive and given once")]
+    InvalidDenomCap { denom: String },
+
+    #[error("Minted amount {minted} of {denom:?} exceeds its cap of {cap}")]
+    DenomCapExceeded {
+        denom: String,
+        cap: Uint128,
+        minted: Uint128,
+    },
+
+    #[error("Proof version {version} does not match the proof version {expected} of the drop")]
+    ProofVersionMismatch { version: u32, expected: u32 },
+
+    #[error("Proof leaf encoding {leaf_encoding:?} does not match the leaf encoding {expected:?} of the drop")]
+    LeafEncodingMismatch {
+        leaf_encoding: LeafEncoding,
+        expected: LeafEncoding,
+    },
+
+    #[error("Proof length {length} exceeds the maximum depth of {max_depth}")]
+    ProofTooLong { length: u128, max_depth: u32 },
+
+    #[error("Claim of {claimer_addr:?} must be sent by the claimer, not {sender:?}")]
+    SelfClaimRequired {
+        sender: String,
+        claimer_addr: String,
+    },
+
+    #[error("Note length {length:?} exceeds the maximum of {max_length:?} bytes")]
+    NoteTooLong { length: usize, max_length: usize },
+
+    #[error("Amounts can only be set in an address-only drop")]
+    NotAddressOnly {},
+
+    #[error("No amount set for {address:?}")]
+    NoAmount { address: String },
+
+    #[error("Amount is required unless the drop is address-only")]
+    MissingAmount {},
+
+    #[error("Denom and vesting are not bound into the leaves of an address-only drop")]
+    AddressOnlyEntry {},
+
+    #[error("Denom {denom:?} of the drop cannot be swept while claims are open")]
+    DropDenomSweep { denom: String },
+
+    #[error("No {denom:?} to sweep")]
+    NothingToSweep { denom: String },
+
+    #[error("Subdenom of the drop is not set")]
+    SubdenomNotSet {},
+
+    #[error("Denom {denom:?} has no tokenfactory authority metadata")]
+    DenomNotFound { denom: String },
+
+    #[error("Owner {owner:?} is not the admin {admin:?} of denom {denom:?}")]
+    NotDenomAdmin {
+        denom: String,
+        admin: String,
+        owner: String,
+    },
 
     #[error("AuthZ grant for tokenfactory mint is not issued for the contract address")]
     NoAuthZMintGrant {},
@@ -0,0 +1,100 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response, Uint128};
+
+use crate::state::{AMOUNTS, CONFIG};
+use crate::ContractError;
+
+// set_amounts sets the amounts of the given addresses of an address-only drop.
+// The root does not bind the amounts, so claimants trust the owner with them.
+pub fn set_amounts(
+    deps: DepsMut,
+    info: MessageInfo,
+    amounts: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // validate sender
+    if config.owner != info.sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: info.sender.into_string(),
+            owner: config.owner.into_string(),
+        });
+    }
+
+    if !config.address_only {
+        return Err(ContractError::NotAddressOnly {});
+    }
+
+    for (address, amount) in amounts.iter() {
+        let address = deps.api.addr_validate(address)?;
+        AMOUNTS.save(deps.storage, &address, amount)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "set_amounts")
+        .add_attribute("owner", info.sender)
+        .add_attribute("count", amounts.len().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Config;
+    use crate::testing::test_config;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+    use cosmwasm_std::Addr;
+
+    fn setup_config(deps: DepsMut, address_only: bool) {
+        let config = Config {
+            address_only,
+            ..test_config()
+        };
+        CONFIG.save(deps.storage, &config).unwrap();
+    }
+
+    #[test]
+    fn set_amounts_owner() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), true);
+
+        let amounts = vec![
+            (String::from("claimer"), Uint128::new(100)),
+            (String::from("other"), Uint128::new(5)),
+        ];
+        set_amounts(deps.as_mut(), mock_info("owner", &[]), amounts).unwrap();
+
+        let amount = AMOUNTS.load(deps.as_ref().storage, &Addr::unchecked("claimer"));
+        assert_eq!(Uint128::new(100), amount.unwrap());
+
+        // amounts can be updated without changing the root.
+        let amounts = vec![(String::from("claimer"), Uint128::new(50))];
+        set_amounts(deps.as_mut(), mock_info("owner", &[]), amounts).unwrap();
+
+        let amount = AMOUNTS.load(deps.as_ref().storage, &Addr::unchecked("claimer"));
+        assert_eq!(Uint128::new(50), amount.unwrap());
+    }
+
+    #[test]
+    fn set_amounts_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), true);
+
+        let amounts = vec![(String::from("claimer"), Uint128::new(100))];
+        let err = set_amounts(deps.as_mut(), mock_info("anyone", &[]), amounts).unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+        assert!(!AMOUNTS.has(deps.as_ref().storage, &Addr::unchecked("claimer")));
+    }
+
+    #[test]
+    fn set_amounts_not_address_only() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), false);
+
+        let amounts = vec![(String::from("claimer"), Uint128::new(100))];
+        let err = set_amounts(deps.as_mut(), mock_info("owner", &[]), amounts).unwrap_err();
+        assert!(matches!(err, ContractError::NotAddressOnly {}), "{:?}", err);
+    }
+}
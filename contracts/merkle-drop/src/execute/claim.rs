@@ -1,62 +1,143 @@
-use cosmwasm_std::{DepsMut, Env, Response, SubMsg, Uint128};
-use merkle::{hash::Hash, proof::Proof};
+use cosmwasm_std::{
+    Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdError, Storage,
+    SubMsg, Timestamp, Uint128,
+};
+use merkle::entry::{Address, Amount, ClaimEntry};
+use merkle::leaf;
+use merkle::proof::{Proof, VersionedProof};
 use osmosis_std::shim::Any;
 use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
 use osmosis_std::types::cosmos::base::v1beta1;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgMint, TokenfactoryQuerier};
 
 use crate::error::ContractError;
+use crate::msg::{ClaimStatus, IbcDestination, Vesting};
 use crate::reply::AUTHZ_EXEC_MINT_MSG_ID;
-use crate::state::{MintReplyState, CLAIMED_ADDRESSES, CONFIG, REPLY_STATE, SUBDENOM};
+use crate::state::{
+    Config, MintReplyState, AMOUNTS, CLAIMED_AMOUNTS, CONFIG, MINTED_AMOUNTS, REPLY_STATE,
+    REVOKED_CLAIMS, SUBDENOM,
+};
 
+// MAX_NOTE_LENGTH is the maximum length of the claim note in bytes.
+pub const MAX_NOTE_LENGTH: usize = 256;
+
+// DEFAULT_IBC_TIMEOUT_SECONDS is the timeout of the IBC transfer
+// of the claimed tokens if the destination does not give one.
+pub const DEFAULT_IBC_TIMEOUT_SECONDS: u64 = 600;
+
+// MAX_IBC_TIMEOUT_SECONDS is the maximum timeout of the IBC transfer of the
+// claimed tokens. Tokens of a failed or timed out transfer are refunded to the claimer.
+pub const MAX_IBC_TIMEOUT_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+#[allow(clippy::too_many_arguments)]
 pub fn claim(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     proof_str: String,
-    amount: Uint128,
+    proof_version: Option<u32>,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+    claimer_addr: String,
+    note: Option<String>,
+    vesting: Option<Vesting>,
+    requested: Option<Uint128>,
+    ibc_destination: Option<IbcDestination>,
+) -> Result<Response, ContractError> {
+    debug!(deps.api, "proof_str {0}", &proof_str);
+
+    let config = CONFIG.load(deps.storage)?;
+    let proof = parse_proof(&config, &proof_str)?;
+
+    claim_typed(
+        deps,
+        env,
+        info,
+        proof,
+        proof_version,
+        amount,
+        denom,
+        claimer_addr,
+        note,
+        vesting,
+        requested,
+        ibc_destination,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn claim_typed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proof: Proof,
+    proof_version: Option<u32>,
+    amount: Option<Uint128>,
+    denom: Option<String>,
     claimer_addr: String,
+    note: Option<String>,
+    vesting: Option<Vesting>,
+    requested: Option<Uint128>,
+    ibc_destination: Option<IbcDestination>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage).unwrap();
 
-    // TODO: validate claimer_addr is an actual account
+    validate_claim_window(&config, &env)?;
+
+    validate_proof_version(&config, proof_version)?;
+
+    validate_proof_depth(&config, &proof)?;
 
-    let claim = format!("{}{}", claimer_addr, amount);
+    validate_self_claim(&config, &info, &claimer_addr)?;
 
-    let claim_check = CLAIMED_ADDRESSES.may_load(deps.storage, &claim)?;
-    if claim_check.is_some() {
-        return Err(ContractError::AlreadyClaimed { claim });
+    let fee_msg = claim_fee_msg(&config, &info.funds)?;
+
+    if let Some(note) = &note {
+        validate_note(note)?;
     }
 
-    deps.api
-        .debug(&format!("merkle_root {0}", &config.merkle_root));
+    if let Some(ibc_destination) = &ibc_destination {
+        validate_ibc_destination(&info, &claimer_addr, ibc_destination)?;
+    }
 
-    deps.api.debug(&format!("proof_str {0}", &proof_str));
+    // TODO: validate claimer_addr is an actual account
 
-    deps.api.debug(&format!("claim {0}", &claim));
+    let (amount, claim) = claim_entry(
+        deps.as_ref(),
+        &config,
+        &claimer_addr,
+        amount,
+        &denom,
+        &vesting,
+    )?;
 
-    verify_proof(&config.merkle_root, &proof_str, claim)?;
+    let requested = validate_entry(
+        deps.as_ref(),
+        &env,
+        &config,
+        &proof,
+        &claim,
+        amount,
+        &vesting,
+        requested,
+    )?;
 
-    deps.api.debug("validation passed");
+    debug!(deps.api, "validation passed");
 
-    let subdenom = SUBDENOM.load(deps.storage)?;
+    // the denom of the entry is bound into the leaf, so it cannot be swapped.
+    let full_denom = claim_denom(deps.as_ref(), &config, &denom)?;
+    debug!(deps.api, "claim full_denom: claim end: {}", full_denom);
 
-    let full_denom = format!("factory/{}/{}", config.owner, subdenom);
-    deps.api
-        .debug(&format!("claim full_denom: claim end: {}", full_denom));
+    validate_denom_cap(deps.storage, &config, &full_denom, requested)?;
 
-    let tf_querier = TokenfactoryQuerier::new(&deps.querier);
-    let admin = tf_querier
-        .denom_authority_metadata(full_denom.clone())?
-        .authority_metadata
-        .unwrap()
-        .admin;
-    deps.api.debug(&format!("denom admin = {admin:?}"));
+    let admin = denom_admin(deps.as_ref(), &full_denom)?;
+    debug!(deps.api, "denom admin = {admin:?}");
 
     let mint_msg_res = MsgMint {
         sender: config.owner.to_string(),
         amount: Some(v1beta1::Coin {
             denom: full_denom.clone(),
-            amount: amount.to_string(),
+            amount: requested.to_string(),
         }),
     };
 
@@ -77,26 +158,513 @@ pub fn claim(
         AUTHZ_EXEC_MINT_MSG_ID,
         &MintReplyState {
             claimer_addr,
-            amount,
+            amount: requested,
             denom: full_denom,
+            claim,
+            ibc_destination: ibc_destination.clone(),
+            sender: info.sender.to_string(),
+            fee_msg,
         },
     )?;
 
-    deps.api.debug("claim end");
+    debug!(deps.api, "claim end");
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_attribute("action", "claim")
-        .add_submessage(SubMsg::reply_on_success(exec_msg, AUTHZ_EXEC_MINT_MSG_ID)))
+        .add_attribute("amount", amount.to_string());
+    if let Some(note) = note {
+        response = response.add_attribute("note", note);
+    }
+    if let Some(ibc_destination) = ibc_destination {
+        response = response
+            .add_attribute("ibc-channel", ibc_destination.channel)
+            .add_attribute("ibc-receiver", ibc_destination.receiver);
+    }
+    if let Some(vesting) = vesting {
+        response = response
+            .add_attribute("vesting-cliff", vesting.cliff.to_string())
+            .add_attribute("vesting-duration", vesting.duration.to_string());
+    }
+
+    // reply always so that a failed mint is reported by handle_mint_reply.
+    Ok(response.add_submessage(SubMsg::reply_always(exec_msg, AUTHZ_EXEC_MINT_MSG_ID)))
+}
+
+// validate_entry returns the amount to claim from the entry of the given leaf data
+// and amount if it is claimable at the block time and the proof verifies it against
+// the root. Shared by claim and claim_status so that they cannot disagree.
+#[allow(clippy::too_many_arguments)]
+fn validate_entry(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    proof: &Proof,
+    claim: &str,
+    amount: Uint128,
+    vesting: &Option<Vesting>,
+    requested: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    let requested =
+        claimable_entry_amount(deps.storage, env, config, claim, amount, vesting, requested)?;
+
+    debug!(deps.api, "merkle_root {0}", &config.merkle_root);
+
+    debug!(deps.api, "claim {0}", claim);
+
+    verify_typed_proof(&config.merkle_root, proof, leaf_data(config, claim)?)?;
+
+    Ok(requested)
+}
+
+// claim_status returns whether the entry of the claimer can be claimed with the proof
+// at the block time, running the checks of claim in the same order. The checks of the
+// sender and the funds of the claim message are not part of the status. Other errors,
+// e.g. an invalid entry, are returned as is.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn claim_status(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    proof: &Proof,
+    claimer_addr: &str,
+    amount: Option<Uint128>,
+    denom: &Option<String>,
+    vesting: &Option<Vesting>,
+) -> Result<ClaimStatus, ContractError> {
+    let res = validate_claim_window(config, env)
+        .and_then(|_| validate_proof_depth(config, proof))
+        .and_then(|_| claim_entry(deps, config, claimer_addr, amount, denom, vesting))
+        .and_then(|(amount, claim)| {
+            validate_entry(deps, env, config, proof, &claim, amount, vesting, None)
+        })
+        .and_then(|requested| {
+            let full_denom = claim_denom(deps, config, denom)?;
+            validate_denom_cap(deps.storage, config, &full_denom, requested)?;
+            denom_admin(deps, &full_denom)
+        });
+    match res {
+        Ok(_) => Ok(ClaimStatus::Claimable),
+        Err(ContractError::ClaimNotStarted {}) => Ok(ClaimStatus::NotStarted),
+        Err(ContractError::ClaimExpired {}) => Ok(ClaimStatus::Expired),
+        Err(ContractError::Revoked { .. }) => Ok(ClaimStatus::Revoked),
+        Err(ContractError::AlreadyClaimed { .. }) => Ok(ClaimStatus::AlreadyClaimed),
+        Err(ContractError::NotVested { .. }) => Ok(ClaimStatus::NotVested),
+        Err(ContractError::FailedVerifyProof {}) | Err(ContractError::ProofTooLong { .. }) => {
+            Ok(ClaimStatus::InvalidProof)
+        }
+        Err(ContractError::SubdenomNotSet {}) => Ok(ClaimStatus::SubdenomNotSet),
+        Err(ContractError::DenomCapExceeded { .. }) => Ok(ClaimStatus::CapReached),
+        Err(ContractError::DenomNotFound { .. }) => Ok(ClaimStatus::DenomNotFound),
+        Err(err) => Err(err),
+    }
+}
+
+// denom_admin returns the admin of the tokenfactory denom, or DenomNotFound if the
+// denom does not exist. Shared by claim and claim_status so that they cannot disagree.
+pub(crate) fn denom_admin(deps: Deps, denom: &str) -> Result<String, ContractError> {
+    let metadata = TokenfactoryQuerier::new(&deps.querier)
+        .denom_authority_metadata(denom.to_string())?
+        .authority_metadata
+        .ok_or_else(|| ContractError::DenomNotFound {
+            denom: denom.to_string(),
+        })?;
+    Ok(metadata.admin)
+}
+
+// claim_denom returns the full denom minted by the claim of an entry
+// of the given subdenom, or of the subdenom of the drop if none.
+pub(crate) fn claim_denom(
+    deps: Deps,
+    config: &Config,
+    denom: &Option<String>,
+) -> Result<String, ContractError> {
+    let subdenom = match denom {
+        Some(denom) => denom.clone(),
+        None => SUBDENOM
+            .may_load(deps.storage)?
+            .ok_or(ContractError::SubdenomNotSet {})?,
+    };
+    Ok(format!("factory/{}/{}", config.owner, subdenom))
+}
+
+// validate_denom_cap returns the total minted of the full denom once the amount is
+// minted, or an error if it exceeds the cap of its subdenom, if any. Shared by
+// claim, claim_status and the mint reply so that they cannot disagree.
+pub(crate) fn validate_denom_cap(
+    storage: &dyn Storage,
+    config: &Config,
+    denom: &str,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let minted = MINTED_AMOUNTS
+        .may_load(storage, denom)?
+        .unwrap_or_default()
+        .checked_add(amount)
+        .map_err(StdError::from)?;
+
+    let cap = config
+        .denom_caps
+        .iter()
+        .find(|cap| format!("factory/{}/{}", config.owner, cap.denom) == denom);
+    if let Some(cap) = cap {
+        if minted > cap.amount {
+            return Err(ContractError::DenomCapExceeded {
+                denom: denom.to_string(),
+                cap: cap.amount,
+                minted,
+            });
+        }
+    }
+    Ok(minted)
+}
+
+// claim_leaf returns the leaf data of the claim.
+// It is encoded by merkle::entry::ClaimEntry as is the CLI's, so that they match.
+// The denom, if any, is appended to the amount as in a coin string, e.g. "100uosmo".
+pub(crate) fn claim_leaf(
+    claimer_addr: &str,
+    amount: Uint128,
+    denom: &Option<String>,
+    vesting: &Option<Vesting>,
+) -> Result<String, ContractError> {
+    let invalid_entry = |reason| ContractError::InvalidEntry { reason };
+    let entry = ClaimEntry {
+        address: Address::new(claimer_addr).map_err(invalid_entry)?,
+        amount: Amount::new(amount.u128(), denom.as_deref().unwrap_or_default())
+            .map_err(invalid_entry)?,
+        vesting: vesting.as_ref().map(|v| (v.cliff, v.duration)),
+    };
+    Ok(entry.to_string())
+}
+
+// claim_entry returns the amount and the leaf data of the entry of the claimer.
+// The leaf of an address-only drop is the address and the amount is the one set
+// by the owner, so the given amount is ignored and need not be given. As neither
+// the denom nor the vesting is bound into its leaf, they cannot be given.
+pub(crate) fn claim_entry(
+    deps: Deps,
+    config: &Config,
+    claimer_addr: &str,
+    amount: Option<Uint128>,
+    denom: &Option<String>,
+    vesting: &Option<Vesting>,
+) -> Result<(Uint128, String), ContractError> {
+    if !config.address_only {
+        let amount = amount.ok_or(ContractError::MissingAmount {})?;
+        return Ok((amount, claim_leaf(claimer_addr, amount, denom, vesting)?));
+    }
+
+    if denom.is_some() || vesting.is_some() {
+        return Err(ContractError::AddressOnlyEntry {});
+    }
+
+    let amount = AMOUNTS
+        .may_load(deps.storage, &Addr::unchecked(claimer_addr))?
+        .ok_or_else(|| ContractError::NoAmount {
+            address: claimer_addr.to_string(),
+        })?;
+    Ok((amount, leaf::encode_address_only(claimer_addr)))
+}
+
+// claim_fee_msg returns the message forwarding the claim fee to the owner
+// if the drop has one. All funds of the fee denom sent with the claim are
+// forwarded, so paying more than the fee is accepted.
+fn claim_fee_msg(config: &Config, funds: &[Coin]) -> Result<Option<BankMsg>, ContractError> {
+    let fee = match &config.claim_fee {
+        Some(fee) => fee,
+        None => return Ok(None),
+    };
+
+    let received = funds
+        .iter()
+        .find(|coin| coin.denom == fee.denom)
+        .cloned()
+        .unwrap_or_else(|| Coin::new(0, fee.denom.clone()));
+    if received.amount < fee.amount {
+        return Err(ContractError::InsufficientClaimFee {
+            expected: fee.clone(),
+            received,
+        });
+    }
+
+    // a zero fee has nothing to forward.
+    if received.amount.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(BankMsg::Send {
+        to_address: config.owner.to_string(),
+        amount: vec![received],
+    }))
+}
+
+// validate_claim_window returns an error if the block time
+// is before the claim start or at or after the claim deadline.
+fn validate_claim_window(config: &Config, env: &Env) -> Result<(), ContractError> {
+    let now = env.block.time;
+    if let Some(start) = config.claim_start {
+        if now < start {
+            return Err(ContractError::ClaimNotStarted {});
+        }
+    }
+    if let Some(deadline) = config.claim_deadline {
+        if now >= deadline {
+            return Err(ContractError::ClaimExpired {});
+        }
+    }
+    Ok(())
+}
+
+// parse_proof parses the proof string: the json of a bare proof or of a VersionedProof,
+// or the hex of the compact bytes of a proof. The version of a versioned proof is
+// checked by validate_proof_version as is the version given with the claim, and its
+// leaf encoding must be the leaf encoding of the drop.
+fn parse_proof(config: &Config, proof_str: &str) -> Result<Proof, ContractError> {
+    // the proof of the single entry of a drop is empty.
+    if proof_str.is_empty() {
+        return Ok(Proof::default());
+    }
+    if proof_str.trim_start().starts_with('[') {
+        return serde_json_wasm::from_str(proof_str)
+            .map_err(|e| StdError::parse_err("Proof", e).into());
+    }
+    if !proof_str.trim_start().starts_with('{') {
+        return parse_compact_proof(config, proof_str);
+    }
+
+    let versioned: VersionedProof = serde_json_wasm::from_str(proof_str)
+        .map_err(|e| StdError::parse_err("VersionedProof", e))?;
+    validate_proof_version(config, Some(versioned.version.into()))?;
+    if versioned.leaf_encoding != config.leaf_encoding {
+        return Err(ContractError::LeafEncodingMismatch {
+            leaf_encoding: versioned.leaf_encoding,
+            expected: config.leaf_encoding,
+        });
+    }
+    Ok(versioned.proof)
+}
+
+// validate_proof_version rejects a proof of another version than the proof version
+// of the drop, e.g. of another leaf encoding, instead of failing to verify it.
+// Proofs of no version predate the versions and are accepted as is.
+fn validate_proof_version(config: &Config, version: Option<u32>) -> Result<(), ContractError> {
+    match version {
+        Some(version) if version != config.proof_version => {
+            Err(ContractError::ProofVersionMismatch {
+                version,
+                expected: config.proof_version,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+// parse_compact_proof parses the hex encoding of the compact bytes of the proof,
+// see merkle::proof::Proof::to_compact_bytes, as printed by the CLI's convert-proof.
+// It is a fraction of the size of the json encoding, so claims cost less to submit.
+// The hashes of the proof have the length of the root.
+fn parse_compact_proof(config: &Config, proof_str: &str) -> Result<Proof, ContractError> {
+    let root =
+        merkle::parse_root(&config.merkle_root).map_err(|e| ContractError::FailedToDecodeRoot {
+            root: e.to_string(),
+        })?;
+    Proof::from_hex_string_with_length(proof_str.trim(), root.get_length())
+        .map_err(|e| StdError::parse_err("Proof", e).into())
+}
+
+// validate_proof_depth rejects proofs longer than the maximum depth of the drop
+// before any hashing, so that a claim cannot spend unbounded gas on verification.
+fn validate_proof_depth(config: &Config, proof: &Proof) -> Result<(), ContractError> {
+    if let Some(max_depth) = config.max_proof_depth {
+        let length = proof.get_num_entries();
+        if length > u128::from(max_depth) {
+            return Err(ContractError::ProofTooLong { length, max_depth });
+        }
+    }
+    Ok(())
+}
+
+// validate_self_claim rejects claims sent by anyone but the claimer, e.g. by a
+// relayer, if the drop requires every claim to prove control of the claimer's key.
+fn validate_self_claim(
+    config: &Config,
+    info: &MessageInfo,
+    claimer_addr: &str,
+) -> Result<(), ContractError> {
+    if config.require_self_claim && info.sender != claimer_addr {
+        return Err(ContractError::SelfClaimRequired {
+            sender: info.sender.to_string(),
+            claimer_addr: claimer_addr.to_string(),
+        });
+    }
+    Ok(())
 }
 
-pub fn verify_proof(
+// leaf_data returns the data of the leaf of the claim that the proof is verified
+// against: the claim with its amount in the leaf encoding of the drop, salted.
+// The claim itself, in the decimal encoding, remains the key of the claim state.
+pub(crate) fn leaf_data(config: &Config, claim: &str) -> Result<Vec<u8>, ContractError> {
+    let encoded = leaf::with_encoding(config.leaf_encoding, claim.as_bytes())
+        .map_err(|reason| ContractError::InvalidEntry { reason })?;
+    Ok(salted_leaf(&config.salt, encoded))
+}
+
+// salted_leaf prefixes the leaf data with the salt of the drop, if any.
+// It must match the salting used by the CLI to build the tree.
+pub(crate) fn salted_leaf<T: AsRef<[u8]>>(salt: &Option<Binary>, data: T) -> Vec<u8> {
+    match salt {
+        Some(salt) => leaf::with_salt(salt.as_slice(), data.as_ref()),
+        None => data.as_ref().to_vec(),
+    }
+}
+
+// claimable_entry_amount returns the amount to claim from the entry of the given
+// leaf data and amount if it is neither revoked nor fully claimed and part of it is
+// vested at the block time. The proof is not verified. Shared by claim and the
+// Remaining query.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn claimable_entry_amount(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    claim: &str,
+    amount: Uint128,
+    vesting: &Option<Vesting>,
+    requested: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    if REVOKED_CLAIMS.has(storage, claim) {
+        return Err(ContractError::Revoked {
+            claim: claim.to_string(),
+        });
+    }
+
+    let claimed = CLAIMED_AMOUNTS
+        .may_load(storage, claim)?
+        .unwrap_or_default();
+    let vested = vested_amount(config, amount, vesting, env.block.time);
+    match claimable_amount(claim, vested, claimed, requested) {
+        // the vested amount is claimed but the entry is not.
+        Err(ContractError::AlreadyClaimed { .. }) if vested < amount => {
+            Err(ContractError::NotVested {
+                claim: claim.to_string(),
+            })
+        }
+        res => res,
+    }
+}
+
+// claimable_amount returns the amount to claim from an entry of the given
+// amount of which claimed is already claimed. Claims the remaining amount
+// if nothing is requested.
+fn claimable_amount(
+    claim: &str,
+    amount: Uint128,
+    claimed: Uint128,
+    requested: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    let remaining = amount.saturating_sub(claimed);
+    if remaining.is_zero() {
+        return Err(ContractError::AlreadyClaimed {
+            claim: claim.to_string(),
+        });
+    }
+
+    let requested = requested.unwrap_or(remaining);
+    if requested.is_zero() || requested > remaining {
+        return Err(ContractError::InvalidRequestedAmount {
+            requested,
+            remaining,
+        });
+    }
+
+    Ok(requested)
+}
+
+// vested_amount returns the part of the amount of an entry with the given vesting
+// schedule that is vested at now. Entries without a schedule are fully vested.
+pub(crate) fn vested_amount(
+    config: &Config,
+    amount: Uint128,
+    vesting: &Option<Vesting>,
+    now: Timestamp,
+) -> Uint128 {
+    let vesting = match vesting {
+        Some(vesting) => vesting,
+        None => return amount,
+    };
+    let elapsed = now.seconds().saturating_sub(config.vesting_start.seconds());
+    if elapsed < vesting.cliff {
+        Uint128::zero()
+    } else if elapsed >= vesting.duration {
+        amount
+    } else {
+        amount.multiply_ratio(elapsed, vesting.duration)
+    }
+}
+
+fn validate_note(note: &str) -> Result<(), ContractError> {
+    if note.len() > MAX_NOTE_LENGTH {
+        return Err(ContractError::NoteTooLong {
+            length: note.len(),
+            max_length: MAX_NOTE_LENGTH,
+        });
+    }
+    Ok(())
+}
+
+// validate_ibc_destination checks that the claimer is the sender, so that the
+// tokens of the claimer cannot be redirected, and that the destination is a
+// transfer channel, a receiver and a timeout within MAX_IBC_TIMEOUT_SECONDS.
+pub(crate) fn validate_ibc_destination(
+    info: &MessageInfo,
+    claimer_addr: &str,
+    ibc_destination: &IbcDestination,
+) -> Result<(), ContractError> {
+    if info.sender != claimer_addr {
+        return Err(ContractError::IbcDestinationNotClaimer {});
+    }
+
+    let is_valid_channel = ibc_destination
+        .channel
+        .strip_prefix("channel-")
+        .map_or(false, |id| id.parse::<u64>().is_ok());
+    if !is_valid_channel {
+        return Err(ContractError::InvalidIbcChannel {
+            channel: ibc_destination.channel.clone(),
+        });
+    }
+
+    if ibc_destination.receiver.is_empty() {
+        return Err(ContractError::InvalidAddress {
+            address: ibc_destination.receiver.clone(),
+            reason: String::from("empty IBC receiver"),
+        });
+    }
+
+    let seconds = ibc_timeout_seconds(ibc_destination);
+    if seconds == 0 || seconds > MAX_IBC_TIMEOUT_SECONDS {
+        return Err(ContractError::InvalidIbcTimeout {
+            seconds,
+            max_seconds: MAX_IBC_TIMEOUT_SECONDS,
+        });
+    }
+    Ok(())
+}
+
+// ibc_timeout_seconds returns the timeout of the transfer to the destination.
+pub(crate) fn ibc_timeout_seconds(ibc_destination: &IbcDestination) -> u64 {
+    ibc_destination
+        .timeout_seconds
+        .unwrap_or(DEFAULT_IBC_TIMEOUT_SECONDS)
+}
+
+pub fn verify_typed_proof<T: AsRef<[u8]>>(
     merkle_root: &String,
-    proof_str: &str,
-    to_verify: String,
+    proof: &Proof,
+    to_verify: T,
 ) -> Result<(), ContractError> {
-    let proof: Proof = serde_json_wasm::from_str(proof_str).unwrap();
-    let root = match base64::decode(merkle_root) {
-        Ok(f) => f,
+    let root_hash = match merkle::parse_root(merkle_root) {
+        Ok(root) => root,
         Err(e) => {
             return Err(ContractError::FailedToDecodeRoot {
                 root: e.to_string(),
@@ -104,8 +672,6 @@ pub fn verify_proof(
         }
     };
 
-    let root_hash = Hash::from(root);
-
     if !proof.verify(&to_verify, &root_hash) {
         return Err(ContractError::FailedVerifyProof {});
     }
@@ -116,18 +682,34 @@ pub fn verify_proof(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{mock_dependencies_with_denom_admin, test_config};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Timestamp};
+    use merkle::proof::VERSION;
 
     // TEST_ROOT test merkel root that was generated from "testdata/uosmo_only.csv" using merkle-drop-cli
-    const TEST_ROOT: &str = "Nz54SQtyBVHwsmEqNI//mxFgiq8MRD7sS92IGkhgMvo=";
-    const TEST_ROOT2_ADDR_AMOUNT: &str = "1V0YcwzXWtB+iuOTob6juiNliUmB278xZIKMnzwjqOU=";
+    const TEST_ROOT: &str = "P7L8+jjWZ9MlHq5j2C9FQofBovlnt6o+VYRyAdXjBHY=";
+    const TEST_ROOT2_ADDR_AMOUNT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+
+    const VALID_PROOF_STR: &str = "[{\"is_left_sibling\":false,\"hash\":[48,76,72,116,56,56,43,82,48,65,117,101,83,98,70,117,106,119,86,71,111,119,87,115,57,102,68,83,75,55,70,49,90,102,74,117,79,107,54,79,103,113,115,61]},{\"is_left_sibling\":true,\"hash\":[81,109,66,110,50,43,57,80,110,101,89,53,103,69,77,56,88,116,88,86,109,86,120,114,89,48,109,81,100,47,65,50,106,50,115,111,50,78,90,110,113,90,52,61]},{\"is_left_sibling\":false,\"hash\":[101,69,84,108,121,72,75,98,89,103,107,53,111,115,104,79,107,77,107,89,115,87,117,67,78,74,110,99,103,108,43,49,122,115,104,114,66,100,66,70,50,112,103,61]},{\"is_left_sibling\":false,\"hash\":[104,54,115,98,71,72,108,88,53,69,89,113,79,55,55,117,89,105,57,122,78,82,71,121,115,109,101,117,71,68,71,88,51,88,72,90,119,68,118,76,47,65,65,61]},{\"is_left_sibling\":true,\"hash\":[107,120,85,69,100,97,90,54,69,118,71,108,102,51,74,104,98,105,72,78,100,68,49,76,55,51,116,47,117,90,80,107,106,113,114,73,116,79,90,98,65,90,89,61]}]";
+    const VALID_PROOF_STR2_ADDR_AMOUNT: &str = "[{\"is_left_sibling\":false,\"hash\":[107,81,47,117,57,77,98,104,49,108,102,109,65,101,79,88,99,89,110,86,88,84,72,74,105,107,116,68,77,55,66,120,85,100,120,108,117,53,78,120,85,114,56,61]},{\"is_left_sibling\":true,\"hash\":[101,48,117,68,51,77,104,98,119,108,120,105,110,106,54,53,71,48,101,100,115,82,106,89,54,68,89,76,70,99,120,119,87,65,55,97,78,101,101,57,104,69,119,61]},{\"is_left_sibling\":true,\"hash\":[77,100,48,79,78,102,99,83,66,83,76,54,109,66,113,87,83,67,84,111,105,77,86,55,84,122,67,87,72,100,99,105,111,79,74,66,88,51,99,71,115,83,103,61]},{\"is_left_sibling\":false,\"hash\":[53,119,49,47,69,51,84,90,88,49,81,79,49,112,74,122,89,122,75,68,49,111,65,121,48,70,70,75,120,52,56,102,111,52,53,83,74,99,116,70,73,56,115,61]}]";
 
-    const VALID_PROOF_STR: &str = "[{\"is_left_sibling\":true,\"hash\":[77,122,52,81,83,57,77,56,52,118,78,84,89,115,86,76,120,80,121,50,109,89,49,111,107,85,111,68,111,108,97,118,105,81,119,71,114,54,111,49,51,107,99,61]},{\"is_left_sibling\":true,\"hash\":[113,53,102,108,80,98,70,114,73,121,100,108,105,97,121,52,85,73,57,100,107,111,106,71,82,116,49,57,90,71,121,105,51,111,55,104,103,112,87,103,73,117,48,61]},{\"is_left_sibling\":true,\"hash\":[114,87,52,69,82,120,75,113,110,53,102,77,69,65,114,57,56,118,90,80,116,54,119,108,67,71,49,69,80,121,99,57,51,54,108,48,112,100,86,97,100,120,48,61]},{\"is_left_sibling\":false,\"hash\":[66,86,43,54,113,43,104,43,100,99,115,116,50,83,66,52,122,68,111,100,118,50,98,90,84,112,108,105,105,65,104,87,50,82,75,121,112,67,72,51,81,66,85,61]},{\"is_left_sibling\":false,\"hash\":[86,100,75,79,107,112,115,120,78,73,103,43,50,111,102,47,57,76,84,111,122,51,107,84,102,53,112,90,113,81,108,108,112,86,84,86,82,55,108,77,103,83,119,61]}]";
-    const VALID_PROOF_STR2_ADDR_AMOUNT: &str = "[{\"is_left_sibling\":true,\"hash\":[89,79,106,114,49,69,77,102,68,119,114,48,69,84,73,103,82,71,97,108,48,79,108,53,105,56,82,103,111,57,85,51,76,70,82,90,115,66,97,78,89,51,73,61]},{\"is_left_sibling\":false,\"hash\":[80,54,110,55,43,55,72,72,111,52,109,104,79,104,102,105,108,83,43,118,87,54,88,85,88,113,48,115,105,99,83,116,116,52,112,54,119,114,68,48,113,47,73,61]},{\"is_left_sibling\":true,\"hash\":[79,79,110,66,86,100,72,56,121,84,70,57,115,78,65,56,80,85,81,97,111,71,89,119,81,89,87,83,109,71,116,89,56,79,118,85,118,98,73,83,122,74,77,61]},{\"is_left_sibling\":false,\"hash\":[102,65,68,121,57,69,49,118,56,70,78,78,81,53,109,47,50,120,78,55,103,110,119,89,78,82,104,80,83,53,69,105,79,53,115,79,77,43,118,106,50,98,56,61]}]";
+    const INVALID_PROOF_STR: &str = "[{\"is_left_sibling\":false,\"hash\":[49,76,72,116,56,56,43,82,48,65,117,101,83,98,70,117,106,119,86,71,111,119,87,115,57,102,68,83,75,55,70,49,90,102,74,117,79,107,54,79,103,113,115,61]},{\"is_left_sibling\":true,\"hash\":[81,109,66,110,50,43,57,80,110,101,89,53,103,69,77,56,88,116,88,86,109,86,120,114,89,48,109,81,100,47,65,50,106,50,115,111,50,78,90,110,113,90,52,61]},{\"is_left_sibling\":false,\"hash\":[101,69,84,108,121,72,75,98,89,103,107,53,111,115,104,79,107,77,107,89,115,87,117,67,78,74,110,99,103,108,43,49,122,115,104,114,66,100,66,70,50,112,103,61]},{\"is_left_sibling\":false,\"hash\":[104,54,115,98,71,72,108,88,53,69,89,113,79,55,55,117,89,105,57,122,78,82,71,121,115,109,101,117,71,68,71,88,51,88,72,90,119,68,118,76,47,65,65,61]},{\"is_left_sibling\":true,\"hash\":[107,120,85,69,100,97,90,54,69,118,71,108,102,51,74,104,98,105,72,78,100,68,49,76,55,51,116,47,117,90,80,107,106,113,114,73,116,79,90,98,65,90,89,61]}]";
 
-    const INVALID_PROOF_STR: &str = "[{\"is_left_sibling\":true,\"hash\":[78,122,52,81,83,57,77,56,52,118,78,84,89,115,86,76,120,80,121,50,109,89,49,111,107,85,111,68,111,108,97,118,105,81,119,71,114,54,111,49,51,107,99,61]},{\"is_left_sibling\":true,\"hash\":[113,53,102,108,80,98,70,114,73,121,100,108,105,97,121,52,85,73,57,100,107,111,106,71,82,116,49,57,90,71,121,105,51,111,55,104,103,112,87,103,73,117,48,61]},{\"is_left_sibling\":true,\"hash\":[114,87,52,69,82,120,75,113,110,53,102,77,69,65,114,57,56,118,90,80,116,54,119,108,67,71,49,69,80,121,99,57,51,54,108,48,112,100,86,97,100,120,48,61]},{\"is_left_sibling\":false,\"hash\":[66,86,43,54,113,43,104,43,100,99,115,116,50,83,66,52,122,68,111,100,118,50,98,90,84,112,108,105,105,65,104,87,50,82,75,121,112,67,72,51,81,66,85,61]},{\"is_left_sibling\":false,\"hash\":[86,100,75,79,107,112,115,120,78,73,103,43,50,111,102,47,57,76,84,111,122,51,107,84,102,53,112,90,113,81,108,108,112,86,84,86,82,55,108,77,103,83,119,61]}]";
+    const TO_VERIFY_VALID: &str = "osmo1003cay8wpc456n3adq785xn0r0pqvmfxlakpxh|9442uosmo";
+    const TO_VERIFY_VALID2_ADDR_AMOUNT: &str =
+        "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901";
+    const TO_VERIFY_ADDR: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
 
-    const TO_VERIFY_VALID: &str = "osmo1003cay8wpc456n3adq785xn0r0pqvmfxlakpxh9442uosmo";
-    const TO_VERIFY_VALID2_ADDR_AMOUNT: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj1421901";
+    fn verify_proof(
+        merkle_root: &String,
+        proof_str: &str,
+        to_verify: String,
+    ) -> Result<(), ContractError> {
+        let proof: Proof = serde_json_wasm::from_str(proof_str).unwrap();
+
+        verify_typed_proof(merkle_root, &proof, to_verify)
+    }
 
     #[test]
     fn verify_proof_success() {
@@ -149,6 +731,698 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn verify_typed_proof_vesting() {
+        let vesting = Some(Vesting {
+            cliff: 60,
+            duration: 3600,
+        });
+        let amount = Uint128::new(1000);
+
+        let vesting_leaf = claim_leaf(TO_VERIFY_ADDR, amount, &None, &vesting).unwrap();
+        let items = vec![vesting_leaf.clone(), String::from(TO_VERIFY_VALID)];
+
+        let mt = merkle::Tree::new(&items);
+        let root = mt.get_root().unwrap().to_string();
+        let proof = mt.find_proof(&vesting_leaf).unwrap();
+
+        verify_typed_proof(&root, &proof, vesting_leaf).unwrap();
+
+        let tampered_vesting = Some(Vesting {
+            cliff: 0,
+            duration: 3600,
+        });
+        verify_typed_proof(
+            &root,
+            &proof,
+            claim_leaf(TO_VERIFY_ADDR, amount, &None, &tampered_vesting).unwrap(),
+        )
+        .unwrap_err();
+
+        verify_typed_proof(
+            &root,
+            &proof,
+            claim_leaf(TO_VERIFY_ADDR, amount, &None, &None).unwrap(),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn verify_typed_proof_leading_zeros_amount() {
+        // the CLI encodes the amount as given in the csv.
+        let csv_leaf = leaf::encode(TO_VERIFY_ADDR, "0100", None);
+        let items = vec![csv_leaf.clone(), String::from(TO_VERIFY_VALID)];
+
+        let mt = merkle::Tree::new(&items);
+        let root = mt.get_root().unwrap().to_string();
+        let proof = mt.find_proof(&csv_leaf).unwrap();
+
+        verify_typed_proof(
+            &root,
+            &proof,
+            claim_leaf(TO_VERIFY_ADDR, Uint128::new(100), &None, &None).unwrap(),
+        )
+        .unwrap();
+    }
+
+    // The CLI encodes the amount column of the data set, a cosmos-sdk coin string,
+    // while the contract encodes the claimed amount and denom. The leaves only
+    // match if the CLI's encoding of Coin::to_string is the contract's.
+    #[test]
+    fn claim_leaf_matches_coin_string() {
+        let denoms = [
+            "uosmo",
+            "mydenom",
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F92",
+            "gamm/pool/1",
+            "a:b.c_d-e",
+        ];
+        let amounts = [0, 1, 10, 1_000_000, u64::MAX as u128, u128::MAX];
+
+        for denom in denoms {
+            for amount in amounts {
+                let coin = Coin::new(amount, denom);
+                let cli_leaf = leaf::encode(TO_VERIFY_ADDR, &coin.to_string(), None);
+
+                let claim = claim_leaf(
+                    TO_VERIFY_ADDR,
+                    coin.amount,
+                    &Some(String::from(denom)),
+                    &None,
+                )
+                .unwrap();
+                assert_eq!(cli_leaf, claim, "{}", coin);
+            }
+        }
+    }
+
+    #[test]
+    fn claim_leaf_rejects_invalid_subdenom() {
+        // full ibc and factory denoms are longer than a subdenom.
+        for denom in [
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+            "factory/osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks/mydenom",
+            "1uosmo",
+        ] {
+            let err = claim_leaf(
+                TO_VERIFY_ADDR,
+                Uint128::new(100),
+                &Some(String::from(denom)),
+                &None,
+            )
+            .unwrap_err();
+            assert!(
+                matches!(err, ContractError::InvalidEntry { .. }),
+                "{}: {:?}",
+                denom,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn claim_leaf_matches_claim_entry() {
+        let vesting = Some(Vesting {
+            cliff: 60,
+            duration: 3600,
+        });
+        // the CLI parses the amount column of the data set.
+        let entry = ClaimEntry {
+            address: Address::new(TO_VERIFY_ADDR).unwrap(),
+            amount: Amount::parse("0100uosmo").unwrap(),
+            vesting: Some((60, 3600)),
+        };
+
+        let claim = claim_leaf(
+            TO_VERIFY_ADDR,
+            Uint128::new(100),
+            &Some(String::from("uosmo")),
+            &vesting,
+        )
+        .unwrap();
+        assert_eq!(entry.to_leaf_bytes(), claim.as_bytes());
+
+        let err = claim_leaf("osmo1 claimer", Uint128::new(100), &None, &None).unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidEntry { .. }),
+            "{:?}",
+            err
+        );
+        let err = claim_leaf(
+            TO_VERIFY_ADDR,
+            Uint128::new(100),
+            &Some(String::from("1uosmo")),
+            &None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidEntry { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn claim_leaf_address_digits_not_shifted_into_amount() {
+        // without a separator, both claims would be "osmo1a510", sharing a leaf
+        // and the key of their claimed amount.
+        let shorter = claim_leaf("osmo1a", Uint128::new(510), &None, &None).unwrap();
+        let longer = claim_leaf("osmo1a5", Uint128::new(10), &None, &None).unwrap();
+        assert_ne!(shorter, longer);
+
+        let mut deps = mock_dependencies();
+        CLAIMED_AMOUNTS
+            .save(deps.as_mut().storage, &shorter, &Uint128::new(510))
+            .unwrap();
+        assert_eq!(
+            None,
+            CLAIMED_AMOUNTS
+                .may_load(deps.as_ref().storage, &longer)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn claim_typed_denom_cannot_be_swapped() {
+        // the CLI encodes a coin amount as given in the csv.
+        let csv_leaf = leaf::encode(TO_VERIFY_ADDR, "100uosmo", None);
+        let items = vec![csv_leaf.clone(), String::from(TO_VERIFY_VALID)];
+
+        let mt = merkle::Tree::new(&items);
+        let root = mt.get_root().unwrap().to_string();
+        let proof = mt.find_proof(&csv_leaf).unwrap();
+
+        let uosmo = Some(String::from("uosmo"));
+        verify_typed_proof(
+            &root,
+            &proof,
+            claim_leaf(TO_VERIFY_ADDR, Uint128::new(100), &uosmo, &None).unwrap(),
+        )
+        .unwrap();
+
+        let mut deps = mock_dependencies();
+        let config = Config {
+            merkle_root: root,
+            ..test_config()
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        // the valid proof for uosmo cannot be replayed to mint uion.
+        let err = claim_typed(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            proof,
+            None,
+            Some(Uint128::new(100)),
+            Some(String::from("uion")),
+            String::from(TO_VERIFY_ADDR),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::FailedVerifyProof {}),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn claim_typed_denom_not_found() {
+        let mut deps = mock_dependencies_with_denom_admin(None);
+        let config = Config {
+            merkle_root: String::from(TEST_ROOT2_ADDR_AMOUNT),
+            ..test_config()
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        SUBDENOM
+            .save(deps.as_mut().storage, &String::from("mydenom"))
+            .unwrap();
+
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR2_ADDR_AMOUNT).unwrap();
+        let err = claim_typed(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(TO_VERIFY_ADDR, &[]),
+            proof,
+            None,
+            Some(Uint128::new(1_421_901)),
+            None,
+            String::from(TO_VERIFY_ADDR),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DenomNotFound { denom } => {
+                assert_eq!("factory/owner/mydenom", denom)
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn claim_typed_relayer_rejected_if_self_claim_required() {
+        let mut deps = mock_dependencies();
+        let config = Config {
+            merkle_root: String::from(TEST_ROOT),
+            require_self_claim: true,
+            ..test_config()
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+        let err = claim_typed(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            proof,
+            None,
+            Some(Uint128::new(100)),
+            None,
+            String::from(TO_VERIFY_ADDR),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::SelfClaimRequired { .. }),
+            "{:?}",
+            err
+        );
+
+        // the claimer itself can claim, and anyone can if self-claims are not required.
+        validate_self_claim(&config, &mock_info(TO_VERIFY_ADDR, &[]), TO_VERIFY_ADDR).unwrap();
+        let config = Config {
+            require_self_claim: false,
+            ..config
+        };
+        validate_self_claim(&config, &mock_info("relayer", &[]), TO_VERIFY_ADDR).unwrap();
+    }
+
+    #[test]
+    fn claim_fee() {
+        let config = Config {
+            claim_fee: Some(Coin::new(10, "uosmo")),
+            ..claim_window_config(0, 0)
+        };
+
+        // correct fee
+        let msg = claim_fee_msg(&config, &[Coin::new(10, "uosmo")]).unwrap();
+        assert_eq!(
+            Some(BankMsg::Send {
+                to_address: String::from("owner"),
+                amount: vec![Coin::new(10, "uosmo")],
+            }),
+            msg
+        );
+
+        // missing fee
+        for funds in [
+            vec![],
+            vec![Coin::new(10, "uion")],
+            vec![Coin::new(9, "uosmo")],
+        ] {
+            let err = claim_fee_msg(&config, &funds).unwrap_err();
+            assert!(
+                matches!(err, ContractError::InsufficientClaimFee { .. }),
+                "{:?}",
+                err
+            );
+        }
+
+        // overpaying is accepted and forwarded
+        let msg = claim_fee_msg(&config, &[Coin::new(15, "uosmo"), Coin::new(1, "uion")]).unwrap();
+        assert_eq!(
+            Some(BankMsg::Send {
+                to_address: String::from("owner"),
+                amount: vec![Coin::new(15, "uosmo")],
+            }),
+            msg
+        );
+
+        // no fee
+        let config = claim_window_config(0, 0);
+        assert_eq!(
+            None,
+            claim_fee_msg(&config, &[Coin::new(10, "uosmo")]).unwrap()
+        );
+    }
+
+    #[test]
+    fn claim_fee_unrelated_funds() {
+        // funds attached to the claim are unrelated to the drop denom,
+        // which is minted by the contract rather than paid by the claimer.
+        let unrelated_funds = coins(1000, "earth");
+
+        // without a fee, the funds are ignored and nothing is forwarded.
+        let config = claim_window_config(0, 0);
+        assert_eq!(None, claim_fee_msg(&config, &unrelated_funds).unwrap());
+
+        // with a fee, unrelated funds do not pay it.
+        let config = Config {
+            claim_fee: Some(Coin::new(10, "uosmo")),
+            ..claim_window_config(0, 0)
+        };
+        let err = claim_fee_msg(&config, &unrelated_funds).unwrap_err();
+        assert!(
+            matches!(err, ContractError::InsufficientClaimFee { .. }),
+            "{:?}",
+            err
+        );
+
+        // and are left untouched when sent along with the fee.
+        let funds = [Coin::new(10, "uosmo"), Coin::new(1000, "earth")];
+        let msg = claim_fee_msg(&config, &funds).unwrap();
+        assert_eq!(
+            Some(BankMsg::Send {
+                to_address: String::from("owner"),
+                amount: vec![Coin::new(10, "uosmo")],
+            }),
+            msg
+        );
+    }
+
+    #[test]
+    fn claim_entry_address_only() {
+        let mut deps = mock_dependencies();
+        let config = Config {
+            address_only: true,
+            ..claim_window_config(0, 0)
+        };
+        AMOUNTS
+            .save(
+                deps.as_mut().storage,
+                &Addr::unchecked(TO_VERIFY_ADDR),
+                &Uint128::new(100),
+            )
+            .unwrap();
+
+        // the amount set by the owner is claimed, whatever the given amount.
+        let (amount, claim) = claim_entry(
+            deps.as_ref(),
+            &config,
+            TO_VERIFY_ADDR,
+            Some(Uint128::new(1_000_000)),
+            &None,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(Uint128::new(100), amount);
+        assert_eq!(TO_VERIFY_ADDR, claim);
+
+        // the amount need not be given.
+        let (amount, claim) =
+            claim_entry(deps.as_ref(), &config, TO_VERIFY_ADDR, None, &None, &None).unwrap();
+        assert_eq!(Uint128::new(100), amount);
+        assert_eq!(TO_VERIFY_ADDR, claim);
+
+        let err = claim_entry(
+            deps.as_ref(),
+            &config,
+            "osmo1unlisted",
+            Some(Uint128::new(100)),
+            &None,
+            &None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoAmount { .. }), "{:?}", err);
+
+        let err = claim_entry(
+            deps.as_ref(),
+            &config,
+            TO_VERIFY_ADDR,
+            Some(Uint128::new(100)),
+            &Some(String::from("uion")),
+            &None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::AddressOnlyEntry {}),
+            "{:?}",
+            err
+        );
+
+        // the leaf of any other drop binds the given amount.
+        let (amount, claim) = claim_entry(
+            deps.as_ref(),
+            &claim_window_config(0, 0),
+            TO_VERIFY_ADDR,
+            Some(Uint128::new(1_000_000)),
+            &None,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(Uint128::new(1_000_000), amount);
+        assert_eq!(format!("{}|1000000", TO_VERIFY_ADDR), claim);
+
+        // and requires it.
+        let err = claim_entry(
+            deps.as_ref(),
+            &claim_window_config(0, 0),
+            TO_VERIFY_ADDR,
+            None,
+            &None,
+            &None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::MissingAmount {}), "{:?}", err);
+    }
+
+    fn claim_window_config(start: u64, deadline: u64) -> Config {
+        Config {
+            merkle_root: String::from(TEST_ROOT),
+            claim_start: Some(Timestamp::from_seconds(start)),
+            claim_deadline: Some(Timestamp::from_seconds(deadline)),
+            ..test_config()
+        }
+    }
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    #[test]
+    fn claim_window_before_start() {
+        let config = claim_window_config(100, 200);
+
+        let err = validate_claim_window(&config, &env_at(99)).unwrap_err();
+        assert!(
+            matches!(err, ContractError::ClaimNotStarted {}),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn claim_window_within() {
+        let config = claim_window_config(100, 200);
+
+        validate_claim_window(&config, &env_at(100)).unwrap();
+        validate_claim_window(&config, &env_at(199)).unwrap();
+
+        // no window
+        let config = Config {
+            claim_start: None,
+            claim_deadline: None,
+            claim_fee: None,
+            ..config
+        };
+        validate_claim_window(&config, &env_at(0)).unwrap();
+    }
+
+    #[test]
+    fn claim_window_after_deadline() {
+        let config = claim_window_config(100, 200);
+
+        let err = validate_claim_window(&config, &env_at(200)).unwrap_err();
+        assert!(matches!(err, ContractError::ClaimExpired {}), "{:?}", err);
+    }
+
+    #[test]
+    fn parse_proof_version() {
+        let config = claim_window_config(100, 200);
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+
+        // bare proofs are accepted as is.
+        assert_eq!(proof, parse_proof(&config, VALID_PROOF_STR).unwrap());
+
+        let versioned = format!("{{\"version\":{},\"proof\":{}}}", VERSION, VALID_PROOF_STR);
+        assert_eq!(proof, parse_proof(&config, &versioned).unwrap());
+
+        // malformed json is an error rather than a panic.
+        let err = parse_proof(&config, &VALID_PROOF_STR[..10]).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn parse_proof_compact() {
+        let config = claim_window_config(100, 200);
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+
+        let compact = proof.to_hex_string();
+        assert!(compact.len() * 2 < VALID_PROOF_STR.len());
+        assert_eq!(proof, parse_proof(&config, &compact).unwrap());
+
+        // the proof still verifies after the round trip.
+        verify_typed_proof(
+            &config.merkle_root,
+            &parse_proof(&config, &compact).unwrap(),
+            TO_VERIFY_VALID,
+        )
+        .unwrap();
+
+        let err = parse_proof(&config, &compact[2..]).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn parse_proof_version_mismatch() {
+        let config = claim_window_config(100, 200);
+
+        let versioned = format!(
+            "{{\"version\":{},\"proof\":{}}}",
+            VERSION + 1,
+            VALID_PROOF_STR
+        );
+        let err = parse_proof(&config, &versioned).unwrap_err();
+        let mismatch = u32::from(VERSION) + 1;
+        assert!(
+            matches!(err, ContractError::ProofVersionMismatch { version, expected }
+                if version == mismatch && expected == u32::from(VERSION)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn parse_proof_leaf_encoding_mismatch() {
+        let config = claim_window_config(100, 200);
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+
+        let versioned = format!(
+            "{{\"version\":{},\"leaf_encoding\":\"decimal_string\",\"proof\":{}}}",
+            VERSION, VALID_PROOF_STR
+        );
+        assert_eq!(proof, parse_proof(&config, &versioned).unwrap());
+
+        let versioned = format!(
+            "{{\"version\":{},\"leaf_encoding\":\"u128_bytes\",\"proof\":{}}}",
+            VERSION, VALID_PROOF_STR
+        );
+        let err = parse_proof(&config, &versioned).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContractError::LeafEncodingMismatch {
+                    leaf_encoding: leaf::LeafEncoding::U128Bytes,
+                    expected: leaf::LeafEncoding::DecimalString,
+                }
+            ),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn claim_typed_proof_version_mismatch() {
+        let mut deps = mock_dependencies();
+        let config = Config {
+            merkle_root: String::from(TEST_ROOT),
+            ..test_config()
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        // the version given with a typed or a compact proof is checked too.
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+        let err = claim_typed(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(TO_VERIFY_ADDR, &[]),
+            proof.clone(),
+            Some(u32::from(VERSION) + 1),
+            Some(Uint128::new(100)),
+            None,
+            String::from(TO_VERIFY_ADDR),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::ProofVersionMismatch { .. }),
+            "{:?}",
+            err
+        );
+
+        let err = claim(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(TO_VERIFY_ADDR, &[]),
+            proof.to_hex_string(),
+            Some(u32::from(VERSION) + 1),
+            Some(Uint128::new(100)),
+            None,
+            String::from(TO_VERIFY_ADDR),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::ProofVersionMismatch { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn validate_proof_depth_at_cap() {
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+        let length = proof.get_num_entries() as u32;
+        let config = Config {
+            max_proof_depth: Some(length),
+            ..claim_window_config(100, 200)
+        };
+
+        validate_proof_depth(&config, &proof).unwrap();
+
+        // no cap
+        let config = Config {
+            max_proof_depth: None,
+            ..config
+        };
+        validate_proof_depth(&config, &proof).unwrap();
+    }
+
+    #[test]
+    fn validate_proof_depth_exceeded() {
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+        let length = proof.get_num_entries() as u32;
+        let config = Config {
+            max_proof_depth: Some(length - 1),
+            ..claim_window_config(100, 200)
+        };
+
+        let err = validate_proof_depth(&config, &proof).unwrap_err();
+        assert!(
+            matches!(err, ContractError::ProofTooLong { .. }),
+            "{:?}",
+            err
+        );
+    }
+
     #[test]
     fn verify_proof_invalid_root_error() {
         verify_proof(
@@ -159,6 +1433,255 @@ mod tests {
         .unwrap_err();
     }
 
+    #[test]
+    fn claimable_amount_partial_claims() {
+        let amount = Uint128::new(100);
+
+        // nothing requested claims the full amount.
+        assert_eq!(
+            amount,
+            claimable_amount(TO_VERIFY_VALID, amount, Uint128::zero(), None).unwrap()
+        );
+
+        // partial claims summing to the amount.
+        let claimed = claimable_amount(
+            TO_VERIFY_VALID,
+            amount,
+            Uint128::zero(),
+            Some(Uint128::new(30)),
+        )
+        .unwrap();
+        assert_eq!(Uint128::new(30), claimed);
+
+        let remaining = claimable_amount(TO_VERIFY_VALID, amount, claimed, None).unwrap();
+        assert_eq!(Uint128::new(70), remaining);
+
+        // fully claimed.
+        let err = claimable_amount(TO_VERIFY_VALID, amount, amount, None).unwrap_err();
+        assert!(
+            matches!(err, ContractError::AlreadyClaimed { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn claimable_amount_over_claim_error() {
+        let amount = Uint128::new(100);
+
+        let err = claimable_amount(
+            TO_VERIFY_VALID,
+            amount,
+            Uint128::new(30),
+            Some(Uint128::new(71)),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidRequestedAmount { .. }),
+            "{:?}",
+            err
+        );
+
+        let err = claimable_amount(
+            TO_VERIFY_VALID,
+            amount,
+            Uint128::zero(),
+            Some(Uint128::zero()),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidRequestedAmount { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn vested_amount_schedule() {
+        let config = Config {
+            vesting_start: Timestamp::from_seconds(1000),
+            ..test_config()
+        };
+        let amount = Uint128::new(1000);
+        let vesting = Some(Vesting {
+            cliff: 60,
+            duration: 400,
+        });
+        let at =
+            |seconds| vested_amount(&config, amount, &vesting, Timestamp::from_seconds(seconds));
+
+        assert_eq!(Uint128::zero(), at(0));
+        assert_eq!(Uint128::zero(), at(1059));
+        assert_eq!(Uint128::new(150), at(1060));
+        assert_eq!(Uint128::new(500), at(1200));
+        assert_eq!(amount, at(1400));
+        assert_eq!(amount, at(5000));
+
+        // no schedule is fully vested.
+        assert_eq!(
+            amount,
+            vested_amount(&config, amount, &None, Timestamp::from_seconds(0))
+        );
+    }
+
+    #[test]
+    fn validate_entry_vesting() {
+        let vesting = Some(Vesting {
+            cliff: 60,
+            duration: 400,
+        });
+        let amount = Uint128::new(1000);
+        let vesting_leaf = claim_leaf(TO_VERIFY_ADDR, amount, &None, &vesting).unwrap();
+        let items = vec![vesting_leaf.clone(), String::from(TO_VERIFY_VALID)];
+        let mt = merkle::Tree::new(&items);
+        let proof = mt.find_proof(&vesting_leaf).unwrap();
+
+        let mut deps = mock_dependencies();
+        let config = Config {
+            merkle_root: mt.get_root().unwrap().to_string(),
+            vesting_start: Timestamp::from_seconds(1000),
+            ..test_config()
+        };
+        let mut env = mock_env();
+        let validate = |deps: Deps, env: &Env, requested| {
+            validate_entry(
+                deps,
+                env,
+                &config,
+                &proof,
+                &vesting_leaf,
+                amount,
+                &vesting,
+                requested,
+            )
+        };
+
+        // nothing is vested before the cliff.
+        env.block.time = Timestamp::from_seconds(1059);
+        let err = validate(deps.as_ref(), &env, None).unwrap_err();
+        assert!(matches!(err, ContractError::NotVested { .. }), "{:?}", err);
+
+        // only the vested amount can be claimed.
+        env.block.time = Timestamp::from_seconds(1200);
+        assert_eq!(
+            Uint128::new(500),
+            validate(deps.as_ref(), &env, None).unwrap()
+        );
+        let err = validate(deps.as_ref(), &env, Some(Uint128::new(501))).unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidRequestedAmount { .. }),
+            "{:?}",
+            err
+        );
+
+        CLAIMED_AMOUNTS
+            .save(deps.as_mut().storage, &vesting_leaf, &Uint128::new(500))
+            .unwrap();
+        let err = validate(deps.as_ref(), &env, None).unwrap_err();
+        assert!(matches!(err, ContractError::NotVested { .. }), "{:?}", err);
+
+        // the rest is claimable once fully vested.
+        env.block.time = Timestamp::from_seconds(1400);
+        assert_eq!(
+            Uint128::new(500),
+            validate(deps.as_ref(), &env, None).unwrap()
+        );
+
+        CLAIMED_AMOUNTS
+            .save(deps.as_mut().storage, &vesting_leaf, &amount)
+            .unwrap();
+        let err = validate(deps.as_ref(), &env, None).unwrap_err();
+        assert!(
+            matches!(err, ContractError::AlreadyClaimed { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn validate_note_max_length_success() {
+        validate_note(&"a".repeat(MAX_NOTE_LENGTH)).unwrap();
+    }
+
+    #[test]
+    fn validate_note_too_long_error() {
+        let err = validate_note(&"a".repeat(MAX_NOTE_LENGTH + 1)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContractError::NoteTooLong {
+                    length,
+                    max_length: MAX_NOTE_LENGTH
+                } if length == MAX_NOTE_LENGTH + 1
+            ),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn validate_ibc_destination_errors() {
+        let ibc_destination = IbcDestination {
+            channel: String::from("channel-0"),
+            receiver: String::from("cosmos1receiver"),
+            timeout_seconds: None,
+        };
+        let claimer = mock_info(TO_VERIFY_ADDR, &[]);
+        validate_ibc_destination(&claimer, TO_VERIFY_ADDR, &ibc_destination).unwrap();
+
+        // the tokens of the claimer cannot be redirected by others.
+        let err =
+            validate_ibc_destination(&mock_info("sender", &[]), TO_VERIFY_ADDR, &ibc_destination)
+                .unwrap_err();
+        assert!(
+            matches!(err, ContractError::IbcDestinationNotClaimer {}),
+            "{:?}",
+            err
+        );
+
+        for channel in ["", "channel-", "channel-x", "transfer/channel-0"] {
+            let invalid = IbcDestination {
+                channel: String::from(channel),
+                ..ibc_destination.clone()
+            };
+            let err = validate_ibc_destination(&claimer, TO_VERIFY_ADDR, &invalid).unwrap_err();
+            assert!(
+                matches!(err, ContractError::InvalidIbcChannel { .. }),
+                "{:?}",
+                err
+            );
+        }
+
+        let invalid = IbcDestination {
+            receiver: String::new(),
+            ..ibc_destination.clone()
+        };
+        let err = validate_ibc_destination(&claimer, TO_VERIFY_ADDR, &invalid).unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidAddress { .. }),
+            "{:?}",
+            err
+        );
+
+        for seconds in [0, MAX_IBC_TIMEOUT_SECONDS + 1] {
+            let invalid = IbcDestination {
+                timeout_seconds: Some(seconds),
+                ..ibc_destination.clone()
+            };
+            let err = validate_ibc_destination(&claimer, TO_VERIFY_ADDR, &invalid).unwrap_err();
+            assert!(
+                matches!(err, ContractError::InvalidIbcTimeout { .. }),
+                "{:?}",
+                err
+            );
+        }
+        let max_timeout = IbcDestination {
+            timeout_seconds: Some(MAX_IBC_TIMEOUT_SECONDS),
+            ..ibc_destination
+        };
+        validate_ibc_destination(&claimer, TO_VERIFY_ADDR, &max_timeout).unwrap();
+    }
+
     #[test]
     fn verify_proof_invalid_proof_error() {
         verify_proof(
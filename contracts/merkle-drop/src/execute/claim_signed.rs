@@ -0,0 +1,407 @@
+use bech32::{ToBase32, Variant};
+use cosmwasm_std::{Api, Binary, DepsMut, Env, MessageInfo, Response, StdError, Uint128};
+use merkle::proof::Proof;
+use ripemd::Ripemd160;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::execute::claim::{claim_entry, claim_typed};
+use crate::msg::Vesting;
+use crate::state::{CLAIMED_AMOUNTS, CONFIG};
+
+// SignedClaim is the payload the claimer signs, serialized as json in the order of
+// its fields. It binds every field of the claim, the contract address so that the
+// signature cannot be replayed on another drop, and the amount of the entry claimed
+// so far so that it cannot be replayed once the claim is made.
+#[derive(Serialize)]
+pub(crate) struct SignedClaim<'a> {
+    pub contract_addr: &'a str,
+    pub claimer_addr: &'a str,
+    pub amount: Option<Uint128>,
+    pub denom: &'a Option<String>,
+    pub vesting: &'a Option<Vesting>,
+    pub requested: Option<Uint128>,
+    pub note: &'a Option<String>,
+    pub proof: &'a Proof,
+    pub proof_version: Option<u32>,
+    pub claimed: Uint128,
+}
+
+// claim_signed is the same as claim_typed but requires the claimer to have signed
+// the claim off-chain. Allows a relayer to submit the claim on the claimer's behalf.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_signed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proof: Proof,
+    proof_version: Option<u32>,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+    claimer_addr: String,
+    note: Option<String>,
+    vesting: Option<Vesting>,
+    requested: Option<Uint128>,
+    signature: Binary,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (_, claim) = claim_entry(
+        deps.as_ref(),
+        &config,
+        &claimer_addr,
+        amount,
+        &denom,
+        &vesting,
+    )?;
+    let claimed = CLAIMED_AMOUNTS
+        .may_load(deps.storage, &claim)?
+        .unwrap_or_default();
+    let payload = signed_claim_payload(&SignedClaim {
+        contract_addr: env.contract.address.as_str(),
+        claimer_addr: &claimer_addr,
+        amount,
+        denom: &denom,
+        vesting: &vesting,
+        requested,
+        note: &note,
+        proof: &proof,
+        proof_version,
+        claimed,
+    })?;
+    verify_claim_signature(deps.api, &claimer_addr, &payload, &signature, &pubkey)?;
+
+    claim_typed(
+        deps,
+        env,
+        info,
+        proof,
+        proof_version,
+        amount,
+        denom,
+        claimer_addr,
+        note,
+        vesting,
+        requested,
+        None,
+    )
+}
+
+// signed_claim_payload returns the json of the payload the claimer signs.
+pub(crate) fn signed_claim_payload(signed_claim: &SignedClaim) -> Result<String, ContractError> {
+    serde_json_wasm::to_string(signed_claim)
+        .map_err(|e| StdError::serialize_err("SignedClaim", e).into())
+}
+
+// verify_claim_signature verifies that the signature of the sha256 hash of the payload
+// is made by the secp256k1 pubkey and that the pubkey derives the claimer address.
+fn verify_claim_signature(
+    api: &dyn Api,
+    claimer_addr: &str,
+    payload: &str,
+    signature: &Binary,
+    pubkey: &Binary,
+) -> Result<(), ContractError> {
+    let message_hash = Sha256::digest(payload.as_bytes());
+    let is_valid = api
+        .secp256k1_verify(&message_hash, signature, pubkey)
+        .map_err(StdError::from)?;
+    if !is_valid {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    let signer = pubkey_to_address(pubkey, claimer_addr)?;
+    if signer != claimer_addr {
+        return Err(ContractError::SignerMismatch {
+            signer,
+            claimer: claimer_addr.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// pubkey_to_address derives the cosmos-sdk address of the secp256k1 pubkey
+// with the same bech32 prefix as the claimer address.
+fn pubkey_to_address(pubkey: &[u8], claimer_addr: &str) -> Result<String, ContractError> {
+    let (prefix, _, _) =
+        bech32::decode(claimer_addr).map_err(|e| ContractError::InvalidAddress {
+            address: claimer_addr.to_string(),
+            reason: e.to_string(),
+        })?;
+
+    let hash = Ripemd160::digest(Sha256::digest(pubkey));
+    bech32::encode(&prefix, hash.to_base32(), Variant::Bech32).map_err(|e| {
+        ContractError::InvalidAddress {
+            address: claimer_addr.to_string(),
+            reason: e.to_string(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::execute::claim::claim_leaf;
+    use crate::msg::ExecuteMsg;
+    use crate::state::{Config, SUBDENOM};
+    use crate::testing::{mock_dependencies_with_denom_admin, test_config};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::Addr;
+    use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use merkle::proof::VERSION;
+
+    const CONTRACT_ADDR: &str = "cosmos2contract";
+    const AMOUNT: u128 = 1421901;
+
+    // SECRET_KEY is a known key used to sign the claims in the tests.
+    const SECRET_KEY: [u8; 32] = [1; 32];
+
+    fn sign(payload: &str) -> (Binary, Binary) {
+        let signing_key = SigningKey::from_bytes(&SECRET_KEY).unwrap();
+        let signature: Signature = signing_key.sign(payload.as_bytes());
+        let signature_bytes: &[u8] = signature.as_ref();
+        let pubkey = signing_key.verifying_key().to_bytes();
+        (
+            Binary::from(signature_bytes),
+            Binary::from(pubkey.as_slice()),
+        )
+    }
+
+    fn signer_addr() -> String {
+        let signing_key = SigningKey::from_bytes(&SECRET_KEY).unwrap();
+        let pubkey = signing_key.verifying_key().to_bytes();
+        // any valid address with the osmo prefix.
+        pubkey_to_address(&pubkey, "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj").unwrap()
+    }
+
+    // SignedClaimFields are the fields of a ClaimSigned message but the signature.
+    #[derive(Clone)]
+    struct SignedClaimFields {
+        proof: Proof,
+        proof_version: Option<u32>,
+        amount: Option<Uint128>,
+        denom: Option<String>,
+        claimer_addr: String,
+        note: Option<String>,
+        vesting: Option<Vesting>,
+        requested: Option<Uint128>,
+    }
+
+    impl SignedClaimFields {
+        fn payload(&self, contract_addr: &str, claimed: Uint128) -> String {
+            signed_claim_payload(&SignedClaim {
+                contract_addr,
+                claimer_addr: &self.claimer_addr,
+                amount: self.amount,
+                denom: &self.denom,
+                vesting: &self.vesting,
+                requested: self.requested,
+                note: &self.note,
+                proof: &self.proof,
+                proof_version: self.proof_version,
+                claimed,
+            })
+            .unwrap()
+        }
+
+        fn msg(self, signature: &Binary, pubkey: &Binary) -> ExecuteMsg {
+            ExecuteMsg::ClaimSigned {
+                proof: self.proof,
+                proof_version: self.proof_version,
+                amount: self.amount,
+                denom: self.denom,
+                claimer_addr: self.claimer_addr,
+                note: self.note,
+                vesting: self.vesting,
+                requested: self.requested,
+                signature: signature.clone(),
+                pubkey: pubkey.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn verify_claim_signature_success() {
+        let deps = mock_dependencies();
+        let claimer_addr = signer_addr();
+        assert!(claimer_addr.starts_with("osmo1"), "{}", claimer_addr);
+
+        let claim = claim_leaf(&claimer_addr, Uint128::new(AMOUNT), &None, &None).unwrap();
+        let (signature, pubkey) = sign(&claim);
+
+        verify_claim_signature(&deps.api, &claimer_addr, &claim, &signature, &pubkey).unwrap();
+
+        let err = verify_claim_signature(&deps.api, &claimer_addr, "other", &signature, &pubkey)
+            .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidSignature {}),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn claim_signed_tampered_fields() {
+        let claimer_addr = signer_addr();
+        let claim = claim_leaf(&claimer_addr, Uint128::new(AMOUNT), &None, &None).unwrap();
+        let items = vec![
+            claim.clone(),
+            String::from("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4|10"),
+        ];
+        let mt = merkle::Tree::new(&items);
+        let proof = mt.find_proof(&claim).unwrap();
+
+        let mut deps = mock_dependencies_with_denom_admin(Some("owner"));
+        let config = Config {
+            merkle_root: mt.get_root().unwrap().to_string(),
+            ..test_config()
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        SUBDENOM
+            .save(deps.as_mut().storage, &String::from("subdenom"))
+            .unwrap();
+
+        let fields = SignedClaimFields {
+            proof,
+            proof_version: None,
+            amount: Some(Uint128::new(AMOUNT)),
+            denom: None,
+            claimer_addr: claimer_addr.clone(),
+            note: Some(String::from("note")),
+            vesting: None,
+            requested: Some(Uint128::new(100)),
+        };
+        let (signature, pubkey) = sign(&fields.payload(CONTRACT_ADDR, Uint128::zero()));
+
+        // every field of the claim is signed.
+        let other_proof = mt.find_proof(&items[1]).unwrap();
+        let tampered: Vec<(&str, SignedClaimFields)> = vec![
+            (
+                "proof",
+                SignedClaimFields {
+                    proof: other_proof,
+                    ..fields.clone()
+                },
+            ),
+            (
+                "proof_version",
+                SignedClaimFields {
+                    proof_version: Some(u32::from(VERSION)),
+                    ..fields.clone()
+                },
+            ),
+            (
+                "amount",
+                SignedClaimFields {
+                    amount: Some(Uint128::new(AMOUNT + 1)),
+                    ..fields.clone()
+                },
+            ),
+            (
+                "denom",
+                SignedClaimFields {
+                    denom: Some(String::from("subdenom")),
+                    ..fields.clone()
+                },
+            ),
+            (
+                "note",
+                SignedClaimFields {
+                    note: Some(String::from("other note")),
+                    ..fields.clone()
+                },
+            ),
+            (
+                "vesting",
+                SignedClaimFields {
+                    vesting: Some(Vesting {
+                        cliff: 0,
+                        duration: 1,
+                    }),
+                    ..fields.clone()
+                },
+            ),
+            (
+                "requested",
+                SignedClaimFields {
+                    requested: Some(Uint128::new(AMOUNT)),
+                    ..fields.clone()
+                },
+            ),
+        ];
+        for (field, tampered) in tampered {
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("relayer", &[]),
+                tampered.msg(&signature, &pubkey),
+            )
+            .unwrap_err();
+            assert!(
+                matches!(err, ContractError::InvalidSignature {}),
+                "{}: {:?}",
+                field,
+                err
+            );
+        }
+
+        // another drop.
+        let mut env = mock_env();
+        env.contract.address = Addr::unchecked("cosmos2other");
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("relayer", &[]),
+            fields.clone().msg(&signature, &pubkey),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidSignature {}),
+            "{:?}",
+            err
+        );
+
+        // the signed claim is relayed once.
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            fields.clone().msg(&signature, &pubkey),
+        )
+        .unwrap();
+        CLAIMED_AMOUNTS
+            .save(deps.as_mut().storage, &claim, &Uint128::new(100))
+            .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            fields.msg(&signature, &pubkey),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidSignature {}),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn verify_claim_signature_signer_mismatch() {
+        let deps = mock_dependencies();
+        let claimer_addr = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+
+        let claim = claim_leaf(claimer_addr, Uint128::new(AMOUNT), &None, &None).unwrap();
+        let (signature, pubkey) = sign(&claim);
+
+        let err = verify_claim_signature(&deps.api, claimer_addr, &claim, &signature, &pubkey)
+            .unwrap_err();
+        assert!(
+            matches!(err, ContractError::SignerMismatch { .. }),
+            "{:?}",
+            err
+        );
+    }
+}
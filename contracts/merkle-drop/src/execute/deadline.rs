@@ -0,0 +1,148 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response, Timestamp};
+
+use crate::state::CONFIG;
+use crate::ContractError;
+
+// extend_deadline moves the claim deadline of the drop to the new deadline, which
+// must be later, so that the owner can give claimants more time but cannot cut
+// claims off early. A drop without a deadline has nothing to extend.
+pub fn extend_deadline(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_deadline: Timestamp,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // validate sender
+    if config.owner != info.sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: info.sender.into_string(),
+            owner: config.owner.into_string(),
+        });
+    }
+
+    let deadline = config
+        .claim_deadline
+        .ok_or(ContractError::NoClaimDeadline {})?;
+    if new_deadline <= deadline {
+        return Err(ContractError::DeadlineNotExtended {
+            deadline,
+            new_deadline,
+        });
+    }
+
+    config.claim_deadline = Some(new_deadline);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "extend_deadline")
+        .add_attribute("owner", info.sender)
+        .add_attribute("old_deadline", deadline.to_string())
+        .add_attribute("new_deadline", new_deadline.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Config;
+    use crate::testing::test_config;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    fn setup_config(deps: DepsMut, claim_deadline: Option<Timestamp>) {
+        let config = Config {
+            claim_start: Some(Timestamp::from_seconds(100)),
+            claim_deadline,
+            ..test_config()
+        };
+        CONFIG.save(deps.storage, &config).unwrap();
+    }
+
+    #[test]
+    fn extend_deadline_later() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), Some(Timestamp::from_seconds(200)));
+
+        let res = extend_deadline(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            Timestamp::from_seconds(300),
+        )
+        .unwrap();
+        let attribute = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.clone())
+        };
+        assert_eq!(
+            Some(Timestamp::from_seconds(200).to_string()),
+            attribute("old_deadline")
+        );
+        assert_eq!(
+            Some(Timestamp::from_seconds(300).to_string()),
+            attribute("new_deadline")
+        );
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(Some(Timestamp::from_seconds(300)), config.claim_deadline);
+    }
+
+    #[test]
+    fn extend_deadline_earlier_rejected() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), Some(Timestamp::from_seconds(200)));
+
+        for seconds in [150, 200] {
+            let err = extend_deadline(
+                deps.as_mut(),
+                mock_info("owner", &[]),
+                Timestamp::from_seconds(seconds),
+            )
+            .unwrap_err();
+            assert!(
+                matches!(err, ContractError::DeadlineNotExtended { .. }),
+                "{:?}",
+                err
+            );
+        }
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(Some(Timestamp::from_seconds(200)), config.claim_deadline);
+    }
+
+    #[test]
+    fn extend_deadline_without_deadline() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), None);
+
+        let err = extend_deadline(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            Timestamp::from_seconds(300),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::NoClaimDeadline {}),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn extend_deadline_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut(), Some(Timestamp::from_seconds(200)));
+
+        let err = extend_deadline(
+            deps.as_mut(),
+            mock_info("anyone", &[]),
+            Timestamp::from_seconds(300),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+    }
+}
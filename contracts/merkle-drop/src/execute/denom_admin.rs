@@ -0,0 +1,104 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::TokenfactoryQuerier;
+
+use crate::state::{CONFIG, SUBDENOM};
+use crate::ContractError;
+
+// verify_denom_admin checks that the owner is still the tokenfactory admin of the
+// drop denom and stores the result, so that operators can confirm that claims will
+// mint before announcing the drop. The owner is not the admin if the denom has no
+// authority metadata, e.g. if the admin was changed to the empty address.
+pub fn verify_denom_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // validate sender
+    if config.owner != info.sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: info.sender.into_string(),
+            owner: config.owner.into_string(),
+        });
+    }
+
+    let subdenom = SUBDENOM.load(deps.storage)?;
+    let full_denom = format!("factory/{}/{}", config.owner, subdenom);
+
+    let tf_querier = TokenfactoryQuerier::new(&deps.querier);
+    let admin = tf_querier
+        .denom_authority_metadata(full_denom.clone())?
+        .authority_metadata
+        .map(|metadata| metadata.admin);
+    debug!(deps.api, "denom admin = {admin:?}");
+
+    config.denom_admin_ok = admin.as_deref() == Some(config.owner.as_str());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "verify_denom_admin")
+        .add_attribute("denom", full_denom)
+        .add_attribute("denom_admin_ok", config.denom_admin_ok.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        mock_dependencies_with_denom_admin, test_config, TokenfactoryMockQuerier,
+    };
+    use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+    use cosmwasm_std::OwnedDeps;
+
+    fn setup(admin: Option<&str>) -> OwnedDeps<MockStorage, MockApi, TokenfactoryMockQuerier> {
+        let mut deps = mock_dependencies_with_denom_admin(admin);
+        CONFIG.save(deps.as_mut().storage, &test_config()).unwrap();
+        SUBDENOM
+            .save(deps.as_mut().storage, &String::from("mydenom"))
+            .unwrap();
+        deps
+    }
+
+    #[test]
+    fn verify_denom_admin_owner_is_admin() {
+        let mut deps = setup(Some("owner"));
+
+        let res = verify_denom_admin(deps.as_mut(), mock_info("owner", &[])).unwrap();
+        assert_eq!(
+            Some("factory/owner/mydenom"),
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "denom")
+                .map(|attr| attr.value.as_str())
+        );
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert!(config.denom_admin_ok);
+    }
+
+    #[test]
+    fn verify_denom_admin_owner_not_admin() {
+        for admin in [Some("other"), None] {
+            let mut deps = setup(admin);
+
+            // a previous successful check is overwritten.
+            let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+            config.denom_admin_ok = true;
+            CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+            verify_denom_admin(deps.as_mut(), mock_info("owner", &[])).unwrap();
+
+            let config = CONFIG.load(deps.as_ref().storage).unwrap();
+            assert!(!config.denom_admin_ok, "{:?}", admin);
+        }
+    }
+
+    #[test]
+    fn verify_denom_admin_unauthorized() {
+        let mut deps = setup(Some("owner"));
+
+        let err = verify_denom_admin(deps.as_mut(), mock_info("anyone", &[])).unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -1,2 +1,10 @@
+pub(crate) mod amounts;
 pub(crate) mod claim;
+pub(crate) mod claim_signed;
+pub(crate) mod deadline;
+pub(crate) mod denom_admin;
+pub(crate) mod refund;
+pub(crate) mod revoke_claim;
+pub(crate) mod root;
 pub(crate) mod set_subdenom;
+pub(crate) mod sweep;
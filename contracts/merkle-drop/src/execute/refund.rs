@@ -0,0 +1,49 @@
+use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
+
+use crate::execute::claim::validate_ibc_destination;
+use crate::ibc::{ibc_transfer_submsg, take_refund};
+use crate::msg::IbcDestination;
+use crate::ContractError;
+
+// withdraw_refund sends the sender the tokens of its failed or timed out IBC
+// transfers of the denom, or transfers them to the IBC destination if given.
+// A transfer that fails again is refunded again.
+pub fn withdraw_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    ibc_destination: Option<IbcDestination>,
+) -> Result<Response, ContractError> {
+    let claimer_addr = info.sender.to_string();
+    if let Some(ibc_destination) = &ibc_destination {
+        validate_ibc_destination(&info, &claimer_addr, ibc_destination)?;
+    }
+
+    let refund = take_refund(deps.storage, &claimer_addr, &denom)?;
+    let amount = Coin::new(refund.u128(), denom);
+
+    let response = Response::new()
+        .add_attribute("method", "withdraw_refund")
+        .add_attribute("amount", amount.to_string());
+    match ibc_destination {
+        Some(ibc_destination) => {
+            let transfer_msg = ibc_transfer_submsg(
+                deps.storage,
+                env.contract.address.as_str(),
+                env.block.time,
+                &claimer_addr,
+                &ibc_destination,
+                amount,
+            )?;
+            Ok(response
+                .add_submessage(transfer_msg)
+                .add_attribute("ibc-channel", ibc_destination.channel)
+                .add_attribute("ibc-receiver", ibc_destination.receiver))
+        }
+        None => Ok(response.add_message(BankMsg::Send {
+            to_address: claimer_addr,
+            amount: vec![amount],
+        })),
+    }
+}
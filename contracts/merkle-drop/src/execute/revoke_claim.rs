@@ -0,0 +1,99 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response, Uint128};
+
+use crate::execute::claim::claim_entry;
+use crate::msg::Vesting;
+use crate::state::{CONFIG, REVOKED_CLAIMS};
+use crate::ContractError;
+
+// revoke_claim prevents the given entry from being claimed without
+// updating the root, e.g. if it turns out to be fraudulent after deployment.
+// Only the remaining amount is revoked, partial claims made so far are kept.
+pub fn revoke_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    amount: Uint128,
+    denom: Option<String>,
+    vesting: Option<Vesting>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // validate sender
+    if config.owner != info.sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: info.sender.into_string(),
+            owner: config.owner.into_string(),
+        });
+    }
+
+    let (_, claim) = claim_entry(
+        deps.as_ref(),
+        &config,
+        &address,
+        Some(amount),
+        &denom,
+        &vesting,
+    )?;
+    REVOKED_CLAIMS.save(deps.storage, &claim, &true)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_claim")
+        .add_attribute("owner", info.sender)
+        .add_attribute("claim", claim))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execute::claim::claim_leaf;
+    use crate::testing::test_config;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    fn setup_config(deps: DepsMut) {
+        let config = test_config();
+        CONFIG.save(deps.storage, &config).unwrap();
+    }
+
+    #[test]
+    fn revoke_claim_owner() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut());
+
+        revoke_claim(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            String::from("claimer"),
+            Uint128::new(100),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let claim = claim_leaf("claimer", Uint128::new(100), &None, &None).unwrap();
+        assert!(REVOKED_CLAIMS.has(deps.as_ref().storage, &claim));
+    }
+
+    #[test]
+    fn revoke_claim_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut());
+
+        let err = revoke_claim(
+            deps.as_mut(),
+            mock_info("anyone", &[]),
+            String::from("claimer"),
+            Uint128::new(100),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+
+        let claim = claim_leaf("claimer", Uint128::new(100), &None, &None).unwrap();
+        assert!(!REVOKED_CLAIMS.has(deps.as_ref().storage, &claim));
+    }
+}
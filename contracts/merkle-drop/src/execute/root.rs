@@ -0,0 +1,163 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+
+use crate::state::{Config, CONFIG};
+use crate::ContractError;
+
+// update_root replaces the merkle root, e.g. to fix a mistake in the data set.
+// Entries already claimed stay claimed under the new root.
+pub fn update_root(
+    deps: DepsMut,
+    info: MessageInfo,
+    merkle_root: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    validate_owner(&config, &info)?;
+
+    if config.root_frozen {
+        return Err(ContractError::RootFrozen {});
+    }
+
+    config.merkle_root = merkle::parse_root(&merkle_root)
+        .map_err(|e| ContractError::FailedToDecodeRoot {
+            root: e.to_string(),
+        })?
+        .to_string();
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_root")
+        .add_attribute("owner", info.sender)
+        .add_attribute("merkle_root", config.merkle_root))
+}
+
+// freeze_root permanently prevents the root from being updated so that
+// claimants can be assured that it does not change once claims begin.
+pub fn freeze_root(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    validate_owner(&config, &info)?;
+
+    config.root_frozen = true;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "freeze_root")
+        .add_attribute("owner", info.sender))
+}
+
+fn validate_owner(config: &Config, info: &MessageInfo) -> Result<(), ContractError> {
+    if config.owner != info.sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: info.sender.to_string(),
+            owner: config.owner.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_config;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    // NEW_ROOT is the root of "testdata/address_amount.csv" and NEW_ROOT_HEX its hex encoding.
+    const NEW_ROOT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+    const NEW_ROOT_HEX: &str = "0xcb13cf6e3fe4e865c082ce93a357a6289042de3ab88527920dc8613acf63fede";
+
+    fn setup_config(deps: DepsMut) {
+        let config = test_config();
+        CONFIG.save(deps.storage, &config).unwrap();
+    }
+
+    #[test]
+    fn update_root_owner() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut());
+
+        update_root(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            String::from(NEW_ROOT),
+        )
+        .unwrap();
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(NEW_ROOT, config.merkle_root);
+
+        // a hex root is stored base64 encoded.
+        setup_config(deps.as_mut());
+        update_root(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            String::from(NEW_ROOT_HEX),
+        )
+        .unwrap();
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(NEW_ROOT, config.merkle_root);
+    }
+
+    #[test]
+    fn update_root_invalid() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut());
+
+        for root in ["new root", "0xcb13cf", ""] {
+            let err = update_root(deps.as_mut(), mock_info("owner", &[]), String::from(root))
+                .unwrap_err();
+            assert!(
+                matches!(err, ContractError::FailedToDecodeRoot { .. }),
+                "{:?}",
+                err
+            );
+        }
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!("root", config.merkle_root);
+    }
+
+    #[test]
+    fn update_root_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut());
+
+        let err = update_root(
+            deps.as_mut(),
+            mock_info("anyone", &[]),
+            String::from(NEW_ROOT),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn freeze_root_rejects_update() {
+        let mut deps = mock_dependencies();
+        setup_config(deps.as_mut());
+
+        let err = freeze_root(deps.as_mut(), mock_info("anyone", &[])).unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+
+        freeze_root(deps.as_mut(), mock_info("owner", &[])).unwrap();
+
+        let err = update_root(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            String::from(NEW_ROOT),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RootFrozen {}), "{:?}", err);
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!("root", config.merkle_root);
+        assert!(config.root_frozen);
+    }
+}
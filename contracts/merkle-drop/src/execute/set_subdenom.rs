@@ -1,4 +1,4 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, QuerierWrapper, Response};
 use osmosis_std::types::{
     cosmos::authz::v1beta1::AuthzQuerier,
     osmosis::tokenfactory::v1beta1::{MsgMint, TokenfactoryQuerier},
@@ -32,18 +32,19 @@ pub fn set_subdenom(
     let full_denom = format!("factory/{}/{}", config.owner, subdenom);
     deps.api
         .debug(&format!("set_subdenom full_denom: {}", full_denom));
-    let response = tf_querier.denom_authority_metadata(full_denom)?;
+    let response = tf_querier.denom_authority_metadata(full_denom.clone())?;
 
-    if response.authority_metadata.is_none() {
-        return Err(ContractError::Std(StdError::GenericErr {
-            msg: String::from("invalid authority metadata"),
-        }));
-    }
-
-    let admin = response.authority_metadata.unwrap().admin;
+    let admin = match response.authority_metadata {
+        Some(metadata) => metadata.admin,
+        None => return Err(ContractError::DenomNotFound { denom: full_denom }),
+    };
     deps.api.debug(&format!("denom admin = {admin:?}"));
-    if !admin.eq(&config.owner.to_string()) {
-        return Err(ContractError::Unauthorized {});
+    if admin != config.owner.as_str() {
+        return Err(ContractError::NotDenomAdmin {
+            denom: full_denom,
+            admin,
+            owner: config.owner.into_string(),
+        });
     }
 
     // ensure that authz grants are created for tokenfactory mint and bank send.
@@ -93,3 +94,65 @@ fn validate_grant(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        mock_dependencies_with_denom_admin, test_config, TokenfactoryMockQuerier,
+    };
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::OwnedDeps;
+
+    fn setup(admin: Option<&str>) -> OwnedDeps<MockStorage, MockApi, TokenfactoryMockQuerier> {
+        let mut deps = mock_dependencies_with_denom_admin(admin);
+        CONFIG.save(deps.as_mut().storage, &test_config()).unwrap();
+        deps
+    }
+
+    #[test]
+    fn set_subdenom_denom_not_found() {
+        let mut deps = setup(None);
+
+        let err = set_subdenom(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            String::from("mydenom"),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DenomNotFound { denom } => {
+                assert_eq!("factory/owner/mydenom", denom)
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+        assert!(SUBDENOM.may_load(deps.as_ref().storage).unwrap().is_none());
+    }
+
+    #[test]
+    fn set_subdenom_not_denom_admin() {
+        let mut deps = setup(Some("other"));
+
+        let err = set_subdenom(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            String::from("mydenom"),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotDenomAdmin {
+                denom,
+                admin,
+                owner,
+            } => {
+                assert_eq!("factory/owner/mydenom", denom);
+                assert_eq!("other", admin);
+                assert_eq!("owner", owner);
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+        assert!(SUBDENOM.may_load(deps.as_ref().storage).unwrap().is_none());
+    }
+}
@@ -0,0 +1,214 @@
+use cosmwasm_std::{BankMsg, DepsMut, Env, MessageInfo, Response};
+
+use crate::state::{CONFIG, REFUND_TOTALS};
+use crate::ContractError;
+
+// sweep sends the whole balance of the contract in the given denom to the recipient,
+// so that the owner can recover tokens sent to the contract by mistake. The denoms the
+// drop mints, the tokenfactory denoms of the owner, cannot be swept until the claims
+// have ended so that tokens misfunded for claims are not drained while they are open.
+// The refunds of failed IBC transfers of claims are kept for the claimers to withdraw.
+pub fn sweep(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // validate sender
+    if config.owner != info.sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: info.sender.into_string(),
+            owner: config.owner.into_string(),
+        });
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let is_drop_denom = denom.starts_with(&format!("factory/{}/", config.owner));
+    let claims_ended = config
+        .claim_deadline
+        .map_or(false, |deadline| env.block.time >= deadline);
+    if is_drop_denom && !claims_ended {
+        return Err(ContractError::DropDenomSweep { denom });
+    }
+
+    let mut balance = deps
+        .querier
+        .query_balance(env.contract.address, denom.clone())?;
+    let refunds = REFUND_TOTALS
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    balance.amount = balance.amount.saturating_sub(refunds);
+    if balance.amount.is_zero() {
+        return Err(ContractError::NothingToSweep { denom });
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![balance.clone()],
+        })
+        .add_attribute("method", "sweep")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", balance.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Config;
+    use crate::testing::test_config;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, CosmosMsg, Timestamp, Uint128};
+
+    const DROP_DENOM: &str = "factory/owner/mydenom";
+
+    fn setup_config(deps: DepsMut, claim_deadline: Option<Timestamp>) {
+        let config = Config {
+            claim_deadline,
+            ..test_config()
+        };
+        CONFIG.save(deps.storage, &config).unwrap();
+    }
+
+    #[test]
+    fn sweep_foreign_denom() {
+        let mut deps = mock_dependencies_with_balance(&[coin(100, "uatom"), coin(5, DROP_DENOM)]);
+        setup_config(deps.as_mut(), None);
+
+        let res = sweep(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            String::from("uatom"),
+            String::from("recipient"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("recipient"),
+                amount: coins(100, "uatom"),
+            })],
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>()
+        );
+
+        let err = sweep(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            String::from("uion"),
+            String::from("recipient"),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::NothingToSweep { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn sweep_drop_denom_while_claims_open() {
+        let mut deps = mock_dependencies_with_balance(&[coin(5, DROP_DENOM)]);
+        let env = mock_env();
+        setup_config(deps.as_mut(), Some(env.block.time.plus_seconds(1)));
+
+        let err = sweep(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            String::from(DROP_DENOM),
+            String::from("recipient"),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::DropDenomSweep { .. }),
+            "{:?}",
+            err
+        );
+
+        // the drop denom can be swept once the claims have ended.
+        let mut env = env;
+        env.block.time = env.block.time.plus_seconds(1);
+        sweep(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            String::from(DROP_DENOM),
+            String::from("recipient"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn sweep_excludes_refunds() {
+        let mut deps = mock_dependencies_with_balance(&[coin(100, DROP_DENOM)]);
+        setup_config(deps.as_mut(), Some(Timestamp::from_seconds(0)));
+        REFUND_TOTALS
+            .save(deps.as_mut().storage, DROP_DENOM, &Uint128::new(60))
+            .unwrap();
+
+        let res = sweep(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            String::from(DROP_DENOM),
+            String::from("recipient"),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("recipient"),
+                amount: coins(40, DROP_DENOM),
+            })],
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>()
+        );
+
+        REFUND_TOTALS
+            .save(deps.as_mut().storage, DROP_DENOM, &Uint128::new(100))
+            .unwrap();
+        let err = sweep(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            String::from(DROP_DENOM),
+            String::from("recipient"),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::NothingToSweep { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn sweep_unauthorized() {
+        let mut deps = mock_dependencies_with_balance(&coins(100, "uatom"));
+        setup_config(deps.as_mut(), None);
+
+        let err = sweep(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            String::from("uatom"),
+            String::from("anyone"),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnauthorizedSender { .. }),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Coin, DepsMut, Response, StdError, StdResult, Storage, SubMsg, Timestamp, Uint128,
+};
+use osmosis_std::types::cosmos::base::v1beta1;
+use osmosis_std::types::ibc::applications::transfer::v1::MsgTransfer;
+
+use crate::execute::claim::ibc_timeout_seconds;
+use crate::msg::{IbcDestination, IbcLifecycleComplete};
+use crate::reply::IBC_TRANSFER_MSG_ID;
+use crate::state::{IbcTransfer, IBC_TRANSFERS, PENDING_TRANSFER, REFUNDS, REFUND_TOTALS};
+use crate::ContractError;
+
+// TRANSFER_PORT is the port of the ICS-20 transfer channels.
+const TRANSFER_PORT: &str = "transfer";
+
+// IbcCallbackMemo is the memo of the transfers of the contract, for ibc-hooks
+// to call the contract back with their acknowledgement or timeout.
+#[cw_serde]
+struct IbcCallbackMemo {
+    ibc_callback: String,
+}
+
+// ibc_transfer_submsg returns the message transferring the amount from the contract
+// to the IBC destination of the claimer, timing out after the timeout of the destination.
+// The transfer is kept in PENDING_TRANSFER until handle_transfer_reply keys it by the
+// sequence of its packet.
+pub(crate) fn ibc_transfer_submsg(
+    storage: &mut dyn Storage,
+    contract_address: &str,
+    block_time: Timestamp,
+    claimer_addr: &str,
+    ibc_destination: &IbcDestination,
+    amount: Coin,
+) -> StdResult<SubMsg> {
+    let memo = serde_json_wasm::to_string(&IbcCallbackMemo {
+        ibc_callback: contract_address.to_string(),
+    })
+    .map_err(|e| StdError::serialize_err("IbcCallbackMemo", e))?;
+    let timeout = block_time.plus_seconds(ibc_timeout_seconds(ibc_destination));
+
+    let msg_transfer = MsgTransfer {
+        source_port: String::from(TRANSFER_PORT),
+        source_channel: ibc_destination.channel.clone(),
+        token: Some(v1beta1::Coin {
+            denom: amount.denom.clone(),
+            amount: amount.amount.to_string(),
+        }),
+        sender: contract_address.to_string(),
+        receiver: ibc_destination.receiver.clone(),
+        timeout_height: None,
+        timeout_timestamp: timeout.nanos(),
+        memo,
+    };
+
+    PENDING_TRANSFER.save(
+        storage,
+        &IbcTransfer {
+            claimer_addr: claimer_addr.to_string(),
+            channel: ibc_destination.channel.clone(),
+            amount,
+        },
+    )?;
+
+    Ok(SubMsg::reply_on_success(msg_transfer, IBC_TRANSFER_MSG_ID))
+}
+
+// ibc_lifecycle_complete settles the transfer of the ibc-hooks callback. The tokens
+// of a transfer that failed or timed out are returned to the contract by the transfer
+// module and added to the refunds of the claimer.
+pub fn ibc_lifecycle_complete(
+    deps: DepsMut,
+    msg: IbcLifecycleComplete,
+) -> Result<Response, ContractError> {
+    let (channel, sequence, success) = match msg {
+        IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        } => (channel, sequence, success),
+        IbcLifecycleComplete::IbcTimeout { channel, sequence } => (channel, sequence, false),
+    };
+
+    let transfer = IBC_TRANSFERS
+        .may_load(deps.storage, (&channel, sequence))?
+        .ok_or_else(|| ContractError::UnknownIbcTransfer {
+            channel: channel.clone(),
+            sequence,
+        })?;
+    IBC_TRANSFERS.remove(deps.storage, (&channel, sequence));
+
+    let response = Response::new()
+        .add_attribute("method", "ibc_lifecycle_complete")
+        .add_attribute("ibc-channel", channel)
+        .add_attribute("ibc-sequence", sequence.to_string())
+        .add_attribute("success", success.to_string());
+    if success {
+        return Ok(response);
+    }
+
+    add_refund(deps.storage, &transfer.claimer_addr, &transfer.amount)?;
+    Ok(response
+        .add_attribute("refund-receiver", transfer.claimer_addr)
+        .add_attribute("refund", transfer.amount.to_string()))
+}
+
+// add_refund adds the amount to the refunds of the claimer.
+fn add_refund(storage: &mut dyn Storage, claimer_addr: &str, amount: &Coin) -> StdResult<()> {
+    REFUNDS.update(
+        storage,
+        (claimer_addr, &amount.denom),
+        |refund| -> StdResult<Uint128> {
+            Ok(refund.unwrap_or_default().checked_add(amount.amount)?)
+        },
+    )?;
+    REFUND_TOTALS.update(storage, &amount.denom, |total| -> StdResult<Uint128> {
+        Ok(total.unwrap_or_default().checked_add(amount.amount)?)
+    })?;
+    Ok(())
+}
+
+// take_refund removes the refund of the claimer in the denom and returns its amount.
+pub(crate) fn take_refund(
+    storage: &mut dyn Storage,
+    claimer_addr: &str,
+    denom: &str,
+) -> Result<Uint128, ContractError> {
+    let refund = REFUNDS
+        .may_load(storage, (claimer_addr, denom))?
+        .unwrap_or_default();
+    if refund.is_zero() {
+        return Err(ContractError::NothingToRefund {
+            denom: denom.to_string(),
+        });
+    }
+
+    REFUNDS.remove(storage, (claimer_addr, denom));
+    REFUND_TOTALS.update(storage, denom, |total| -> StdResult<Uint128> {
+        Ok(total.unwrap_or_default().checked_sub(refund)?)
+    })?;
+    Ok(refund)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    const CHANNEL: &str = "channel-0";
+
+    fn save_transfer(storage: &mut dyn Storage, sequence: u64, amount: u128) {
+        IBC_TRANSFERS
+            .save(
+                storage,
+                (CHANNEL, sequence),
+                &IbcTransfer {
+                    claimer_addr: String::from("claimer"),
+                    channel: String::from(CHANNEL),
+                    amount: Coin::new(amount, "factory/owner/subdenom"),
+                },
+            )
+            .unwrap();
+    }
+
+    fn refund(storage: &dyn Storage) -> Option<Uint128> {
+        REFUNDS
+            .may_load(storage, ("claimer", "factory/owner/subdenom"))
+            .unwrap()
+    }
+
+    #[test]
+    fn ibc_lifecycle_complete_refunds() {
+        let mut deps = mock_dependencies();
+        save_transfer(deps.as_mut().storage, 1, 100);
+        save_transfer(deps.as_mut().storage, 2, 50);
+        save_transfer(deps.as_mut().storage, 3, 10);
+
+        // a received transfer is settled without a refund.
+        ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcAck {
+                channel: String::from(CHANNEL),
+                sequence: 3,
+                ack: String::from("AQ=="),
+                success: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(None, refund(deps.as_ref().storage));
+
+        // the failed and the timed out transfers are refunded.
+        ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcAck {
+                channel: String::from(CHANNEL),
+                sequence: 1,
+                ack: String::from("error"),
+                success: false,
+            },
+        )
+        .unwrap();
+        ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcTimeout {
+                channel: String::from(CHANNEL),
+                sequence: 2,
+            },
+        )
+        .unwrap();
+        assert_eq!(Some(Uint128::new(150)), refund(deps.as_ref().storage));
+        assert_eq!(
+            Uint128::new(150),
+            REFUND_TOTALS
+                .load(deps.as_ref().storage, "factory/owner/subdenom")
+                .unwrap()
+        );
+
+        // a transfer is settled once.
+        let err = ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcTimeout {
+                channel: String::from(CHANNEL),
+                sequence: 2,
+            },
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::UnknownIbcTransfer { sequence: 2, .. }),
+            "{:?}",
+            err
+        );
+        assert_eq!(Some(Uint128::new(150)), refund(deps.as_ref().storage));
+
+        assert_eq!(
+            Uint128::new(150),
+            take_refund(deps.as_mut().storage, "claimer", "factory/owner/subdenom").unwrap()
+        );
+        assert_eq!(None, refund(deps.as_ref().storage));
+        let err =
+            take_refund(deps.as_mut().storage, "claimer", "factory/owner/subdenom").unwrap_err();
+        assert!(
+            matches!(err, ContractError::NothingToRefund { .. }),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -1,8 +1,14 @@
+#[macro_use]
+mod debug;
+
 pub mod contract;
 mod error;
 pub mod execute;
+pub mod ibc;
 pub mod msg;
 pub mod reply;
 pub mod state;
+#[cfg(test)]
+mod testing;
 
 pub use crate::error::ContractError;
@@ -1,9 +1,64 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Coin, Timestamp, Uint128};
+use merkle::leaf::LeafEncoding;
+use merkle::proof::Proof;
 
+/// InstantiateMsg defaults to a drop without any of the optional settings,
+/// to be completed with at least the root and the total amount.
 #[cw_serde]
+#[derive(Default)]
 pub struct InstantiateMsg {
     pub merkle_root: String,
+    /// owner overrides the contract owner. Defaults to the sender.
+    /// Allows a factory to deploy the drop on behalf of a different owner.
+    pub owner: Option<String>,
+    /// total_amount is the sum of the amounts of all entries. It is trusted
+    /// and only reported by the ExpectedTotal query for operators to
+    /// compare against the minted supply.
+    pub total_amount: Uint128,
+    /// salt is the hex encoded salt prefixed to every leaf, if the tree
+    /// was built with one. Must match the salt given to the CLI.
+    pub salt: Option<String>,
+    /// claim_start is the time claims open at. Claims are open immediately if not given.
+    pub claim_start: Option<Timestamp>,
+    /// claim_deadline is the time claims close at. Claims never close if not given.
+    pub claim_deadline: Option<Timestamp>,
+    /// claim_fee is paid by the sender of each claim and forwarded to the owner,
+    /// or refunded if the mint of the claim fails. Lets operators offset the cost of minting. Claims are free if not given.
+    pub claim_fee: Option<Coin>,
+    /// address_only drops have a tree of the addresses only. The amount of each
+    /// address is set by the owner with SetAmounts and claimed in full, so amounts
+    /// can change without changing the root. The root no longer proves the amounts,
+    /// claimants must trust the owner with them.
+    #[serde(default)]
+    pub address_only: bool,
+    /// denom_caps cap the total amount minted of each of the given subdenoms of a
+    /// multi-denom drop, so that a root listing more than intended for a denom
+    /// cannot mint more than its cap. Claims exceeding the cap of their denom fail.
+    #[serde(default)]
+    pub denom_caps: Vec<Coin>,
+    /// eligibility_height is the height of the snapshot the entries were computed
+    /// from. It is not enforced, but reported for users to verify the snapshot.
+    pub eligibility_height: Option<u64>,
+    /// leaf_count is the number of entries of the tree. It is trusted and only reported.
+    pub leaf_count: Option<u64>,
+    /// max_proof_depth is the maximum number of entries of a claim's proof, e.g. the
+    /// max proof length printed by the CLI's estimate-gas. Bounds the gas a claim
+    /// can spend on verification. Proofs of any length are verified if not given.
+    pub max_proof_depth: Option<u32>,
+    /// require_self_claim rejects claims not sent by the claimer itself, e.g. by a
+    /// relayer with ClaimSigned, so that every claim proves control of the key of
+    /// the eligible address. Claims can be relayed if not set.
+    #[serde(default)]
+    pub require_self_claim: bool,
+    /// leaf_encoding is the encoding of the amounts in the leaves, "decimal_string"
+    /// by default or "u128_bytes" for trees built with the CLI's --leaf-encoding u128-bytes.
+    #[serde(default)]
+    pub leaf_encoding: LeafEncoding,
+    /// hash is the hash function the tree was built with, the CLI's --hash. Only
+    /// "sha3-256" trees can be verified, so any other hash function is rejected
+    /// instead of instantiating a drop none can claim from. Defaults to "sha3-256".
+    pub hash: Option<String>,
 }
 
 #[cw_serde]
@@ -13,11 +68,153 @@ pub enum ExecuteMsg {
     },
     Claim {
         proof: String,
-        amount: Uint128,
+        /// proof_version is the version of the proof format, merkle::proof::VERSION,
+        /// the proof was built with. A proof of another version than the drop's is
+        /// rejected instead of failing to verify. The version of a versioned json
+        /// proof is checked as well. Proofs of no version are accepted as is.
+        proof_version: Option<u32>,
+        /// amount is the amount of the entry. It is required unless the drop is
+        /// address-only, which claims the amount set by the owner instead and
+        /// ignores the given amount.
+        amount: Option<Uint128>,
+        /// denom is the subdenom of the entry in a multi-denom drop. It is bound
+        /// into the leaf after the amount and minted instead of the drop's subdenom.
+        denom: Option<String>,
+        claimer_addr: String,
+        /// note is an optional memo echoed back as a response attribute.
+        /// Lets front-ends tag claims with a campaign source or referral code.
+        note: Option<String>,
+        /// vesting must be given if the entry was encoded with a vesting schedule.
+        vesting: Option<Vesting>,
+        /// requested is the amount to claim, at most the remaining amount of the entry.
+        /// Allows claiming an entry gradually. Claims the remaining amount if not given.
+        requested: Option<Uint128>,
+        /// ibc_destination has the minted tokens transferred over IBC to a receiver
+        /// on another chain instead of sent to the claimer. Only the claimer can
+        /// give it, so that the tokens of the claimer cannot be redirected.
+        ibc_destination: Option<IbcDestination>,
+    },
+    /// ClaimTyped is the same as Claim but accepts the proof
+    /// as a typed Proof instead of its json string.
+    ClaimTyped {
+        proof: Proof,
+        proof_version: Option<u32>,
+        amount: Option<Uint128>,
+        denom: Option<String>,
         claimer_addr: String,
+        note: Option<String>,
+        vesting: Option<Vesting>,
+        requested: Option<Uint128>,
+        ibc_destination: Option<IbcDestination>,
+    },
+    /// ClaimSigned is the same as ClaimTyped but must be signed by the claimer.
+    /// Allows a relayer to submit the claim on the claimer's behalf.
+    /// signature is the secp256k1 signature of the sha256 hash of the json of
+    /// {"contract_addr", "claimer_addr", "amount", "denom", "vesting", "requested",
+    /// "note", "proof", "proof_version", "claimed"}, in that order and with null for
+    /// the fields not given, where claimed is the amount of the entry claimed so far,
+    /// so that a signature is used once. pubkey is the compressed secp256k1 pubkey of
+    /// the claimer. The tokens cannot be claimed to an IBC destination, since the
+    /// destination is not signed.
+    ClaimSigned {
+        proof: Proof,
+        proof_version: Option<u32>,
+        amount: Option<Uint128>,
+        denom: Option<String>,
+        claimer_addr: String,
+        note: Option<String>,
+        vesting: Option<Vesting>,
+        requested: Option<Uint128>,
+        signature: Binary,
+        pubkey: Binary,
+    },
+    /// RevokeClaim prevents the given entry from being claimed.
+    /// Allows the owner to invalidate a fraudulent entry without updating the root.
+    RevokeClaim {
+        address: String,
+        amount: Uint128,
+        denom: Option<String>,
+        vesting: Option<Vesting>,
+    },
+    /// UpdateRoot replaces the merkle root. Rejected once the root is frozen.
+    UpdateRoot {
+        merkle_root: String,
+    },
+    /// FreezeRoot permanently prevents the root from being updated.
+    FreezeRoot {},
+    /// SetAmounts sets the amounts of the given addresses of an address-only drop.
+    /// Amounts claimed so far are kept, so an address can claim the difference
+    /// if its amount is raised and nothing if it is lowered below the claimed amount.
+    SetAmounts {
+        amounts: Vec<(String, Uint128)>,
+    },
+    /// VerifyDenomAdmin checks that the owner is still the tokenfactory admin
+    /// of the drop denom, so that claims can mint, and stores the result.
+    VerifyDenomAdmin {},
+    /// Sweep sends the whole balance of the contract in the given denom to the
+    /// recipient, recovering tokens sent to the contract by mistake. The denoms
+    /// the drop mints cannot be swept until the claim deadline has passed.
+    Sweep {
+        denom: String,
+        recipient: String,
+    },
+    /// ExtendDeadline moves the claim deadline to new_deadline, which must be
+    /// later than the current one, so that claims can never be cut off early.
+    /// Rejected if the drop has no claim deadline.
+    ExtendDeadline {
+        new_deadline: Timestamp,
+    },
+    /// WithdrawRefund sends the claimer the tokens of its failed or timed out IBC
+    /// transfers of the given denom, or transfers them to ibc_destination if given.
+    WithdrawRefund {
+        denom: String,
+        ibc_destination: Option<IbcDestination>,
     },
 }
 
+/// SudoMsg is called by the chain. IbcLifecycleComplete is the ibc-hooks callback
+/// of the IBC transfers of the contract sent with an "ibc_callback" memo.
+#[cw_serde]
+pub enum SudoMsg {
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    /// IbcAck is the acknowledgement of the transfer, success if the tokens were received.
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    /// IbcTimeout the transfer timed out and the tokens were returned to the contract.
+    IbcTimeout { channel: String, sequence: u64 },
+}
+
+/// Vesting is the vesting schedule bound into a leaf.
+/// Both values are in seconds from the claim start, or from the instantiation
+/// of drops without a claim start. Nothing vests before the cliff, then the
+/// amount vests linearly until the whole amount is vested at the duration.
+#[cw_serde]
+pub struct Vesting {
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+/// IbcDestination is the receiver of the claimed tokens on another chain,
+/// reached over the ICS-20 transfer channel of this chain.
+#[cw_serde]
+pub struct IbcDestination {
+    /// channel is the transfer channel, e.g. "channel-0".
+    pub channel: String,
+    /// receiver is the address of the receiver on the other chain.
+    pub receiver: String,
+    /// timeout_seconds is the time after the claim the transfer times out at,
+    /// at most MAX_IBC_TIMEOUT_SECONDS. Defaults to DEFAULT_IBC_TIMEOUT_SECONDS.
+    pub timeout_seconds: Option<u64>,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -26,6 +223,126 @@ pub enum QueryMsg {
 
     #[returns(GetSubdenomResponse)]
     GetSubdenom {},
+
+    #[returns(IsOwnerResponse)]
+    IsOwner { address: String },
+
+    /// Remaining returns the amount of the given entry that a claim would accept at
+    /// the block time, the vested amount that is not claimed yet. The remaining amount
+    /// of a revoked entry is zero. The entry is not verified against the root.
+    #[returns(RemainingResponse)]
+    Remaining {
+        claimer_addr: String,
+        amount: Uint128,
+        denom: Option<String>,
+        vesting: Option<Vesting>,
+    },
+
+    /// LeafPreimage returns the leaf data, prefixed with the salt if any,
+    /// that the contract hashes to verify the proof of the given entry.
+    #[returns(LeafPreimageResponse)]
+    LeafPreimage {
+        address: String,
+        amount: Uint128,
+        denom: Option<String>,
+        vesting: Option<Vesting>,
+    },
+
+    /// ExpectedTotal returns the sum of the amounts of all entries
+    /// as given at instantiation.
+    #[returns(ExpectedTotalResponse)]
+    ExpectedTotal {},
+
+    /// HasClaimedBatch returns whether each of the given entries of
+    /// an address and an amount has been claimed, in the same order.
+    /// An entry counts as claimed once any of its amount is claimed.
+    /// The denom of the amount is that of the entry in a multi-denom drop,
+    /// and empty for the entries without a denom.
+    /// At most MAX_HAS_CLAIMED_BATCH_SIZE entries can be queried at once.
+    #[returns(HasClaimedBatchResponse)]
+    HasClaimedBatch { entries: Vec<(String, Coin)> },
+
+    /// GetAmount returns the amount set by the owner for the given address
+    /// of an address-only drop, if any.
+    #[returns(GetAmountResponse)]
+    GetAmount { address: String },
+
+    /// ListEntries returns the addresses and the amounts set by the owner of an
+    /// address-only drop, ordered by address, so that the whole drop can be audited
+    /// on chain. Pages start after the address of the last entry of the previous
+    /// page. At most MAX_LIST_ENTRIES_LIMIT entries are returned per page.
+    #[returns(ListEntriesResponse)]
+    ListEntries {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// ExportClaims returns the claim state of every claimed entry, ordered by
+    /// its leaf data, for operators to back up before a migration. Pages start
+    /// after the leaf data of the last claim of the previous page. At most
+    /// MAX_EXPORT_CLAIMS_LIMIT claims are returned per page.
+    #[returns(ExportClaimsResponse)]
+    ExportClaims {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// ProofInfo returns the format of the proofs the contract verifies
+    /// so that front-ends and the CLI can choose the matching proof generator.
+    #[returns(ProofInfoResponse)]
+    ProofInfo {},
+
+    /// DenomAdmin returns whether the owner was the tokenfactory admin
+    /// of the drop denom at the latest VerifyDenomAdmin.
+    #[returns(DenomAdminResponse)]
+    DenomAdmin {},
+
+    /// DropInfo returns the descriptor of the drop: its root, the snapshot
+    /// it was computed from and the number and total amount of its entries.
+    #[returns(DropInfoResponse)]
+    DropInfo {},
+
+    /// ProofAmount verifies the proof of the given entry without claiming it, so
+    /// that a wallet can display the amount its proof makes the address eligible for.
+    /// The amount is bound into the leaf as its hash only, so it cannot be extracted
+    /// from the proof and must be given as in Claim, unless the drop is address-only.
+    #[returns(ProofAmountResponse)]
+    ProofAmount {
+        address: String,
+        amount: Option<Uint128>,
+        denom: Option<String>,
+        vesting: Option<Vesting>,
+        proof: Proof,
+    },
+
+    /// Claimable returns whether the given entry can be claimed with the proof at the
+    /// current block time, checking the claim window, the proof, the claim state and the
+    /// denom cap as Claim does, so that a front-end can check a claim before submitting it.
+    /// The sender and the funds of the claim, e.g. the claim fee, are not checked.
+    #[returns(ClaimableResponse)]
+    Claimable {
+        address: String,
+        amount: Option<Uint128>,
+        denom: Option<String>,
+        vesting: Option<Vesting>,
+        proof: Proof,
+    },
+
+    /// Version returns the contract name and version stored with cw2,
+    /// for tooling to check the deployed version before migrating.
+    #[returns(VersionResponse)]
+    Version {},
+
+    /// RecentFailures returns the latest failed mints of claims, newest first, for
+    /// operators to diagnose systemic failures, e.g. a lost denom admin. At most
+    /// MAX_RECENT_FAILURES failures are kept.
+    #[returns(RecentFailuresResponse)]
+    RecentFailures {},
+
+    /// Refunds returns the tokens of the failed or timed out IBC transfers
+    /// of the claimer that are not withdrawn yet.
+    #[returns(RefundsResponse)]
+    Refunds { claimer_addr: String },
 }
 
 #[cw_serde]
@@ -37,3 +354,158 @@ pub struct GetRootResponse {
 pub struct GetSubdenomResponse {
     pub subdenom: String,
 }
+
+#[cw_serde]
+pub struct IsOwnerResponse {
+    pub is_owner: bool,
+}
+
+#[cw_serde]
+pub struct RemainingResponse {
+    pub remaining: Uint128,
+}
+
+#[cw_serde]
+pub struct ExpectedTotalResponse {
+    pub total_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct HasClaimedBatchResponse {
+    pub has_claimed: Vec<bool>,
+}
+
+#[cw_serde]
+pub struct LeafPreimageResponse {
+    /// preimage is the hex encoded leaf data.
+    pub preimage: String,
+}
+
+/// ProofInfoResponse describes the tree and the proofs of the drop.
+#[cw_serde]
+pub struct ProofInfoResponse {
+    /// version is the version of the proof format the drop was instantiated with.
+    pub version: u32,
+    /// hash is the hash function of the tree, e.g. "sha3-256".
+    pub hash: String,
+    /// arity is the number of children of each branch of the tree.
+    pub arity: u32,
+    /// leaf_encoding is "address_amount" if the leaves are the address and the
+    /// amount separated by "|", see merkle::leaf::encode, "address_amount_u128_bytes"
+    /// if the amount is encoded as its 16 big-endian bytes, see merkle::leaf::with_encoding,
+    /// or "address_only" if the leaves are the addresses only.
+    pub leaf_encoding: String,
+    /// salted is set if the leaves are prefixed with the salt of the drop.
+    pub salted: bool,
+    /// proof_format is the format of the proof string of Claim,
+    /// "json" for the json encoded merkle::proof::Proof.
+    pub proof_format: String,
+}
+
+#[cw_serde]
+pub struct DropInfoResponse {
+    pub merkle_root: String,
+    pub total_amount: Uint128,
+    pub leaf_count: Option<u64>,
+    pub eligibility_height: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ProofAmountResponse {
+    /// valid is set if the proof verifies the entry against the root.
+    pub valid: bool,
+    /// amount is the amount of the entry, claimed or not.
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub status: ClaimStatus,
+}
+
+#[cw_serde]
+pub enum ClaimStatus {
+    /// Claimable the entry can be claimed.
+    Claimable,
+    /// AlreadyClaimed the whole amount of the entry is claimed.
+    AlreadyClaimed,
+    /// NotVested the whole vested amount of the entry is claimed.
+    NotVested,
+    /// Revoked the entry is revoked by the owner.
+    Revoked,
+    /// InvalidProof the proof does not verify the entry against the root.
+    InvalidProof,
+    /// NotStarted the claim window has not started yet.
+    NotStarted,
+    /// Expired the claim window has ended.
+    Expired,
+    /// SubdenomNotSet the entry has no denom and the subdenom of the drop is not set yet.
+    SubdenomNotSet,
+    /// CapReached claiming the entry would exceed the cap of its denom.
+    CapReached,
+    /// DenomNotFound the tokenfactory denom of the entry does not exist.
+    DenomNotFound,
+}
+
+#[cw_serde]
+pub struct VersionResponse {
+    pub contract: String,
+    pub version: String,
+}
+
+#[cw_serde]
+pub struct DenomAdminResponse {
+    /// denom_admin_ok is false until VerifyDenomAdmin finds the owner to be the admin.
+    pub denom_admin_ok: bool,
+}
+
+#[cw_serde]
+pub struct GetAmountResponse {
+    pub amount: Option<Uint128>,
+}
+
+/// ListEntriesResponse is a page of the entries of an address-only drop.
+/// The list is complete once a page has fewer entries than the limit.
+#[cw_serde]
+pub struct ListEntriesResponse {
+    pub entries: Vec<(String, Uint128)>,
+}
+
+/// ExportClaimsResponse is a page of the claim state snapshot.
+/// The snapshot is complete once a page has fewer claims than the limit.
+#[cw_serde]
+pub struct ExportClaimsResponse {
+    pub claims: Vec<ExportedClaim>,
+}
+
+/// ExportedClaim is the claim state of an entry.
+/// claimer_addr and last_claimed_at are not known for
+/// entries claimed before claims were recorded.
+#[cw_serde]
+pub struct ExportedClaim {
+    /// claim is the leaf data of the entry.
+    pub claim: String,
+    /// claimed is the amount claimed so far.
+    pub claimed: Uint128,
+    pub claimer_addr: Option<String>,
+    pub last_claimed_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct RecentFailuresResponse {
+    pub failures: Vec<FailedMint>,
+}
+
+/// FailedMint is a mint of a claim that failed, with the reason given by the chain.
+#[cw_serde]
+pub struct FailedMint {
+    pub claimer_addr: String,
+    pub denom: String,
+    pub reason: String,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct RefundsResponse {
+    pub refunds: Vec<Coin>,
+}
@@ -1,23 +1,46 @@
-use cosmwasm_std::{DepsMut, Reply, Response, SubMsg, SubMsgResponse, SubMsgResult};
+use cosmwasm_std::{
+    BankMsg, DepsMut, Reply, Response, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult,
+    Timestamp, Uint128,
+};
 use osmosis_std::shim::Any;
 use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
 use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
+use osmosis_std::types::ibc::applications::transfer::v1::MsgTransferResponse;
 
-use crate::state::CONFIG;
+use crate::ibc::ibc_transfer_submsg;
+use crate::msg::FailedMint;
+use crate::state::{
+    Config, CONFIG, FAILED_MINTS, FAILED_MINT_COUNT, IBC_TRANSFERS, MINTED_AMOUNTS,
+    PENDING_TRANSFER,
+};
 use crate::{
+    execute::claim::validate_denom_cap,
     execute::set_subdenom::BANK_SEND_TYPE_URL,
-    state::{CLAIMED_ADDRESSES, REPLY_STATE},
+    state::{ClaimRecord, MintReplyState, CLAIMED_AMOUNTS, CLAIM_RECORDS, REPLY_STATE},
     ContractError,
 };
 
 pub const AUTHZ_EXEC_MINT_MSG_ID: u64 = 1;
 pub const AUTHZ_EXEC_SEND_MSG_ID: u64 = 2;
+pub const IBC_TRANSFER_MSG_ID: u64 = 3;
+
+// MAX_RECENT_FAILURES is the number of the latest failed mints kept in FAILED_MINTS.
+pub const MAX_RECENT_FAILURES: u64 = 20;
 
+// handle_mint_reply sends the minted tokens to the claimer, or to the contract
+// to transfer them over IBC if the claim has an IBC destination, and forwards the
+// claim fee to the owner. It errors if the total minted of the denom exceeds its
+// cap, reverting the mint.
+// If the mint failed, the failed mint is reverted but the reply succeeds, so that
+// the failure is kept in FAILED_MINTS and reported in the events of the claim
+// for systemic failures, e.g. a lost denom admin, to be diagnosed. Nothing is
+// claimed and the claim fee is refunded to the sender.
 pub fn handle_mint_reply(
     deps: DepsMut,
     msg: Reply,
     contract_address: String,
+    block_height: u64,
 ) -> Result<Response, ContractError> {
     deps.api.debug("mint reply reached");
 
@@ -27,11 +50,22 @@ pub fn handle_mint_reply(
 
             let mint_reply_state = REPLY_STATE.load(deps.storage, AUTHZ_EXEC_MINT_MSG_ID)?;
 
-            let owner = CONFIG.load(deps.storage)?.owner;
+            let config = CONFIG.load(deps.storage)?;
+            add_minted_amount(
+                deps.storage,
+                &config,
+                &mint_reply_state.denom,
+                mint_reply_state.amount,
+            )?;
+
+            let to_address = match mint_reply_state.ibc_destination {
+                Some(_) => contract_address.clone(),
+                None => mint_reply_state.claimer_addr,
+            };
 
             let msg_send = MsgSend {
-                from_address: owner.to_string(),
-                to_address: mint_reply_state.claimer_addr,
+                from_address: config.owner.to_string(),
+                to_address,
                 amount: vec![Coin {
                     denom: mint_reply_state.denom,
                     amount: mint_reply_state.amount.to_string(),
@@ -50,19 +84,93 @@ pub fn handle_mint_reply(
                 msgs: vec![msg_send_any],
             };
 
-            return Ok(Response::new()
-                .add_submessage(SubMsg::reply_on_success(exec_msg, AUTHZ_EXEC_SEND_MSG_ID))
-                .add_attribute("reply", "tf_mint"));
+            // reply always so that a failed send is reported by handle_send_reply.
+            let mut response = Response::new()
+                .add_submessage(SubMsg::reply_always(exec_msg, AUTHZ_EXEC_SEND_MSG_ID))
+                .add_attribute("reply", "tf_mint");
+            if let Some(fee_msg) = mint_reply_state.fee_msg {
+                response = response.add_message(fee_msg);
+            }
+            Ok(response)
         }
         SubMsgResult::Err(e) => {
             deps.api.debug(&e);
+
+            let mint_reply_state = REPLY_STATE.load(deps.storage, AUTHZ_EXEC_MINT_MSG_ID)?;
+            REPLY_STATE.remove(deps.storage, AUTHZ_EXEC_MINT_MSG_ID);
+
+            let failed_mint = FailedMint {
+                claimer_addr: mint_reply_state.claimer_addr.clone(),
+                denom: mint_reply_state.denom.clone(),
+                reason: e,
+                height: block_height,
+            };
+            add_failed_mint(deps.storage, &failed_mint)?;
+
+            let mut response = Response::new()
+                .add_attribute("reply", "tf_mint_failed")
+                .add_attribute("merkle-drop-denom", failed_mint.denom)
+                .add_attribute("merkle-drop-receiver", failed_mint.claimer_addr)
+                .add_attribute("merkle-drop-failure", failed_mint.reason)
+                .add_attribute("merkle-drop-failure-height", block_height.to_string());
+            if let Some(refund_msg) = fee_refund_msg(&mint_reply_state) {
+                response = response.add_message(refund_msg);
+            }
+            Ok(response)
         }
     }
+}
+
+// add_failed_mint records the failed mint, pruning the
+// failures older than the latest MAX_RECENT_FAILURES.
+fn add_failed_mint(storage: &mut dyn Storage, failed_mint: &FailedMint) -> StdResult<()> {
+    let count = FAILED_MINT_COUNT.may_load(storage)?.unwrap_or_default();
+    FAILED_MINTS.save(storage, count, failed_mint)?;
+    if count >= MAX_RECENT_FAILURES {
+        FAILED_MINTS.remove(storage, count - MAX_RECENT_FAILURES);
+    }
+    FAILED_MINT_COUNT.save(storage, &(count + 1))
+}
+
+// fee_refund_msg returns the message refunding the claim fee of the failed claim
+// to its sender, if the claim paid a fee.
+fn fee_refund_msg(mint_reply_state: &MintReplyState) -> Option<BankMsg> {
+    match &mint_reply_state.fee_msg {
+        Some(BankMsg::Send { amount, .. }) => Some(BankMsg::Send {
+            to_address: mint_reply_state.sender.clone(),
+            amount: amount.clone(),
+        }),
+        _ => None,
+    }
+}
 
-    Err(ContractError::FailedToMint {})
+// add_minted_amount adds the amount to the total minted of the full denom
+// and errors if the total exceeds the cap of its subdenom, if any.
+fn add_minted_amount(
+    storage: &mut dyn Storage,
+    config: &Config,
+    denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let minted = validate_denom_cap(storage, config, denom, amount)?;
+    MINTED_AMOUNTS.save(storage, denom, &minted)?;
+    Ok(())
 }
 
-pub fn handle_send_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+// handle_send_reply adds the sent amount to the claimed amount of the entry
+// once the minted tokens are delivered and records the claim at the block time.
+// If the claim has an IBC destination, the tokens sent to the contract are
+// transferred to the receiver, timing out after the timeout of the destination.
+// The tokens of a failed or timed out transfer are refunded to the claimer by
+// ibc_lifecycle_complete, so the claim is recorded nonetheless.
+// If the send failed, it errors so that the whole claim is reverted and
+// the claimer is not marked as claimed.
+pub fn handle_send_reply(
+    deps: DepsMut,
+    msg: Reply,
+    contract_address: String,
+    block_time: Timestamp,
+) -> Result<Response, ContractError> {
     deps.api.debug("send reply reached");
 
     match msg.result {
@@ -71,21 +179,434 @@ pub fn handle_send_reply(deps: DepsMut, msg: Reply) -> Result<Response, Contract
 
             let mint_reply_state = REPLY_STATE.load(deps.storage, AUTHZ_EXEC_MINT_MSG_ID)?;
 
-            CLAIMED_ADDRESSES.save(deps.storage, &mint_reply_state.claimer_addr, &true)?;
+            CLAIMED_AMOUNTS.update(
+                deps.storage,
+                &mint_reply_state.claim,
+                |claimed| -> StdResult<Uint128> {
+                    Ok(claimed
+                        .unwrap_or_default()
+                        .checked_add(mint_reply_state.amount)?)
+                },
+            )?;
+            CLAIM_RECORDS.save(
+                deps.storage,
+                &mint_reply_state.claim,
+                &ClaimRecord {
+                    claimer_addr: mint_reply_state.claimer_addr.clone(),
+                    last_claimed_at: block_time,
+                },
+            )?;
 
             // Prune mint reply state
             REPLY_STATE.remove(deps.storage, AUTHZ_EXEC_MINT_MSG_ID);
 
-            return Ok(Response::new()
+            let mut response = Response::new()
                 .add_attribute("reply", "send")
-                .add_attribute("merkle-drop-denom", mint_reply_state.denom)
-                .add_attribute("merkle-drop-amount", mint_reply_state.amount.to_string())
-                .add_attribute("merkle-drop-receiver", mint_reply_state.claimer_addr));
+                .add_attribute("merkle-drop-denom", &mint_reply_state.denom)
+                .add_attribute("merkle-drop-amount", mint_reply_state.amount.to_string());
+
+            match mint_reply_state.ibc_destination {
+                Some(ibc_destination) => {
+                    let transfer_msg = ibc_transfer_submsg(
+                        deps.storage,
+                        &contract_address,
+                        block_time,
+                        &mint_reply_state.claimer_addr,
+                        &ibc_destination,
+                        cosmwasm_std::Coin::new(
+                            mint_reply_state.amount.u128(),
+                            mint_reply_state.denom,
+                        ),
+                    )?;
+                    response = response
+                        .add_submessage(transfer_msg)
+                        .add_attribute("merkle-drop-receiver", ibc_destination.receiver)
+                        .add_attribute("merkle-drop-ibc-channel", ibc_destination.channel);
+                }
+                None => {
+                    response = response
+                        .add_attribute("merkle-drop-receiver", mint_reply_state.claimer_addr);
+                }
+            }
+            Ok(response)
         }
         SubMsgResult::Err(e) => {
             deps.api.debug(&e);
+            Err(ContractError::FailedToSend { reason: e })
+        }
+    }
+}
+
+// handle_transfer_reply keys the pending transfer by the sequence of its packet,
+// for ibc_lifecycle_complete to find it once the transfer completes.
+pub fn handle_transfer_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    deps.api.debug("transfer reply reached");
+
+    let data = match msg.result {
+        SubMsgResult::Ok(SubMsgResponse {
+            data: Some(data), ..
+        }) => data,
+        SubMsgResult::Ok(SubMsgResponse { data: None, .. }) => {
+            return Err(ContractError::FailedToTransfer {
+                reason: String::from("missing transfer response"),
+            })
+        }
+        SubMsgResult::Err(e) => return Err(ContractError::FailedToTransfer { reason: e }),
+    };
+    let transfer_response: MsgTransferResponse = data.try_into()?;
+
+    let transfer = PENDING_TRANSFER.load(deps.storage)?;
+    PENDING_TRANSFER.remove(deps.storage);
+    IBC_TRANSFERS.save(
+        deps.storage,
+        (&transfer.channel, transfer_response.sequence),
+        &transfer,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("reply", "ibc_transfer")
+        .add_attribute(
+            "merkle-drop-ibc-sequence",
+            transfer_response.sequence.to_string(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execute::claim::DEFAULT_IBC_TIMEOUT_SECONDS;
+    use crate::msg::IbcDestination;
+    use crate::state::IbcTransfer;
+    use crate::testing::test_config;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{attr, coins, Binary, Order};
+    use osmosis_std::types::ibc::applications::transfer::v1::MsgTransfer;
+
+    const CLAIMER_ADDR: &str = "claimer";
+    const CLAIM: &str = "claimer100";
+
+    // reply_ok returns the reply of the successful submessage with the given id and data.
+    fn reply_ok(id: u64, data: Option<Binary>) -> Reply {
+        Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data,
+            }),
+        }
+    }
+
+    // reply_err returns the reply of the submessage with the given id failed with the error.
+    fn reply_err(id: u64, error: &str) -> Reply {
+        Reply {
+            id,
+            result: SubMsgResult::Err(String::from(error)),
+        }
+    }
+
+    fn save_mint_reply_state(deps: DepsMut) {
+        REPLY_STATE
+            .save(
+                deps.storage,
+                AUTHZ_EXEC_MINT_MSG_ID,
+                &MintReplyState {
+                    claimer_addr: String::from(CLAIMER_ADDR),
+                    amount: Uint128::new(100),
+                    denom: String::from("factory/owner/subdenom"),
+                    claim: String::from(CLAIM),
+                    ibc_destination: None,
+                    sender: String::from(CLAIMER_ADDR),
+                    fee_msg: None,
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn handle_send_reply_success() {
+        let mut deps = mock_dependencies();
+        save_mint_reply_state(deps.as_mut());
+
+        let msg = reply_ok(AUTHZ_EXEC_SEND_MSG_ID, None);
+
+        handle_send_reply(
+            deps.as_mut(),
+            msg.clone(),
+            String::from("contract"),
+            Timestamp::from_seconds(10),
+        )
+        .unwrap();
+
+        let claimed = CLAIMED_AMOUNTS
+            .may_load(deps.as_ref().storage, CLAIM)
+            .unwrap();
+        assert_eq!(Some(Uint128::new(100)), claimed);
+
+        let record = CLAIM_RECORDS.load(deps.as_ref().storage, CLAIM).unwrap();
+        assert_eq!(
+            ClaimRecord {
+                claimer_addr: String::from(CLAIMER_ADDR),
+                last_claimed_at: Timestamp::from_seconds(10),
+            },
+            record
+        );
+
+        let reply_state = REPLY_STATE
+            .may_load(deps.as_ref().storage, AUTHZ_EXEC_MINT_MSG_ID)
+            .unwrap();
+        assert!(reply_state.is_none());
+
+        // a second partial claim accumulates.
+        save_mint_reply_state(deps.as_mut());
+        handle_send_reply(
+            deps.as_mut(),
+            msg,
+            String::from("contract"),
+            Timestamp::from_seconds(20),
+        )
+        .unwrap();
+
+        let claimed = CLAIMED_AMOUNTS
+            .may_load(deps.as_ref().storage, CLAIM)
+            .unwrap();
+        assert_eq!(Some(Uint128::new(200)), claimed);
+
+        let record = CLAIM_RECORDS.load(deps.as_ref().storage, CLAIM).unwrap();
+        assert_eq!(Timestamp::from_seconds(20), record.last_claimed_at);
+    }
+
+    #[test]
+    fn handle_send_reply_ibc_transfer() {
+        let mut deps = mock_dependencies();
+        REPLY_STATE
+            .save(
+                deps.as_mut().storage,
+                AUTHZ_EXEC_MINT_MSG_ID,
+                &MintReplyState {
+                    claimer_addr: String::from(CLAIMER_ADDR),
+                    amount: Uint128::new(100),
+                    denom: String::from("factory/owner/subdenom"),
+                    claim: String::from(CLAIM),
+                    ibc_destination: Some(IbcDestination {
+                        channel: String::from("channel-0"),
+                        receiver: String::from("cosmos1receiver"),
+                        timeout_seconds: None,
+                    }),
+                    sender: String::from(CLAIMER_ADDR),
+                    fee_msg: None,
+                },
+            )
+            .unwrap();
+
+        let msg = reply_ok(AUTHZ_EXEC_SEND_MSG_ID, None);
+
+        let res = handle_send_reply(
+            deps.as_mut(),
+            msg,
+            String::from("contract"),
+            Timestamp::from_seconds(10),
+        )
+        .unwrap();
+
+        // the tokens sent to the contract are transferred to the receiver,
+        // with the callback of the contract.
+        assert_eq!(
+            vec![SubMsg::reply_on_success(
+                MsgTransfer {
+                    source_port: String::from("transfer"),
+                    source_channel: String::from("channel-0"),
+                    token: Some(Coin {
+                        denom: String::from("factory/owner/subdenom"),
+                        amount: String::from("100"),
+                    }),
+                    sender: String::from("contract"),
+                    receiver: String::from("cosmos1receiver"),
+                    timeout_height: None,
+                    timeout_timestamp: Timestamp::from_seconds(10 + DEFAULT_IBC_TIMEOUT_SECONDS)
+                        .nanos(),
+                    memo: String::from(r#"{"ibc_callback":"contract"}"#),
+                },
+                IBC_TRANSFER_MSG_ID
+            )],
+            res.messages
+        );
+
+        // the transfer is tracked by its sequence.
+        let data: Binary = MsgTransferResponse { sequence: 7 }.into();
+        handle_transfer_reply(deps.as_mut(), reply_ok(IBC_TRANSFER_MSG_ID, Some(data))).unwrap();
+        assert_eq!(
+            IbcTransfer {
+                claimer_addr: String::from(CLAIMER_ADDR),
+                channel: String::from("channel-0"),
+                amount: cosmwasm_std::Coin::new(100, "factory/owner/subdenom"),
+            },
+            IBC_TRANSFERS
+                .load(deps.as_ref().storage, ("channel-0", 7))
+                .unwrap()
+        );
+        assert!(PENDING_TRANSFER
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+
+        let claimed = CLAIMED_AMOUNTS
+            .may_load(deps.as_ref().storage, CLAIM)
+            .unwrap();
+        assert_eq!(Some(Uint128::new(100)), claimed);
+    }
+
+    #[test]
+    fn handle_mint_reply_denom_cap() {
+        let mut deps = mock_dependencies();
+        let config = Config {
+            total_amount: Uint128::new(1000),
+            denom_caps: vec![cosmwasm_std::Coin::new(250, "subdenom")],
+            ..test_config()
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let msg = reply_ok(AUTHZ_EXEC_MINT_MSG_ID, None);
+
+        // claims of 100 approach the cap of 250.
+        for minted in [100, 200] {
+            save_mint_reply_state(deps.as_mut());
+            handle_mint_reply(deps.as_mut(), msg.clone(), String::from("contract"), 1).unwrap();
+            assert_eq!(
+                Uint128::new(minted),
+                MINTED_AMOUNTS
+                    .load(deps.as_ref().storage, "factory/owner/subdenom")
+                    .unwrap()
+            );
         }
+
+        save_mint_reply_state(deps.as_mut());
+        let err = handle_mint_reply(deps.as_mut(), msg, String::from("contract"), 1).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContractError::DenomCapExceeded { ref denom, cap, minted }
+                    if denom == "factory/owner/subdenom" && cap.u128() == 250 && minted.u128() == 300
+            ),
+            "{:?}",
+            err
+        );
+        assert_eq!(
+            Uint128::new(200),
+            MINTED_AMOUNTS
+                .load(deps.as_ref().storage, "factory/owner/subdenom")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn handle_mint_reply_failed_mint() {
+        let mut deps = mock_dependencies();
+        REPLY_STATE
+            .save(
+                deps.as_mut().storage,
+                AUTHZ_EXEC_MINT_MSG_ID,
+                &MintReplyState {
+                    claimer_addr: String::from(CLAIMER_ADDR),
+                    amount: Uint128::new(100),
+                    denom: String::from("factory/owner/subdenom"),
+                    claim: String::from(CLAIM),
+                    ibc_destination: None,
+                    sender: String::from("sender"),
+                    fee_msg: Some(BankMsg::Send {
+                        to_address: String::from("owner"),
+                        amount: coins(10, "uosmo"),
+                    }),
+                },
+            )
+            .unwrap();
+
+        let msg = reply_err(AUTHZ_EXEC_MINT_MSG_ID, "unauthorized account");
+
+        let res = handle_mint_reply(deps.as_mut(), msg, String::from("contract"), 12345).unwrap();
+
+        // the failure is reported and the claim fee refunded.
+        assert_eq!(
+            vec![
+                attr("reply", "tf_mint_failed"),
+                attr("merkle-drop-denom", "factory/owner/subdenom"),
+                attr("merkle-drop-receiver", CLAIMER_ADDR),
+                attr("merkle-drop-failure", "unauthorized account"),
+                attr("merkle-drop-failure-height", "12345"),
+            ],
+            res.attributes
+        );
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: String::from("sender"),
+                amount: coins(10, "uosmo"),
+            })],
+            res.messages
+        );
+
+        assert_eq!(
+            vec![(
+                0,
+                FailedMint {
+                    claimer_addr: String::from(CLAIMER_ADDR),
+                    denom: String::from("factory/owner/subdenom"),
+                    reason: String::from("unauthorized account"),
+                    height: 12345,
+                }
+            )],
+            FAILED_MINTS
+                .range(deps.as_ref().storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap()
+        );
+        assert!(!CLAIMED_AMOUNTS.has(deps.as_ref().storage, CLAIM));
+        assert!(!REPLY_STATE.has(deps.as_ref().storage, AUTHZ_EXEC_MINT_MSG_ID));
     }
 
-    Err(ContractError::FailedToMint {})
+    #[test]
+    fn handle_mint_reply_failed_mints_pruned() {
+        let mut deps = mock_dependencies();
+
+        for height in 0..MAX_RECENT_FAILURES + 5 {
+            save_mint_reply_state(deps.as_mut());
+            let msg = reply_err(AUTHZ_EXEC_MINT_MSG_ID, "unauthorized account");
+            let res =
+                handle_mint_reply(deps.as_mut(), msg, String::from("contract"), height).unwrap();
+            assert!(res.messages.is_empty());
+        }
+
+        // only the latest failures are kept.
+        let heights = FAILED_MINTS
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, failed_mint)| failed_mint.height))
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!((5..MAX_RECENT_FAILURES + 5).collect::<Vec<_>>(), heights);
+    }
+
+    #[test]
+    fn handle_send_reply_failed_send() {
+        let mut deps = mock_dependencies();
+        save_mint_reply_state(deps.as_mut());
+
+        let msg = reply_err(AUTHZ_EXEC_SEND_MSG_ID, "insufficient funds");
+
+        let err = handle_send_reply(
+            deps.as_mut(),
+            msg,
+            String::from("contract"),
+            Timestamp::from_seconds(10),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::FailedToSend { ref reason } if reason == "insufficient funds"),
+            "{:?}",
+            err
+        );
+
+        let claimed = CLAIMED_AMOUNTS
+            .may_load(deps.as_ref().storage, CLAIM)
+            .unwrap();
+        assert!(claimed.is_none());
+        assert!(!CLAIM_RECORDS.has(deps.as_ref().storage, CLAIM));
+    }
 }
@@ -1,12 +1,45 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, BankMsg, Binary, Coin, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
+use merkle::leaf::LeafEncoding;
+
+use crate::msg::{FailedMint, IbcDestination};
 
 #[cw_serde]
 pub struct Config {
     pub merkle_root: String,
     pub owner: Addr,
+    pub total_amount: Uint128,
+    pub salt: Option<Binary>,
+    // root_frozen is set once the root can no longer be updated.
+    pub root_frozen: bool,
+    pub claim_start: Option<Timestamp>,
+    pub claim_deadline: Option<Timestamp>,
+    pub claim_fee: Option<Coin>,
+    // address_only is set if the leaves are the addresses only
+    // and the amounts are kept in AMOUNTS.
+    pub address_only: bool,
+    // proof_version is the merkle::proof::VERSION the drop was instantiated with.
+    pub proof_version: u32,
+    // denom_admin_ok is set if the owner was the tokenfactory admin of the
+    // drop denom at the latest VerifyDenomAdmin.
+    pub denom_admin_ok: bool,
+    // denom_caps are the caps of the total amount minted of the subdenoms.
+    pub denom_caps: Vec<Coin>,
+    // eligibility_height and leaf_count describe the snapshot
+    // and the tree of the drop as given at instantiation.
+    pub eligibility_height: Option<u64>,
+    pub leaf_count: Option<u64>,
+    // max_proof_depth is the maximum number of entries of a claim's proof, if any.
+    pub max_proof_depth: Option<u32>,
+    // require_self_claim is set if claims must be sent by the claimer itself.
+    pub require_self_claim: bool,
+    // leaf_encoding is the encoding of the amounts in the leaves.
+    pub leaf_encoding: LeafEncoding,
+    // vesting_start is the start of the vesting schedules bound into the leaves,
+    // the claim start if any, else the instantiation time.
+    pub vesting_start: Timestamp,
 }
 
 #[cw_serde]
@@ -14,13 +47,72 @@ pub struct MintReplyState {
     pub claimer_addr: String,
     pub amount: Uint128,
     pub denom: String,
+    // claim is the leaf data of the claimed entry, used as the key of CLAIMED_AMOUNTS.
+    pub claim: String,
+    // ibc_destination is set if the minted tokens are transferred over IBC.
+    pub ibc_destination: Option<IbcDestination>,
+    // sender is the sender of the claim, refunded the claim fee if the mint fails.
+    pub sender: String,
+    // fee_msg forwards the claim fee to the owner once the mint succeeds.
+    pub fee_msg: Option<BankMsg>,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
 pub const SUBDENOM: Item<String> = Item::new("subdenom");
 
-pub const CLAIMED_ADDRESSES: Map<&str, bool> = Map::new("claimed");
+// CLAIMED_AMOUNTS maps the leaf data of an entry to the amount claimed so far.
+// "claimed" is not reused, it holds the claimed flags of earlier versions.
+pub const CLAIMED_AMOUNTS: Map<&str, Uint128> = Map::new("claimed_amounts");
+
+// MINTED_AMOUNTS maps the full denom to the total amount minted by claims.
+pub const MINTED_AMOUNTS: Map<&str, Uint128> = Map::new("minted");
+
+// AMOUNTS maps the addresses of an address-only drop to the amounts set by the owner.
+pub const AMOUNTS: Map<&Addr, Uint128> = Map::new("amounts");
+
+// ClaimRecord is the claimer of an entry and the time of its latest claim,
+// kept alongside CLAIMED_AMOUNTS so that the claim state can be exported.
+#[cw_serde]
+pub struct ClaimRecord {
+    pub claimer_addr: String,
+    pub last_claimed_at: Timestamp,
+}
+
+// CLAIM_RECORDS maps the leaf data of an entry to its claim record.
+pub const CLAIM_RECORDS: Map<&str, ClaimRecord> = Map::new("claim_records");
+
+// REVOKED_CLAIMS contains the leaf data of the entries revoked by the owner.
+pub const REVOKED_CLAIMS: Map<&str, bool> = Map::new("revoked");
 
 // MINT_REPLY_STATE persists data from tf mint message creation until the reply receipt.
 pub const REPLY_STATE: Map<u64, MintReplyState> = Map::new("mint_reply_state");
+
+// FAILED_MINTS maps the sequence number of the failed mints of claims to the failures.
+// Only the latest MAX_RECENT_FAILURES failures are kept.
+pub const FAILED_MINTS: Map<u64, FailedMint> = Map::new("failed_mints");
+
+// FAILED_MINT_COUNT is the number of failed mints so far.
+pub const FAILED_MINT_COUNT: Item<u64> = Item::new("failed_mint_count");
+
+// IbcTransfer is a transfer of claimed tokens to the IBC destination of the claimer.
+#[cw_serde]
+pub struct IbcTransfer {
+    pub claimer_addr: String,
+    pub channel: String,
+    pub amount: Coin,
+}
+
+// PENDING_TRANSFER is the transfer being sent until its sequence is known from the reply.
+pub const PENDING_TRANSFER: Item<IbcTransfer> = Item::new("pending_transfer");
+
+// IBC_TRANSFERS maps the channel and the sequence of the transfers in flight to the
+// transfers, until the ibc-hooks callback reports their acknowledgement or timeout.
+pub const IBC_TRANSFERS: Map<(&str, u64), IbcTransfer> = Map::new("ibc_transfers");
+
+// REFUNDS maps the claimer and the denom to the amount of the failed or timed out
+// transfers of the claimer, returned to the contract and withdrawn with WithdrawRefund.
+pub const REFUNDS: Map<(&str, &str), Uint128> = Map::new("refunds");
+
+// REFUND_TOTALS maps the denom to the total of its REFUNDS, which cannot be swept.
+pub const REFUND_TOTALS: Map<&str, Uint128> = Map::new("refund_totals");
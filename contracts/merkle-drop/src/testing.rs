@@ -0,0 +1,86 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_slice, to_binary, Addr, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Timestamp, Uint128,
+};
+use merkle::leaf::LeafEncoding;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
+    DenomAuthorityMetadata, QueryDenomAuthorityMetadataResponse,
+};
+use std::marker::PhantomData;
+
+use crate::state::Config;
+
+// test_config returns the config of a drop of the owner "owner" without
+// any of the optional settings. Tests override the fields they depend on.
+pub(crate) fn test_config() -> Config {
+    Config {
+        merkle_root: String::from("root"),
+        owner: Addr::unchecked("owner"),
+        total_amount: Uint128::new(100),
+        salt: None,
+        root_frozen: false,
+        claim_start: None,
+        claim_deadline: None,
+        claim_fee: None,
+        address_only: false,
+        proof_version: u32::from(merkle::proof::VERSION),
+        denom_admin_ok: false,
+        denom_caps: vec![],
+        eligibility_height: None,
+        leaf_count: None,
+        max_proof_depth: None,
+        require_self_claim: false,
+        leaf_encoding: LeafEncoding::DecimalString,
+        vesting_start: Timestamp::from_seconds(0),
+    }
+}
+
+// TokenfactoryMockQuerier answers the stargate denom authority metadata
+// queries with the given admin and the other queries with the MockQuerier.
+pub(crate) struct TokenfactoryMockQuerier {
+    base: MockQuerier,
+    pub(crate) admin: Option<String>,
+}
+
+impl Querier for TokenfactoryMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(request) => request,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: e.to_string(),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        match request {
+            QueryRequest::Stargate { .. } => {
+                let response = QueryDenomAuthorityMetadataResponse {
+                    authority_metadata: self
+                        .admin
+                        .clone()
+                        .map(|admin| DenomAuthorityMetadata { admin }),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+            }
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
+
+// mock_dependencies_with_denom_admin returns the mock dependencies of a chain where
+// every tokenfactory denom has the given admin, or no authority metadata if none.
+pub(crate) fn mock_dependencies_with_denom_admin(
+    admin: Option<&str>,
+) -> OwnedDeps<MockStorage, MockApi, TokenfactoryMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: TokenfactoryMockQuerier {
+            base: MockQuerier::default(),
+            admin: admin.map(String::from),
+        },
+        custom_query_type: PhantomData,
+    }
+}
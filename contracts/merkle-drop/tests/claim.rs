@@ -1,14 +1,20 @@
 mod test_env;
-use cosmwasm_std::{Coin, Uint128};
+use cosmwasm_std::Uint128;
+use merkle::leaf;
 use merkle_drop::msg::ExecuteMsg;
+use osmosis_std::types::cosmwasm::wasm::v1::MsgExecuteContractResponse;
 use osmosis_testing::{
     cosmrs::proto::cosmos::bank::v1beta1::{QueryBalanceRequest, QueryBalanceResponse},
-    Module, Runner, Wasm,
+    Module, OsmosisTestApp, Runner, RunnerExecuteResult, Wasm,
 };
 use test_env::*;
 
-const VALID_PROOF_STR: &str = "[{\"is_left_sibling\":true,\"hash\":[89,79,106,114,49,69,77,102,68,119,114,48,69,84,73,103,82,71,97,108,48,79,108,53,105,56,82,103,111,57,85,51,76,70,82,90,115,66,97,78,89,51,73,61]},{\"is_left_sibling\":false,\"hash\":[80,54,110,55,43,55,72,72,111,52,109,104,79,104,102,105,108,83,43,118,87,54,88,85,88,113,48,115,105,99,83,116,116,52,112,54,119,114,68,48,113,47,73,61]},{\"is_left_sibling\":true,\"hash\":[79,79,110,66,86,100,72,56,121,84,70,57,115,78,65,56,80,85,81,97,111,71,89,119,81,89,87,83,109,71,116,89,56,79,118,85,118,98,73,83,122,74,77,61]},{\"is_left_sibling\":false,\"hash\":[102,65,68,121,57,69,49,118,56,70,78,78,81,53,109,47,50,120,78,55,103,110,119,89,78,82,104,80,83,53,69,105,79,53,115,79,77,43,118,106,50,98,56,61]}]";
+const VALID_PROOF_STR: &str = "[{\"is_left_sibling\":false,\"hash\":[107,81,47,117,57,77,98,104,49,108,102,109,65,101,79,88,99,89,110,86,88,84,72,74,105,107,116,68,77,55,66,120,85,100,120,108,117,53,78,120,85,114,56,61]},{\"is_left_sibling\":true,\"hash\":[101,48,117,68,51,77,104,98,119,108,120,105,110,106,54,53,71,48,101,100,115,82,106,89,54,68,89,76,70,99,120,119,87,65,55,97,78,101,101,57,104,69,119,61]},{\"is_left_sibling\":true,\"hash\":[77,100,48,79,78,102,99,83,66,83,76,54,109,66,113,87,83,67,84,111,105,77,86,55,84,122,67,87,72,100,99,105,111,79,74,66,88,51,99,71,115,83,103,61]},{\"is_left_sibling\":false,\"hash\":[53,119,49,47,69,51,84,90,88,49,81,79,49,112,74,122,89,122,75,68,49,111,65,121,48,70,70,75,120,52,56,102,111,52,53,83,74,99,116,70,73,56,115,61]}]";
 const TO_VERIFY_VALID2_ADDR_AMOUNT: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+// SINGLE_LEAF_ROOT is the root of a drop with the single entry of
+// TO_VERIFY_VALID2_ADDR_AMOUNT and 1421901, i.e. the hash of its leaf.
+const SINGLE_LEAF_ROOT: &str = "gtZbsHNbDfC5GtBQHtw/Tfjvdgjac5IWUH2x8GucUUc=";
+const OTHER_ADDR: &str = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
 
 test_claim!(
     claim
@@ -18,6 +24,63 @@ test_claim!(
     amount: Uint128::from(1421901 as u128)
 );
 
+test_claim!(
+    claim_with_note
+    should succeed,
+
+    proof: String::from(VALID_PROOF_STR),
+    amount: Uint128::from(1421901 as u128),
+    note: Some(String::from("referral"))
+);
+
+test_claim!(
+    claim_typed
+    should succeed typed,
+
+    proof: String::from(VALID_PROOF_STR),
+    amount: Uint128::from(1421901 as u128)
+);
+
+test_claim!(
+    claim_frozen_root
+    should succeed frozen,
+
+    proof: String::from(VALID_PROOF_STR),
+    amount: Uint128::from(1421901 as u128)
+);
+
+test_claim!(
+    claim_partial
+    should succeed partially,
+
+    proof: String::from(VALID_PROOF_STR),
+    amount: Uint128::from(1421901 as u128),
+    requested: vec![Uint128::from(1000000 as u128), Uint128::from(421901 as u128)]
+);
+
+test_claim!(
+    claim_revoked
+    should fail revoked,
+
+    proof: String::from(VALID_PROOF_STR),
+    amount: Uint128::from(1421901 as u128)
+);
+
+test_claim!(
+    claim_single_leaf
+    should succeed single leaf,
+
+    proof: String::new(),
+    amount: Uint128::from(1421901 as u128)
+);
+
+test_claim!(
+    claim_address_only
+    should succeed address only,
+
+    amount: Uint128::from(1421901 as u128)
+);
+
 // ======= helpers ========
 
 #[macro_export]
@@ -25,52 +88,288 @@ macro_rules! test_claim {
     ($test_name:ident should succeed, proof: $proof:expr, amount: $amount:expr) => {
         #[test]
         fn $test_name() {
-            test_claim_success_case($proof, $amount)
+            test_claim_success_case(ClaimBuilder::new($proof), $amount)
+        }
+    };
+    ($test_name:ident should succeed, proof: $proof:expr, amount: $amount:expr, note: $note:expr) => {
+        #[test]
+        fn $test_name() {
+            test_claim_success_case(ClaimBuilder::new($proof).note($note), $amount)
+        }
+    };
+    ($test_name:ident should succeed typed, proof: $proof:expr, amount: $amount:expr) => {
+        #[test]
+        fn $test_name() {
+            test_claim_success_case(ClaimBuilder::new($proof).typed(), $amount)
+        }
+    };
+    ($test_name:ident should succeed frozen, proof: $proof:expr, amount: $amount:expr) => {
+        #[test]
+        fn $test_name() {
+            test_frozen_root_claim_case($proof, $amount)
         }
     };
+    ($test_name:ident should succeed single leaf, proof: $proof:expr, amount: $amount:expr) => {
+        #[test]
+        fn $test_name() {
+            test_single_leaf_claim_case($proof, $amount)
+        }
+    };
+    ($test_name:ident should succeed partially, proof: $proof:expr, amount: $amount:expr, requested: $requested:expr) => {
+        #[test]
+        fn $test_name() {
+            test_partial_claim_case($proof, $amount, $requested)
+        }
+    };
+    ($test_name:ident should succeed address only, amount: $amount:expr) => {
+        #[test]
+        fn $test_name() {
+            test_address_only_claim_case($amount)
+        }
+    };
+    ($test_name:ident should fail revoked, proof: $proof:expr, amount: $amount:expr) => {
+        #[test]
+        fn $test_name() {
+            test_revoked_claim_case($proof, $amount)
+        }
+    };
+}
+
+// ClaimBuilder builds the claim of TO_VERIFY_VALID2_ADDR_AMOUNT with the proof,
+// without any of the optional fields unless they are set.
+#[derive(Clone)]
+struct ClaimBuilder {
+    proof: String,
+    amount: Option<Uint128>,
+    note: Option<String>,
+    requested: Option<Uint128>,
+    typed: bool,
+}
+
+impl ClaimBuilder {
+    fn new(proof: String) -> Self {
+        ClaimBuilder {
+            proof,
+            amount: None,
+            note: None,
+            requested: None,
+            typed: false,
+        }
+    }
+
+    fn amount(mut self, amount: Uint128) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    fn note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    fn requested(mut self, requested: Uint128) -> Self {
+        self.requested = Some(requested);
+        self
+    }
+
+    // typed sends the proof as a typed Proof with ClaimTyped.
+    fn typed(mut self) -> Self {
+        self.typed = true;
+        self
+    }
+
+    fn build(&self) -> ExecuteMsg {
+        let claimer_addr = String::from(TO_VERIFY_VALID2_ADDR_AMOUNT);
+        if self.typed {
+            ExecuteMsg::ClaimTyped {
+                proof: serde_json_wasm::from_str(&self.proof).unwrap(),
+                proof_version: None,
+                amount: self.amount,
+                denom: None,
+                claimer_addr,
+                note: self.note.clone(),
+                vesting: None,
+                requested: self.requested,
+                ibc_destination: None,
+            }
+        } else {
+            ExecuteMsg::Claim {
+                proof: self.proof.clone(),
+                proof_version: None,
+                amount: self.amount,
+                denom: None,
+                claimer_addr,
+                note: self.note.clone(),
+                vesting: None,
+                requested: self.requested,
+                ibc_destination: None,
+            }
+        }
+    }
 }
 
-fn test_claim_success_case(proof: String, amount: Uint128) {
-    let test_env = TestEnv::new();
+// execute_claim sends the claim from the claim sender of the env.
+fn execute_claim(
+    env: &ClaimEnv,
+    claim: &ClaimBuilder,
+) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+    Wasm::new(&env.app).execute(
+        &env.contract_address,
+        &claim.build(),
+        &[],
+        &env.claim_sender,
+    )
+}
 
-    test_env.execute_msg_grant_mint();
-    test_env.execute_msg_grant_bank_send();
+// assert_claim_succeeds sends the claim and checks that the amount is minted
+// to the claimer and that the note, if any, is echoed back as an attribute.
+fn assert_claim_succeeds(env: &ClaimEnv, claim: &ClaimBuilder, amount: Uint128) {
+    let res = execute_claim(env, claim);
 
-    let TestEnv {
-        app,
-        contract_address,
-        owner,
-        full_denom,
-    } = test_env;
+    // check if execution succeeded
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    if let Some(note) = &claim.note {
+        let has_note = res.unwrap().events.iter().any(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "note" && &attr.value == note)
+        });
+        assert!(has_note, "note attribute {} not found", note);
+    }
+
+    assert_eq!(
+        amount,
+        query_balance(&env.app, TO_VERIFY_VALID2_ADDR_AMOUNT, &env.full_denom)
+    );
+}
 
-    let set_subdenom_msg = ExecuteMsg::SetSubDenom {
-        subdenom: String::from(VALID_SUBDENOM),
+fn test_claim_success_case(claim: ClaimBuilder, amount: Uint128) {
+    let env = setup_claim_env(TestEnv::new());
+
+    assert_claim_succeeds(&env, &claim.amount(amount), amount);
+}
+
+fn test_frozen_root_claim_case(proof: String, amount: Uint128) {
+    let env = setup_claim_env(TestEnv::new());
+    let wasm = Wasm::new(&env.app);
+
+    let res = wasm.execute(
+        &env.contract_address,
+        &ExecuteMsg::FreezeRoot {},
+        &[],
+        &env.owner,
+    );
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    let update_root_msg = ExecuteMsg::UpdateRoot {
+        merkle_root: String::from(SINGLE_LEAF_ROOT),
     };
-    // setup denum from owner address
-    let wasm = Wasm::new(&app);
-    let _res = wasm.execute(&contract_address, &set_subdenom_msg, &[], &owner);
+    let res = wasm.execute(&env.contract_address, &update_root_msg, &[], &env.owner);
+    assert!(res.is_err());
+
+    assert_claim_succeeds(&env, &ClaimBuilder::new(proof).amount(amount), amount);
+}
+
+fn test_single_leaf_claim_case(proof: String, amount: Uint128) {
+    let env = setup_claim_env(TestEnv::new_with_root(SINGLE_LEAF_ROOT));
+
+    // the single entry is proven by an empty proof.
+    assert_claim_succeeds(&env, &ClaimBuilder::new(proof).amount(amount), amount);
+}
 
-    // claim from a new address
-    let initial_balance = [Coin::new(1_000_000_000_000, "uosmo")];
-    let claim_sender = app.init_account(&initial_balance).unwrap();
+fn test_partial_claim_case(proof: String, amount: Uint128, requested: Vec<Uint128>) {
+    let env = setup_claim_env(TestEnv::new());
+    let claim = ClaimBuilder::new(proof).amount(amount);
+
+    let mut expected_amount = Uint128::zero();
+    for requested in requested {
+        expected_amount += requested;
+        assert_claim_succeeds(&env, &claim.clone().requested(requested), expected_amount);
+    }
+
+    // claiming more than the remaining amount fails.
+    let res = execute_claim(&env, &claim.requested(Uint128::new(1)));
+    assert!(res.is_err());
+    assert_eq!(
+        expected_amount,
+        query_balance(&env.app, TO_VERIFY_VALID2_ADDR_AMOUNT, &env.full_denom)
+    );
+}
 
+fn test_address_only_claim_case(amount: Uint128) {
     let claimer_addr = String::from(TO_VERIFY_VALID2_ADDR_AMOUNT);
 
-    let msg = ExecuteMsg::Claim {
-        proof: proof,
-        amount: amount,
-        claimer_addr: claimer_addr.clone(),
+    // the tree of an address-only drop is built of the addresses only.
+    let leaves = vec![
+        leaf::encode_address_only(&claimer_addr),
+        leaf::encode_address_only(OTHER_ADDR),
+    ];
+    let tree = merkle::Tree::new(&leaves);
+    let root = tree.get_root().unwrap().to_string();
+    let proof = serde_json_wasm::to_string(&tree.find_proof(&leaves[0]).unwrap()).unwrap();
+
+    let env = setup_claim_env(TestEnv::new_address_only(&root));
+
+    let set_amounts_msg = ExecuteMsg::SetAmounts {
+        amounts: vec![(claimer_addr, amount)],
     };
+    let res = Wasm::new(&env.app).execute(&env.contract_address, &set_amounts_msg, &[], &env.owner);
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
 
-    let wasm = Wasm::new(&app);
-    let res = wasm.execute(&contract_address, &msg, &[], &claim_sender);
+    // the amount is looked up by the contract.
+    let claim = ClaimBuilder::new(proof);
+    let res = execute_claim(&env, &claim);
 
     // check if execution succeeded
     assert!(res.is_ok(), "{:?}", res.unwrap_err());
 
+    // check that the resolved amount is reported as an attribute
+    let has_amount = res.unwrap().events.iter().any(|event| {
+        event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "amount" && attr.value == amount.to_string())
+    });
+    assert!(has_amount, "amount attribute {} not found", amount);
+
+    assert_eq!(
+        amount,
+        query_balance(&env.app, TO_VERIFY_VALID2_ADDR_AMOUNT, &env.full_denom)
+    );
+
+    // the stored amount is claimed in full, so a second claim is rejected.
+    let err = execute_claim(&env, &claim).unwrap_err();
+    assert!(err.to_string().contains("already claimed"), "{:?}", err);
+    assert_eq!(
+        amount,
+        query_balance(&env.app, TO_VERIFY_VALID2_ADDR_AMOUNT, &env.full_denom)
+    );
+}
+
+fn test_revoked_claim_case(proof: String, amount: Uint128) {
+    let env = setup_claim_env(TestEnv::new());
+
+    // revoke the entry from the owner address
+    let revoke_msg = ExecuteMsg::RevokeClaim {
+        address: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+        amount,
+        denom: None,
+        vesting: None,
+    };
+    let res = Wasm::new(&env.app).execute(&env.contract_address, &revoke_msg, &[], &env.owner);
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    // the proof is valid but the entry is revoked
+    let err = execute_claim(&env, &ClaimBuilder::new(proof).amount(amount)).unwrap_err();
+    assert!(err.to_string().contains("revoked"), "{:?}", err);
+}
+
+fn query_balance(app: &OsmosisTestApp, address: &str, denom: &str) -> Uint128 {
     let balances_query = QueryBalanceRequest {
-        denom: full_denom.clone(),
-        address: claimer_addr.clone(),
+        denom: String::from(denom),
+        address: String::from(address),
     };
 
     let balance = app
@@ -82,8 +381,7 @@ fn test_claim_success_case(proof: String, amount: Uint128) {
         .balance
         .unwrap();
 
-    let actual_amount = Uint128::from(balance.amount.parse::<u128>().unwrap());
+    assert_eq!(denom, balance.denom);
 
-    assert_eq!(full_denom, balance.denom);
-    assert_eq!(amount, actual_amount);
+    Uint128::from(balance.amount.parse::<u128>().unwrap())
 }
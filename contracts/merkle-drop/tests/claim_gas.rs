@@ -0,0 +1,89 @@
+mod test_env;
+use cosmwasm_std::Uint128;
+use merkle::{hash, leaf, proof::Proof};
+use merkle_drop::msg::ExecuteMsg;
+use osmosis_testing::{Module, Wasm};
+use test_env::*;
+
+const CLAIMER_ADDR: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+const AMOUNT: u128 = 1421901;
+
+// PROOF_DEPTHS are the proof lengths of drops of about a thousand,
+// thirty thousand and a million entries.
+const PROOF_DEPTHS: [usize; 3] = [10, 15, 20];
+
+// MAX_PER_LEVEL_GAS_DEVIATION is the tolerated relative difference
+// between the gas per proof level measured over different depths.
+const MAX_PER_LEVEL_GAS_DEVIATION: f64 = 0.25;
+
+#[test]
+fn claim_gas_is_linear_in_proof_depth() {
+    let gas_used: Vec<u64> = PROOF_DEPTHS
+        .iter()
+        .map(|&depth| claim_gas_used(depth))
+        .collect();
+
+    // the cost of a claim grows by the same amount for every level of the proof.
+    let per_level_gas: Vec<f64> = (1..PROOF_DEPTHS.len())
+        .map(|i| {
+            assert!(gas_used[i] > gas_used[i - 1], "{:?}", gas_used);
+            (gas_used[i] - gas_used[i - 1]) as f64 / (PROOF_DEPTHS[i] - PROOF_DEPTHS[i - 1]) as f64
+        })
+        .collect();
+
+    let deviation = (per_level_gas[1] - per_level_gas[0]).abs() / per_level_gas[0];
+    assert!(
+        deviation < MAX_PER_LEVEL_GAS_DEVIATION,
+        "gas per proof level is not constant: {:?} for the gas used {:?}",
+        per_level_gas,
+        gas_used
+    );
+}
+
+// claim_gas_used returns the gas used by a claim with a proof of the given depth.
+fn claim_gas_used(depth: usize) -> u64 {
+    let (root, proof) = proof_of_depth(depth);
+
+    let env = setup_claim_env(TestEnv::new_with_root(&root.to_string()));
+
+    let claim_msg = ExecuteMsg::ClaimTyped {
+        proof,
+        proof_version: None,
+        amount: Some(Uint128::new(AMOUNT)),
+        denom: None,
+        claimer_addr: String::from(CLAIMER_ADDR),
+        note: None,
+        vesting: None,
+        requested: None,
+        ibc_destination: None,
+    };
+    let res =
+        Wasm::new(&env.app).execute(&env.contract_address, &claim_msg, &[], &env.claim_sender);
+
+    res.unwrap().gas_info.gas_used
+}
+
+// proof_of_depth returns a proof with the given number of entries for the
+// claim of AMOUNT by CLAIMER_ADDR and the root it verifies against.
+// Building the tree of a million entries is avoided by folding the leaf
+// with made-up siblings instead.
+fn proof_of_depth(depth: usize) -> (hash::Hash, Proof) {
+    let claim = leaf::encode(CLAIMER_ADDR, &AMOUNT.to_string(), None);
+
+    let mut proof = Proof::default();
+    let mut root = hash::leaf(claim.as_bytes());
+    for level in 0..depth {
+        let sibling = hash::leaf(format!("sibling{}", level).as_bytes());
+        let is_left_sibling = level % 2 == 0;
+
+        root = if is_left_sibling {
+            hash::branch(&sibling, &root)
+        } else {
+            hash::branch(&root, &sibling)
+        };
+        proof.push(is_left_sibling, sibling);
+    }
+    assert!(proof.verify(&claim, &root));
+
+    (root, proof)
+}
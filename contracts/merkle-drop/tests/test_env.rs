@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
-use cosmwasm_std::Coin;
-use merkle_drop::msg::InstantiateMsg;
+use cosmwasm_std::{Coin, Uint128};
+use merkle_drop::msg::{ExecuteMsg, InstantiateMsg};
 use osmosis_std::types::osmosis::tokenfactory;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgCreateDenom, MsgCreateDenomResponse};
 use osmosis_testing::{cosmrs::tx::MessageExt, Module, Wasm};
@@ -16,7 +16,9 @@ use osmosis_std::{
     },
 };
 
-const TEST_ROOT: &str = "1V0YcwzXWtB+iuOTob6juiNliUmB278xZIKMnzwjqOU=";
+const TEST_ROOT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+// TEST_TOTAL_AMOUNT is the sum of the amounts in "testdata/address_amount.csv".
+const TEST_TOTAL_AMOUNT: u128 = 1_456_255;
 
 pub const VALID_SUBDENOM: &str = "subdenom";
 const BANK_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
@@ -32,7 +34,25 @@ pub struct TestEnv {
 }
 
 impl TestEnv {
+    // new is only used by some of the test crates including this module.
+    #[allow(dead_code)]
     pub fn new() -> Self {
+        TestEnv::new_with_root(TEST_ROOT)
+    }
+
+    // new_with_root is only used by some of the test crates including this module.
+    #[allow(dead_code)]
+    pub fn new_with_root(merkle_root: &str) -> Self {
+        TestEnv::instantiate(merkle_root, false)
+    }
+
+    // new_address_only is only used by some of the test crates including this module.
+    #[allow(dead_code)]
+    pub fn new_address_only(merkle_root: &str) -> Self {
+        TestEnv::instantiate(merkle_root, true)
+    }
+
+    fn instantiate(merkle_root: &str, address_only: bool) -> Self {
         let app = OsmosisTestApp::new();
         let wasm = Wasm::new(&app);
 
@@ -63,7 +83,10 @@ impl TestEnv {
             .instantiate(
                 code_id,
                 &InstantiateMsg {
-                    merkle_root: String::from(TEST_ROOT),
+                    merkle_root: String::from(merkle_root),
+                    total_amount: Uint128::new(TEST_TOTAL_AMOUNT),
+                    address_only,
+                    ..InstantiateMsg::default()
                 },
                 Some(&owner.address()),
                 None,
@@ -85,6 +108,49 @@ impl TestEnv {
     }
 }
 
+// ClaimEnv is a TestEnv whose contract is granted the authorizations of the owner
+// and has the subdenom of the drop set, with an account to send the claims from.
+pub struct ClaimEnv {
+    pub app: OsmosisTestApp,
+    pub contract_address: String,
+    pub owner: SigningAccount,
+    pub full_denom: String,
+    pub claim_sender: SigningAccount,
+}
+
+// setup_claim_env grants the contract the mint and send authorizations of the owner,
+// sets the subdenom of the drop and creates the account the claims are sent from.
+// It is only used by some of the test crates including this module.
+#[allow(dead_code)]
+pub fn setup_claim_env(test_env: TestEnv) -> ClaimEnv {
+    test_env.execute_msg_grant_mint();
+    test_env.execute_msg_grant_bank_send();
+
+    let TestEnv {
+        app,
+        contract_address,
+        owner,
+        full_denom,
+    } = test_env;
+
+    let set_subdenom_msg = ExecuteMsg::SetSubDenom {
+        subdenom: String::from(VALID_SUBDENOM),
+    };
+    let res = Wasm::new(&app).execute(&contract_address, &set_subdenom_msg, &[], &owner);
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    let initial_balance = [Coin::new(1_000_000_000_000, "uosmo")];
+    let claim_sender = app.init_account(&initial_balance).unwrap();
+
+    ClaimEnv {
+        app,
+        contract_address,
+        owner,
+        full_denom,
+        claim_sender,
+    }
+}
+
 pub trait Granter {
     fn execute_msg_grant_mint(&self);
 
@@ -0,0 +1,334 @@
+use osmosis_std::types::cosmwasm::wasm::v1::{
+    QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+};
+use prost::Message;
+use serde::Deserialize;
+use std::error::Error;
+use std::time::Duration;
+
+// SMART_CONTRACT_STATE_PATH is the abci query path of the wasm smart query.
+const SMART_CONTRACT_STATE_PATH: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+
+// GET_ROOT_QUERY is the json of the merkle-drop contract's GetRoot query.
+const GET_ROOT_QUERY: &[u8] = br#"{"get_root":{}}"#;
+
+// DEFAULT_MAX_RETRIES is the number of times a query failing with a
+// RetryableError is retried unless given with --max-retries.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+// INITIAL_BACKOFF is the wait before the first retry, doubled on every retry.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+// AbciQuerier sends abci queries to a node and returns the response value.
+pub trait AbciQuerier {
+    fn abci_query(&self, path: &str, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>>;
+}
+
+// RetryableError is a transient error of the rpc endpoint, e.g. a timeout or a
+// server error, that may not recur if the query is retried.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct RetryableError(String);
+
+// RetryingQuerier retries the queries of the querier failing with a RetryableError
+// up to max_retries times with exponential backoff, so that a single flaky response
+// does not abort an audit. Other errors, e.g. a bad request, are returned at once.
+pub struct RetryingQuerier<Q> {
+    querier: Q,
+    max_retries: u32,
+    initial_backoff: Duration,
+}
+
+impl<Q> RetryingQuerier<Q> {
+    pub fn new(querier: Q, max_retries: u32) -> Self {
+        RetryingQuerier {
+            querier,
+            max_retries,
+            initial_backoff: INITIAL_BACKOFF,
+        }
+    }
+}
+
+impl<Q: AbciQuerier> AbciQuerier for RetryingQuerier<Q> {
+    fn abci_query(&self, path: &str, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut backoff = self.initial_backoff;
+        let mut retries = 0;
+        loop {
+            match self.querier.abci_query(path, data) {
+                Err(err) if err.is::<RetryableError>() && retries < self.max_retries => {
+                    eprintln!("retrying query in {:?}: {}", backoff, err);
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// RpcClient queries a node over its tendermint rpc endpoint.
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        RpcClient {
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl AbciQuerier for RpcClient {
+    fn abci_query(&self, path: &str, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let body = ureq::get(&format!("{}/abci_query", self.url))
+            .query("path", &format!("\"{}\"", path))
+            .query("data", &format!("0x{}", hex::encode(data)))
+            .call()
+            .map_err(classify_rpc_error)?
+            .into_string()
+            .map_err(|err| RetryableError(err.to_string()))?;
+
+        decode_abci_response(&body)
+    }
+}
+
+// classify_rpc_error returns the timeouts, connection failures, rate limits and
+// server errors of the rpc endpoint as a RetryableError. Other errors, e.g. an
+// invalid url or a bad request, are returned as they are.
+fn classify_rpc_error(err: ureq::Error) -> Box<dyn Error> {
+    let is_retryable = match &err {
+        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+        ureq::Error::Transport(transport) => matches!(
+            transport.kind(),
+            ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::Io
+        ),
+    };
+    if is_retryable {
+        return Box::new(RetryableError(err.to_string()));
+    }
+    err.into()
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: AbciQueryResult,
+}
+
+#[derive(Deserialize)]
+struct AbciQueryResult {
+    response: AbciQueryResponse,
+}
+
+#[derive(Deserialize)]
+struct AbciQueryResponse {
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    log: String,
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GetRootResponse {
+    root: String,
+}
+
+// decode_abci_response returns the decoded value of the json rpc response
+// to an abci query or an error if the query failed.
+fn decode_abci_response(body: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let response = serde_json_wasm::from_str::<RpcResponse>(body)?
+        .result
+        .response;
+
+    if response.code != 0 {
+        return Err(format!(
+            "abci query failed with code {}: {}",
+            response.code, response.log
+        )
+        .into());
+    }
+
+    Ok(base64::decode(response.value.unwrap_or_default())?)
+}
+
+// query_root returns the merkle root of the deployed merkle-drop contract.
+pub fn query_root(querier: &dyn AbciQuerier, contract: &str) -> Result<String, Box<dyn Error>> {
+    let request = QuerySmartContractStateRequest {
+        address: contract.to_string(),
+        query_data: GET_ROOT_QUERY.to_vec(),
+    };
+
+    let value = querier.abci_query(SMART_CONTRACT_STATE_PATH, &request.encode_to_vec())?;
+    let response = QuerySmartContractStateResponse::decode(value.as_slice())?;
+
+    let get_root: GetRootResponse = serde_json_wasm::from_slice(&response.data)?;
+    Ok(get_root.root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "osmo14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sq2r9g9";
+    const ROOT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+
+    // MockQuerier responds to the GetRoot query of CONTRACT with the given root.
+    struct MockQuerier(&'static str);
+
+    impl AbciQuerier for MockQuerier {
+        fn abci_query(&self, path: &str, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+            assert_eq!(SMART_CONTRACT_STATE_PATH, path);
+
+            let request = QuerySmartContractStateRequest::decode(data)?;
+            if request.address != CONTRACT || request.query_data != GET_ROOT_QUERY {
+                return Err("unexpected query".into());
+            }
+
+            let response = QuerySmartContractStateResponse {
+                data: format!("{{\"root\":\"{}\"}}", self.0).into_bytes(),
+            };
+            Ok(response.encode_to_vec())
+        }
+    }
+
+    // FlakyQuerier fails with the given errors, one per query, and then responds as MockQuerier
+    // with ROOT.
+    struct FlakyQuerier {
+        errors: std::cell::RefCell<Vec<Box<dyn Error>>>,
+        calls: std::cell::Cell<u32>,
+    }
+
+    impl FlakyQuerier {
+        fn new(errors: Vec<Box<dyn Error>>) -> Self {
+            FlakyQuerier {
+                errors: std::cell::RefCell::new(errors),
+                calls: std::cell::Cell::new(0),
+            }
+        }
+    }
+
+    impl AbciQuerier for FlakyQuerier {
+        fn abci_query(&self, path: &str, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+            self.calls.set(self.calls.get() + 1);
+            if !self.errors.borrow().is_empty() {
+                return Err(self.errors.borrow_mut().remove(0));
+            }
+            MockQuerier(ROOT).abci_query(path, data)
+        }
+    }
+
+    fn retrying(querier: FlakyQuerier, max_retries: u32) -> RetryingQuerier<FlakyQuerier> {
+        RetryingQuerier {
+            querier,
+            max_retries,
+            initial_backoff: Duration::ZERO,
+        }
+    }
+
+    fn timeout() -> Box<dyn Error> {
+        Box::new(RetryableError(String::from("timed out")))
+    }
+
+    #[test]
+    fn retrying_querier_retries_transient_errors() {
+        let querier = retrying(FlakyQuerier::new(vec![timeout(), timeout()]), 2);
+        assert_eq!(ROOT, query_root(&querier, CONTRACT).unwrap());
+        assert_eq!(3, querier.querier.calls.get());
+
+        // the retries are exhausted.
+        let querier = retrying(FlakyQuerier::new(vec![timeout(), timeout()]), 1);
+        let err = query_root(&querier, CONTRACT).unwrap_err();
+        assert_eq!("timed out", err.to_string());
+        assert_eq!(2, querier.querier.calls.get());
+    }
+
+    #[test]
+    fn retrying_querier_fatal_error() {
+        let querier = retrying(FlakyQuerier::new(vec!["bad request".into()]), 3);
+        let err = query_root(&querier, CONTRACT).unwrap_err();
+        assert_eq!("bad request", err.to_string());
+        assert_eq!(1, querier.querier.calls.get());
+    }
+
+    #[test]
+    fn query_root_mocked() {
+        assert_eq!(ROOT, query_root(&MockQuerier(ROOT), CONTRACT).unwrap());
+
+        assert!(query_root(&MockQuerier(ROOT), "osmo1other").is_err());
+    }
+
+    fn address_amount_path() -> std::path::PathBuf {
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("testdata")
+            .join("address_amount.csv")
+    }
+
+    #[test]
+    fn audit_against_mocked_root() {
+        let mut entries = crate::parse_csv(address_amount_path()).unwrap();
+        let root = query_root(&MockQuerier(ROOT), CONTRACT).unwrap();
+
+        assert!(crate::controller::audit(&entries, &root)
+            .unwrap()
+            .is_empty());
+
+        // an entry missing from the deployed tree changes the root
+        // so no entry can claim.
+        entries.push(merkle::leaf::encode("osmo1unlisted", "10", None).into_bytes());
+        let failures = crate::controller::audit(&entries, &root).unwrap();
+        assert_eq!(entries.len(), failures.len());
+    }
+
+    #[test]
+    fn audit_u128_bytes_against_mocked_root() {
+        // the root of address_amount.csv with the amounts hashed as u128 bytes.
+        let querier = MockQuerier("I/4RLirzczzmE8wjt/MrgUd2cmMvCKzghSKkOdo+QYo=");
+        let root = query_root(&querier, CONTRACT).unwrap();
+        let records = crate::read_csv_records(address_amount_path()).unwrap();
+
+        let tree_options = |leaf_encoding| crate::TreeOptions {
+            salt: None,
+            address_only: false,
+            hasher: crate::hasher::Hasher::Sha3_256,
+            leaf_encoding,
+            decimals: None,
+        };
+        let entries = crate::tree_leaves(
+            &records,
+            &tree_options(merkle::leaf::LeafEncoding::U128Bytes),
+        )
+        .unwrap();
+        assert!(crate::controller::audit(&entries, &root)
+            .unwrap()
+            .is_empty());
+
+        // the leaves of decimal strings cannot claim from a u128 bytes drop.
+        let entries = crate::tree_leaves(
+            &records,
+            &tree_options(merkle::leaf::LeafEncoding::DecimalString),
+        )
+        .unwrap();
+        let failures = crate::controller::audit(&entries, &root).unwrap();
+        assert_eq!(entries.len(), failures.len());
+    }
+
+    #[test]
+    fn decode_abci_response_value() {
+        let body = format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":-1,\"result\":{{\"response\":{{\"code\":0,\"log\":\"\",\"value\":\"{}\"}}}}}}",
+            base64::encode("value")
+        );
+        assert_eq!(b"value".to_vec(), decode_abci_response(&body).unwrap());
+
+        let body = "{\"jsonrpc\":\"2.0\",\"id\":-1,\"result\":{\"response\":{\"code\":18,\"log\":\"contract not found\",\"value\":null}}}";
+        let err = decode_abci_response(body).unwrap_err();
+        assert_eq!(
+            "abci query failed with code 18: contract not found",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use csv::StringRecord;
+
+// DEFAULT_ADDRESS_COLUMN and DEFAULT_AMOUNT_COLUMN are the header names
+// of the address and amount columns unless given with --address-col and --amount-col.
+pub const DEFAULT_ADDRESS_COLUMN: &str = "address";
+pub const DEFAULT_AMOUNT_COLUMN: &str = "amount";
+
+// Columns are the header names of the address and amount columns given on the command line.
+#[derive(Debug, Default)]
+pub struct Columns {
+    pub address: Option<String>,
+    pub amount: Option<String>,
+}
+
+// ColumnMapping reorders the fields of the records so that the address comes first
+// and the amount second, followed by the other columns in their order, e.g. the
+// vesting columns. This is the order the entries are read in, whatever the order
+// of the columns of the file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ColumnMapping {
+    // order is the index of the column of each field of the mapped records,
+    // or empty if the records are read in the order of the file.
+    order: Vec<usize>,
+}
+
+impl ColumnMapping {
+    // from_headers finds the address and amount columns by their names, ignoring
+    // case and surrounding whitespace. If no column name is given and the header
+    // does not name both columns, the columns are read by position so that files
+    // with other headers keep working. A given column name must be in the header.
+    pub fn from_headers(headers: &StringRecord, columns: &Columns) -> Result<Self, String> {
+        let address_column = columns.address.as_deref();
+        let amount_column = columns.amount.as_deref();
+
+        let address = find_column(
+            headers,
+            address_column.unwrap_or(DEFAULT_ADDRESS_COLUMN),
+            address_column.is_some(),
+        )?;
+        let amount = find_column(
+            headers,
+            amount_column.unwrap_or(DEFAULT_AMOUNT_COLUMN),
+            amount_column.is_some(),
+        )?;
+
+        let (address, amount) = match (address, amount) {
+            (Some(address), Some(amount)) => (address, amount),
+            _ => return Ok(ColumnMapping { order: vec![] }),
+        };
+        if address == amount {
+            return Err(format!(
+                "the address and amount columns are the same column {}",
+                address
+            ));
+        }
+
+        let others = (0..headers.len()).filter(|&i| i != address && i != amount);
+        let order = [address, amount].into_iter().chain(others).collect();
+        Ok(ColumnMapping { order })
+    }
+
+    // apply returns the record with its fields reordered. Records with another
+    // number of fields than the header, e.g. the address-only rows of a file
+    // read with a default amount, are returned as they are.
+    pub fn apply(&self, record: &StringRecord) -> StringRecord {
+        if self.order.is_empty() || self.order.len() != record.len() {
+            return record.clone();
+        }
+        self.order.iter().map(|&i| &record[i]).collect()
+    }
+}
+
+// find_column returns the index of the named column of the header.
+// A column that is not found is an error if it is required.
+fn find_column(
+    headers: &StringRecord,
+    name: &str,
+    required: bool,
+) -> Result<Option<usize>, String> {
+    let index = headers
+        .iter()
+        .position(|header| crate::clean_field(header).eq_ignore_ascii_case(name.trim()));
+    match index {
+        None if required => Err(format!(
+            "column {:?} not found in the header {:?}",
+            name,
+            headers.as_slice()
+        )),
+        _ => Ok(index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(address: &str, amount: &str) -> Columns {
+        Columns {
+            address: Some(String::from(address)),
+            amount: Some(String::from(amount)),
+        }
+    }
+
+    #[test]
+    fn from_headers_swapped_columns() {
+        let headers = StringRecord::from(vec![" Amount", "address ", "cliff", "duration"]);
+        let mapping = ColumnMapping::from_headers(&headers, &Columns::default()).unwrap();
+
+        let record = StringRecord::from(vec!["10", "osmo1a", "60", "3600"]);
+        assert_eq!(
+            StringRecord::from(vec!["osmo1a", "10", "60", "3600"]),
+            mapping.apply(&record)
+        );
+    }
+
+    #[test]
+    fn from_headers_positional_fallback() {
+        // files without the default names are read by position.
+        let headers = StringRecord::from(vec!["wallet", "tokens"]);
+        let mapping = ColumnMapping::from_headers(&headers, &Columns::default()).unwrap();
+
+        let record = StringRecord::from(vec!["osmo1a", "10"]);
+        assert_eq!(record, mapping.apply(&record));
+    }
+
+    #[test]
+    fn from_headers_given_columns() {
+        let headers = StringRecord::from(vec!["tokens", "wallet"]);
+        let mapping = ColumnMapping::from_headers(&headers, &columns("wallet", "tokens")).unwrap();
+
+        let record = StringRecord::from(vec!["10", "osmo1a"]);
+        assert_eq!(
+            StringRecord::from(vec!["osmo1a", "10"]),
+            mapping.apply(&record)
+        );
+
+        // a short record, e.g. of an address only, is kept as is.
+        let record = StringRecord::from(vec!["osmo1a"]);
+        assert_eq!(record, mapping.apply(&record));
+
+        let err = ColumnMapping::from_headers(&headers, &columns("address", "tokens")).unwrap_err();
+        assert!(err.contains("\"address\" not found"), "{}", err);
+
+        ColumnMapping::from_headers(&headers, &columns("wallet", "wallet")).unwrap_err();
+    }
+}
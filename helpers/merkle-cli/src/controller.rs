@@ -1,48 +1,879 @@
+use merkle::entry::{is_valid_subdenom, MAX_SUBDENOM_LENGTH};
 use merkle::hash;
+use merkle::leaf::LeafEncoding;
+use merkle::proof::{Proof, VersionedProof};
+use merkle::test_vectors::TestVector;
 use merkle::Tree;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 
-pub fn generate_root(data: &[Vec<u8>]) -> String {
-    let tree = Tree::new(data);
-    let hash = tree.get_root().unwrap();
+use crate::decimals::Decimals;
+
+// get_proof returns the index of the first matching entry in data
+// and the proof serialized as json.
+pub fn get_proof(data: &[Vec<u8>], proof_for: &Vec<u8>) -> Result<(usize, String), Box<dyn Error>> {
+    let (index, proof) = find_proof(&Tree::new(data), data, proof_for)?;
+
+    let serialized = serde_json_wasm::to_string(&proof)?;
 
-    base64::encode(hash)
+    Ok((index, serialized))
 }
 
-pub fn get_proof(data: &[Vec<u8>], proof_for: &Vec<u8>) -> Result<String, Box<dyn Error>> {
+// get_versioned_proof is the same as get_proof but serializes the proof
+// as a merkle::proof::VersionedProof of the current version and the leaf encoding.
+pub fn get_versioned_proof(
+    data: &[Vec<u8>],
+    proof_for: &Vec<u8>,
+    leaf_encoding: LeafEncoding,
+) -> Result<(usize, String), Box<dyn Error>> {
+    let (index, proof) = find_proof(&Tree::new(data), data, proof_for)?;
+
+    let serialized = serde_json_wasm::to_string(&VersionedProof::new(proof, leaf_encoding))?;
+
+    Ok((index, serialized))
+}
+
+// EmbeddedProof is a proof together with the entry it is for and the root
+// it verifies against, so that a proof file is self-contained for front-ends.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmbeddedProof {
+    pub address: String,
+    pub amount: String,
+    pub root: String,
+    pub proof: Proof,
+}
+
+// get_embedded_proof is the same as get_proof but serializes the proof
+// as an EmbeddedProof of the given address and amount.
+pub fn get_embedded_proof(
+    data: &[Vec<u8>],
+    proof_for: &Vec<u8>,
+    address: &str,
+    amount: &str,
+) -> Result<(usize, String), Box<dyn Error>> {
     let tree = Tree::new(data);
+    let (index, proof) = find_proof(&tree, data, proof_for)?;
+
+    let embedded = EmbeddedProof {
+        address: address.to_string(),
+        amount: amount.to_string(),
+        root: tree.get_root().unwrap().to_string(),
+        proof,
+    };
+    let serialized = serde_json_wasm::to_string(&embedded)?;
+
+    Ok((index, serialized))
+}
 
-    let proof_opt = tree.find_proof(proof_for);
+// verify_embedded_proof verifies the EmbeddedProof serialized in data against the
+// root. The leaf is rebuilt from the given address, e.g. the name of the proof file,
+// and the embedded amount so that a proof shipped under the wrong address fails.
+pub fn verify_embedded_proof(
+    data: &str,
+    address: &str,
+    salt: &[u8],
+    leaf_encoding: LeafEncoding,
+    decimals: &Option<Decimals>,
+    root: &hash::Hash,
+) -> Result<bool, Box<dyn Error>> {
+    let embedded: EmbeddedProof = serde_json_wasm::from_str(data)?;
+    let amount = match decimals {
+        Some(decimals) => decimals.to_base_units(&embedded.amount)?,
+        None => embedded.amount,
+    };
+    let entry = merkle::entry::ClaimEntry {
+        address: merkle::entry::Address::new(address)?,
+        amount: merkle::entry::Amount::parse(&amount)?,
+        vesting: None,
+    };
+    let leaf = merkle::leaf::with_encoding(leaf_encoding, &entry.to_leaf_bytes())?;
+    let leaf = merkle::leaf::with_salt(salt, &leaf);
+    Ok(embedded.proof.verify(&leaf, root))
+}
+
+// Manifest describes the tree of a data set, e.g. to publish along with the root
+// so that anyone can check that they hold the list the root was built from.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Manifest {
+    pub root: String,
+    pub checksum: String,
+    pub entry_count: usize,
+    pub hash: String,
+}
+
+// IndexedProof is the amount and the proof of an address in a proof index.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IndexedProof {
+    pub amount: String,
+    pub proof: Proof,
+}
+
+// get_proof_index returns the root of the tree of the entries and the index of the
+// amount and the proof of each address, given in the order of its entry. Every proof
+// is verified against the root before it is indexed.
+pub fn get_proof_index(
+    entries: &[Vec<u8>],
+    addresses_amounts: &[(String, String)],
+) -> Result<(String, BTreeMap<String, IndexedProof>), Box<dyn Error>> {
+    let tree = Tree::new(entries);
+    let root = tree.root_or_default();
+
+    // the index is built in the order of the entries, whatever the order
+    // the proofs are found in, so that its errors are deterministic.
+    let mut index = BTreeMap::new();
+    let proofs = find_verified_proofs(&tree, &root, entries);
+    for ((proof, is_valid), (address, amount)) in proofs.into_iter().zip(addresses_amounts) {
+        if !is_valid {
+            return Err(format!("proof of address {} does not verify", address).into());
+        }
+
+        let indexed = IndexedProof {
+            amount: amount.clone(),
+            proof,
+        };
+        if index.insert(address.clone(), indexed).is_some() {
+            return Err(format!("address {} has more than one entry", address).into());
+        }
+    }
+    Ok((root.to_string(), index))
+}
+
+// find_verified_proof returns the proof of the entry and whether it verifies against the root.
+fn find_verified_proof(tree: &Tree, root: &hash::Hash, entry: &[u8]) -> (Proof, bool) {
+    // a single entry tree has an empty proof.
+    let proof = tree.find_proof(&entry).unwrap_or_default();
+    let is_valid = proof.verify(&entry, root);
+    (proof, is_valid)
+}
 
-    if proof_opt.is_none() {
-        return Err(format!(
+// find_verified_proofs returns the verified proof of each entry, in the order of the entries.
+#[cfg(not(feature = "parallel"))]
+fn find_verified_proofs(tree: &Tree, root: &hash::Hash, entries: &[Vec<u8>]) -> Vec<(Proof, bool)> {
+    entries
+        .iter()
+        .map(|entry| find_verified_proof(tree, root, entry))
+        .collect()
+}
+
+// find_verified_proofs is the same as the serial one but finds the proofs on a thread pool.
+#[cfg(feature = "parallel")]
+fn find_verified_proofs(tree: &Tree, root: &hash::Hash, entries: &[Vec<u8>]) -> Vec<(Proof, bool)> {
+    use rayon::prelude::*;
+
+    entries
+        .par_iter()
+        .map(|entry| find_verified_proof(tree, root, entry))
+        .collect()
+}
+
+// find_proof returns the proof of proof_for in the tree built from data, together
+// with the index of its first occurrence in data rather than among the sorted
+// leaves, so that callers can report the row it was read from.
+fn find_proof(
+    tree: &Tree,
+    data: &[Vec<u8>],
+    proof_for: &Vec<u8>,
+) -> Result<(usize, Proof), Box<dyn Error>> {
+    match (
+        tree.find_proof(proof_for),
+        data.iter().position(|entry| entry == proof_for),
+    ) {
+        (Some(proof), Some(index)) => Ok((index, proof)),
+        _ => Err(format!(
             "failed to find proof for {:?}, the data hash is {:?}",
             proof_for,
             hash::leaf(proof_for)
         )
-        .into());
+        .into()),
     }
-
-    let proof = proof_opt.unwrap();
-
-    let serialized = serde_json_wasm::to_string(&proof)?;
-
-    Ok(serialized)
 }
 
 pub fn verify_proof(
     root: &String,
     proof_bytes: &str,
-    to_verify: String,
+    to_verify: &[u8],
 ) -> Result<bool, Box<dyn Error>> {
     let proof: merkle::proof::Proof = serde_json_wasm::from_str(proof_bytes)?;
-    let root_decoded = base64::decode(root)?;
+    let root = merkle::parse_root(root)?;
 
-    proof.verify(&to_verify, &merkle::hash::Hash::from(root_decoded));
+    Ok(proof.verify(&to_verify, &root))
+}
+
+// audit returns the indices of the entries whose proof does not verify
+// against the given root, e.g. the root of a deployed contract.
+// If the data set does not match the root, every entry fails.
+pub fn audit(data: &[Vec<u8>], root: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+    let root = merkle::parse_root(root)?;
+    let tree = Tree::new(data);
 
-    Ok(true)
+    Ok(data
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            // a single entry tree has an empty proof.
+            let proof = tree.find_proof(entry).unwrap_or_default();
+            !proof.verify(entry, &root)
+        })
+        .map(|(i, _)| i)
+        .collect())
+}
+
+// ProofStats are the statistics of the proofs of all entries of a tree.
+pub struct ProofStats {
+    pub entry_count: usize,
+    pub max_proof_length: u128,
+    pub average_proof_length: f64,
+}
+
+// proof_stats builds the tree and returns the statistics of the proofs
+// of all entries. The proof length is the number of proof entries,
+// each of which costs one branch hash to verify.
+pub fn proof_stats(data: &[Vec<u8>]) -> ProofStats {
+    let tree = Tree::new(data);
+
+    let proof_lengths: Vec<u128> = data
+        .iter()
+        .map(|entry| {
+            tree.find_proof(entry)
+                .map_or(0, |proof| proof.get_num_entries())
+        })
+        .collect();
+
+    let total: u128 = proof_lengths.iter().sum();
+    let average_proof_length = if data.is_empty() {
+        0.0
+    } else {
+        total as f64 / data.len() as f64
+    };
+
+    ProofStats {
+        entry_count: data.len(),
+        max_proof_length: proof_lengths.into_iter().max().unwrap_or_default(),
+        average_proof_length,
+    }
+}
+
+// median returns the median of the amounts, or None if there are none. The median
+// of an even number of amounts is the average of the two middle amounts, rounded
+// down. The amounts are compared as integers, e.g. 9 is less than 10.
+pub fn median(mut amounts: Vec<u128>) -> Option<u128> {
+    amounts.sort_unstable();
+
+    let middle = amounts.len() / 2;
+    if amounts.is_empty() {
+        None
+    } else if amounts.len() % 2 == 1 {
+        Some(amounts[middle])
+    } else {
+        // the difference is halved first so that large amounts do not overflow.
+        let (low, high) = (amounts[middle - 1], amounts[middle]);
+        Some(low + (high - low) / 2)
+    }
+}
+
+pub fn proof_json_to_hex(proof_json: &str) -> Result<String, Box<dyn Error>> {
+    let proof: merkle::proof::Proof = serde_json_wasm::from_str(proof_json)?;
+
+    Ok(proof.to_hex_string())
+}
+
+pub fn proof_hex_to_json(proof_hex: &str) -> Result<String, Box<dyn Error>> {
+    let proof = merkle::proof::Proof::from_hex_string(proof_hex)?;
+
+    let serialized = serde_json_wasm::to_string(&proof)?;
+
+    Ok(serialized)
+}
+
+// validate_single_denom returns an error naming the first row
+// whose amount is not in the expected denom.
+// The amount is expected in the second column in cosmos-sdk Coin string format.
+pub fn validate_single_denom(
+    records: &[csv::StringRecord],
+    expected_denom: &str,
+) -> Result<(), Box<dyn Error>> {
+    for (i, record) in records.iter().enumerate() {
+        let row = row_number(i, record);
+
+        let amount = match record.get(1) {
+            Some(amount) => amount.trim(),
+            None => return Err(format!("row {} has no amount column", row).into()),
+        };
+
+        let denom = amount.trim_start_matches(|c: char| c.is_ascii_digit());
+        if denom != expected_denom {
+            return Err(format!(
+                "row {} has denom {:?}, expected {:?}",
+                row, denom, expected_denom
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+// validate_records validates the address and the amount of every record
+// and that there are no duplicate records. Returns the number of valid
+// records or an error naming the first invalid row.
+pub fn validate_records(records: &[csv::StringRecord]) -> Result<usize, Box<dyn Error>> {
+    let mut seen: HashMap<&str, u64> = HashMap::new();
+
+    for (i, record) in records.iter().enumerate() {
+        let row = row_number(i, record);
+
+        let address = record.get(0).unwrap_or_default().trim();
+        if let Err(err) = bech32::decode(address) {
+            return Err(format!("row {} has invalid address {:?}: {}", row, address, err).into());
+        }
+
+        let amount = match record.get(1) {
+            Some(amount) => amount.trim(),
+            None => return Err(format!("row {} has no amount column", row).into()),
+        };
+        validate_amount(amount).map_err(|err| format!("row {} {}", row, err))?;
+
+        if let Some(first_row) = seen.insert(record.as_slice(), row) {
+            return Err(format!("row {} duplicates row {}", row, first_row).into());
+        }
+    }
+    Ok(records.len())
+}
+
+// validate_amount validates that the amount is either an integer
+// or a coin of a tokenfactory subdenom in cosmos-sdk Coin string format.
+pub fn validate_amount(amount: &str) -> Result<(), String> {
+    let denom = amount.trim_start_matches(|c: char| c.is_ascii_digit());
+    let number = &amount[..amount.len() - denom.len()];
+
+    if number.parse::<u128>().is_err() {
+        return Err(format!("has invalid amount {:?}", amount));
+    }
+
+    if !denom.is_empty() && !is_valid_subdenom(denom) {
+        return Err(format!("has invalid denom {:?}", denom));
+    }
+    Ok(())
+}
+
+// OWNER_PREFIX is the bech32 prefix of osmosis addresses.
+const OWNER_PREFIX: &str = "osmo";
+
+// MAX_DENOM_LENGTH is the maximum length of a cosmos-sdk denom.
+const MAX_DENOM_LENGTH: usize = 128;
+
+// validate_instantiate validates the fields of the contract's instantiate message
+// and the subdenom the drop will mint, so that a deploy script can check them before
+// submitting. Returns every problem found, none if the fields are valid.
+pub fn validate_instantiate(root: &str, subdenom: &str, owner: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if let Err(err) = merkle::parse_root(root) {
+        problems.push(format!("root {:?} is invalid: {}", root, err));
+    }
+
+    match bech32::decode(owner) {
+        Ok((hrp, _, _)) if hrp != OWNER_PREFIX => problems.push(format!(
+            "owner {:?} is not an {} address",
+            owner, OWNER_PREFIX
+        )),
+        Ok(_) => {}
+        Err(err) => problems.push(format!("owner {:?} is invalid: {}", owner, err)),
+    }
+
+    if !is_valid_subdenom(subdenom) {
+        problems.push(format!(
+            "subdenom {:?} is not a valid subdenom of at most {} bytes",
+            subdenom, MAX_SUBDENOM_LENGTH
+        ));
+    }
+    // tokenfactory denoms are "factory/{creator}/{subdenom}" and must
+    // not be longer than cosmos-sdk denoms as a whole.
+    let full_denom = format!("factory/{}/{}", owner, subdenom);
+    if full_denom.len() > MAX_DENOM_LENGTH {
+        problems.push(format!(
+            "subdenom {:?} makes the denom {:?} longer than {} bytes",
+            subdenom, full_denom, MAX_DENOM_LENGTH
+        ));
+    }
+    problems
+}
+
+// validate_column_counts returns an error naming the first row
+// whose number of fields is not one of the expected counts.
+pub fn validate_column_counts(
+    records: &[csv::StringRecord],
+    expected_counts: &[usize],
+) -> Result<(), Box<dyn Error>> {
+    for (i, record) in records.iter().enumerate() {
+        if !expected_counts.contains(&record.len()) {
+            return Err(format!(
+                "row {} has {} columns, expected one of {:?}",
+                row_number(i, record),
+                record.len(),
+                expected_counts
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+// exclude_addresses returns the records whose address is not one of the given
+// addresses and the given addresses that no record has.
+pub fn exclude_addresses(
+    records: &[csv::StringRecord],
+    addresses: &[String],
+) -> (Vec<csv::StringRecord>, Vec<String>) {
+    let excluded: HashSet<&str> = addresses.iter().map(String::as_str).collect();
+    let kept = records
+        .iter()
+        .filter(|record| !excluded.contains(record.get(0).unwrap_or_default()))
+        .cloned()
+        .collect();
+
+    let present: HashSet<&str> = records
+        .iter()
+        .map(|record| record.get(0).unwrap_or_default())
+        .collect();
+    let not_found = addresses
+        .iter()
+        .filter(|address| !present.contains(address.as_str()))
+        .cloned()
+        .collect();
+
+    (kept, not_found)
+}
+
+// canonicalize_records returns the records with their amounts normalized, see
+// merkle::leaf::normalize_amount, sorted and without duplicate records, so that
+// the same entries always produce the same file whatever their order and formatting.
+pub fn canonicalize_records(records: &[csv::StringRecord]) -> Vec<csv::StringRecord> {
+    let mut canonical: Vec<csv::StringRecord> = records
+        .iter()
+        .map(|record| {
+            record
+                .iter()
+                .enumerate()
+                .map(|(i, field)| match i {
+                    1 => merkle::leaf::normalize_amount(field),
+                    _ => field.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+    canonical.sort_by(|a, b| a.iter().cmp(b.iter()));
+    canonical.dedup();
+    canonical
+}
+
+// checksum returns the hex encoded sha256 of the records with trimmed fields,
+// sorted and written as csv. The amounts are not normalized and duplicate rows
+// are kept, so that unlike the root it differs for any difference of the input
+// lists but their order and whitespace, e.g. "05" for "5" or a duplicate row,
+// and two parties can confirm that they started from identical lists.
+pub fn checksum(records: &[csv::StringRecord]) -> Result<String, Box<dyn Error>> {
+    let mut rows: Vec<csv::StringRecord> = records
+        .iter()
+        .map(|record| record.iter().map(str::trim).collect())
+        .collect();
+    rows.sort_by(|a, b| a.iter().cmp(b.iter()));
+
+    let mut csv_writer = csv::Writer::from_writer(vec![]);
+    for row in rows.iter() {
+        csv_writer.write_record(row)?;
+    }
+    let data = csv_writer.into_inner()?;
+    Ok(hex::encode(sha2::Sha256::digest(&data)))
+}
+
+// level_roots returns the hash of the concatenated nodes of each level of the
+// tree of the data, from the leaves up to the root, so that two diverging runs
+// can be pinpointed to the first level where they differ.
+pub fn level_roots(data: &[Vec<u8>]) -> Vec<String> {
+    Tree::new(data)
+        .levels()
+        .iter()
+        .map(|level| {
+            let nodes: Vec<u8> = level
+                .iter()
+                .flat_map(|node| node.as_ref().to_vec())
+                .collect();
+            merkle::hash::leaf(&nodes).to_string()
+        })
+        .collect()
+}
+
+// self_test rebuilds the trees of the vectors, e.g. merkle::test_vectors::VECTORS,
+// and returns a description of every root or proof that differs from the expected
+// one, so that a build with a misbehaving hash dependency is caught before use.
+pub fn self_test(vectors: &[TestVector]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for (i, vector) in vectors.iter().enumerate() {
+        let tree = Tree::new(vector.leaves);
+        let root = tree.root_or_default();
+        if root.to_string() != vector.root {
+            mismatches.push(format!(
+                "vector {}: root {} is not {}",
+                i, root, vector.root
+            ));
+        }
+
+        for (leaf, expected) in vector.leaves.iter().zip(vector.proofs.iter()) {
+            // a single leaf tree has no proof to find.
+            let proof = tree.find_proof(leaf).unwrap_or_default();
+            if proof.to_hex_string() != *expected || !proof.verify(leaf, &root) {
+                mismatches.push(format!("vector {}: proof of {:?} differs", i, leaf));
+            }
+        }
+    }
+    mismatches
+}
+
+// row_number returns the line of the record in the csv file.
+// Falls back to the index if the record was not read from a csv reader.
+pub fn row_number(index: usize, record: &csv::StringRecord) -> u64 {
+    record.position().map_or(index as u64, |p| p.line())
 }
 
 pub fn hash(data: &String) -> String {
     return merkle::hash::leaf(data.as_bytes()).to_string();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ADDRESS_AMOUNT_ROOT is the root of "testdata/address_amount.csv" as documented in the README.
+    const ADDRESS_AMOUNT_ROOT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+
+    #[test]
+    fn exclude_addresses_reports_not_found() {
+        let records = vec![
+            csv::StringRecord::from(vec!["osmo1a", "10"]),
+            csv::StringRecord::from(vec!["osmo1b", "20"]),
+        ];
+        let addresses = vec![String::from("osmo1b"), String::from("osmo1c")];
+
+        let (kept, not_found) = exclude_addresses(&records, &addresses);
+
+        assert_eq!(vec![records[0].clone()], kept);
+        assert_eq!(vec![String::from("osmo1c")], not_found);
+    }
+
+    fn address_amount_entries() -> Vec<Vec<u8>> {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("testdata")
+            .join("address_amount.csv");
+        crate::parse_csv(path).unwrap()
+    }
+
+    #[test]
+    fn root_display() {
+        let entries = address_amount_entries();
+
+        let root = Tree::new(&entries).get_root().unwrap();
+
+        assert_eq!(ADDRESS_AMOUNT_ROOT, root.to_string());
+    }
+
+    #[test]
+    fn embedded_proof_verifies_against_embedded_root() {
+        let entries = address_amount_entries();
+        let address = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+        let proof_for = format!("{}|1421901", address).into_bytes();
+
+        let (index, serialized) =
+            get_embedded_proof(&entries, &proof_for, address, "1421901").unwrap();
+        let embedded: EmbeddedProof = serde_json_wasm::from_str(&serialized).unwrap();
+
+        assert_eq!(address, embedded.address);
+        assert_eq!("1421901", embedded.amount);
+        assert_eq!(ADDRESS_AMOUNT_ROOT, embedded.root);
+        assert_eq!(index, get_proof(&entries, &proof_for).unwrap().0);
+        assert_eq!(proof_for, entries[index]);
+
+        let root = Tree::new(&entries).get_root().unwrap();
+        assert_eq!(true, embedded.proof.verify(&proof_for, &root));
+    }
+
+    #[test]
+    fn proof_stats_max_is_tree_depth() {
+        let entries = address_amount_entries();
+
+        let stats = proof_stats(&entries);
+
+        // 12 entries are 4 levels deep, ceil(log2(12)).
+        let depth = (entries.len() as f64).log2().ceil() as u128;
+        assert_eq!(12, stats.entry_count);
+        assert_eq!(depth, stats.max_proof_length);
+        assert!((stats.average_proof_length - 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn convert_proof_round_trip() {
+        let entries = address_amount_entries();
+
+        let (_, proof_json) = get_proof(&entries, &entries[0]).unwrap();
+
+        let proof_hex = proof_json_to_hex(&proof_json).unwrap();
+        let actual_json = proof_hex_to_json(&proof_hex).unwrap();
+
+        assert_eq!(proof_json, actual_json);
+    }
+
+    fn read_records(data: &str) -> Vec<csv::StringRecord> {
+        csv::Reader::from_reader(data.as_bytes())
+            .records()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn leading_zeros_amount_verifies() {
+        let address = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
+        let records = read_records(&format!(
+            "address,amount\n{},0100uosmo\nosmo10009zx2uyaw2zkj7ye5zmmxsnu2d9dulh6fd8d,7uosmo\n",
+            address
+        ));
+        let entries = crate::to_entries(&records).unwrap();
+
+        // the claim is made with the amount as stringified by the contract.
+        let claim = format!("{}|100uosmo", address);
+
+        let root = Tree::new(&entries).root_or_default().to_string();
+        let (_, proof) = get_proof(&entries, &claim.as_bytes().to_vec()).unwrap();
+
+        assert!(verify_proof(&root, &proof, claim.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn validate_records_success() {
+        let records = read_records(
+            "address,amount\nosmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4,10\nosmo10009zx2uyaw2zkj7ye5zmmxsnu2d9dulh6fd8d,100uosmo\n",
+        );
+
+        assert_eq!(2, validate_records(&records).unwrap());
+    }
+
+    #[test]
+    fn validate_records_errors() {
+        let address = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
+
+        let records = read_records(&format!(
+            "address,amount\n{},10uosmo\n{},10uosmo\n",
+            address, address
+        ));
+        let err = validate_records(&records).unwrap_err();
+        assert_eq!("row 3 duplicates row 2", err.to_string());
+
+        let records = read_records(&format!("address,amount\n{},ten\n", address));
+        let err = validate_records(&records).unwrap_err();
+        assert_eq!("row 2 has invalid amount \"ten\"", err.to_string());
+
+        let records = read_records(&format!("address,amount\n{},10u!\n", address));
+        let err = validate_records(&records).unwrap_err();
+        assert_eq!("row 2 has invalid denom \"u!\"", err.to_string());
+
+        let records = read_records("address,amount\nosmo1invalid,10uosmo\n");
+        let err = validate_records(&records).unwrap_err();
+        assert!(
+            err.to_string().starts_with("row 2 has invalid address"),
+            "{}",
+            err
+        );
+    }
+
+    const OWNER: &str = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
+
+    #[test]
+    fn validate_instantiate_success() {
+        assert!(validate_instantiate(ADDRESS_AMOUNT_ROOT, "mydenom", OWNER).is_empty());
+    }
+
+    #[test]
+    fn validate_instantiate_invalid_root() {
+        let problems = validate_instantiate("not a root", "mydenom", OWNER);
+        assert_eq!(1, problems.len(), "{:?}", problems);
+        assert!(problems[0].starts_with("root "), "{:?}", problems);
+    }
+
+    #[test]
+    fn validate_instantiate_invalid_subdenom() {
+        let too_long = "d".repeat(MAX_SUBDENOM_LENGTH + 1);
+        for subdenom in ["my denom", "mydenom!", too_long.as_str()] {
+            let problems = validate_instantiate(ADDRESS_AMOUNT_ROOT, subdenom, OWNER);
+            assert!(!problems.is_empty(), "{:?}", subdenom);
+            assert!(
+                problems
+                    .iter()
+                    .all(|problem| problem.starts_with("subdenom ")),
+                "{:?}",
+                problems
+            );
+        }
+    }
+
+    #[test]
+    fn validate_instantiate_invalid_owner() {
+        let problems = validate_instantiate(ADDRESS_AMOUNT_ROOT, "mydenom", "osmo1invalid");
+        assert_eq!(1, problems.len(), "{:?}", problems);
+        assert!(problems[0].starts_with("owner "), "{:?}", problems);
+    }
+
+    #[test]
+    fn validate_instantiate_owner_of_other_chain() {
+        let owner = "cosmos10004ufcv2aln3vl8defyk9agv5kacrzp7layh8";
+        let problems = validate_instantiate(ADDRESS_AMOUNT_ROOT, "mydenom", owner);
+        assert_eq!(1, problems.len(), "{:?}", problems);
+        assert!(
+            problems[0].ends_with("is not an osmo address"),
+            "{:?}",
+            problems
+        );
+    }
+
+    #[test]
+    fn validate_column_counts_three_columns() {
+        let records = read_records("address,amount\nosmo1a,10uosmo\nosmo1b,5uosmo\n");
+        validate_column_counts(&records, &[2, 4]).unwrap();
+
+        let records = read_records("address,amount,extra\nosmo1a,10uosmo,1\nosmo1b,5uosmo,2\n");
+        let err = validate_column_counts(&records, &[2, 4]).unwrap_err();
+
+        assert_eq!(
+            "row 2 has 3 columns, expected one of [2, 4]",
+            err.to_string()
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn find_verified_proofs_parallel_matches_serial() {
+        let entries = address_amount_entries();
+        let tree = Tree::new(&entries);
+        let root = tree.root_or_default();
+
+        let serial: Vec<(Proof, bool)> = entries
+            .iter()
+            .map(|entry| find_verified_proof(&tree, &root, entry))
+            .collect();
+
+        assert_eq!(serial, find_verified_proofs(&tree, &root, &entries));
+        assert!(serial.iter().all(|(_, is_valid)| *is_valid));
+    }
+
+    #[test]
+    fn canonicalize_records_sorted_deduplicated() {
+        let records = read_records("address,amount\nosmo1b,005uosmo\nosmo1a,10\nosmo1b,5uosmo\n");
+
+        let canonical = canonicalize_records(&records);
+
+        assert_eq!(
+            vec![
+                csv::StringRecord::from(vec!["osmo1a", "10"]),
+                csv::StringRecord::from(vec!["osmo1b", "5uosmo"]),
+            ],
+            canonical
+        );
+        assert_eq!(canonical, canonicalize_records(&canonical));
+    }
+
+    #[test]
+    fn median_empty_and_single() {
+        assert_eq!(None, median(vec![]));
+        assert_eq!(Some(u128::MAX), median(vec![u128::MAX]));
+    }
+
+    #[test]
+    fn median_odd_and_even() {
+        // sorted as integers, not lexically as "10" < "9".
+        assert_eq!(Some(10), median(vec![100, 9, 10]));
+        assert_eq!(
+            Some(u128::MAX - 1),
+            median(vec![u128::MAX, 1, u128::MAX - 1])
+        );
+
+        assert_eq!(Some(9), median(vec![10, 100, 9, 1]));
+        assert_eq!(Some(5), median(vec![10, 1]));
+        assert_eq!(
+            Some(u128::MAX - 1),
+            median(vec![u128::MAX, u128::MAX - 2, 0, u128::MAX])
+        );
+    }
+
+    #[test]
+    fn self_test_vectors() {
+        assert!(self_test(merkle::test_vectors::VECTORS).is_empty());
+
+        let tampered = TestVector {
+            root: merkle::test_vectors::TWO_LEAVES.root,
+            ..merkle::test_vectors::THREE_LEAVES
+        };
+        let mismatches = self_test(&[tampered]);
+        assert_eq!(1, mismatches.len(), "{:?}", mismatches);
+        assert!(
+            mismatches[0].starts_with("vector 0: root"),
+            "{:?}",
+            mismatches
+        );
+    }
+
+    #[test]
+    fn checksum_stable_and_row_sensitive() {
+        let records = read_records("address,amount\nosmo1a,10\nosmo1b,5uosmo\n");
+        let expected = checksum(&records).unwrap();
+        assert_eq!(64, expected.len());
+
+        // the same rows in another order and with surrounding whitespace.
+        let reordered = read_records("address,amount\nosmo1b, 5uosmo \nosmo1a,10\n");
+        assert_eq!(expected, checksum(&reordered).unwrap());
+
+        let changed = read_records("address,amount\nosmo1a,10\nosmo1b,6uosmo\n");
+        assert_ne!(expected, checksum(&changed).unwrap());
+
+        // the amounts are not normalized and duplicate rows are kept.
+        let padded = read_records("address,amount\nosmo1a,10\nosmo1b,05uosmo\n");
+        assert_ne!(expected, checksum(&padded).unwrap());
+        let duplicated = read_records("address,amount\nosmo1a,10\nosmo1b,5uosmo\nosmo1a,10\n");
+        assert_ne!(expected, checksum(&duplicated).unwrap());
+    }
+
+    #[test]
+    fn get_proof_index_duplicate_address() {
+        let entries: Vec<Vec<u8>> = vec![b"osmo1a|10".to_vec(), b"osmo1a|20".to_vec()];
+        let addresses_amounts = vec![
+            (String::from("osmo1a"), String::from("10")),
+            (String::from("osmo1a"), String::from("20")),
+        ];
+
+        let err = get_proof_index(&entries, &addresses_amounts).unwrap_err();
+        assert_eq!("address osmo1a has more than one entry", err.to_string());
+
+        let (root, index) = get_proof_index(&entries[..1], &addresses_amounts[..1]).unwrap();
+        assert_eq!(Tree::new(&entries[..1]).root_or_default().to_string(), root);
+        assert_eq!("10", index["osmo1a"].amount);
+    }
+
+    #[test]
+    fn validate_single_denom_success() {
+        let records = read_records("address,amount\nosmo1a,10uosmo\nosmo1b,5uosmo\n");
+
+        validate_single_denom(&records, "uosmo").unwrap();
+    }
+
+    #[test]
+    fn validate_single_denom_mixed_denoms() {
+        let records = read_records("address,amount\nosmo1a,10uosmo\nosmo1b,5uion\n");
+
+        let err = validate_single_denom(&records, "uosmo").unwrap_err();
+
+        assert_eq!(
+            "row 3 has denom \"uion\", expected \"uosmo\"",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use csv::StringRecord;
+
+// Decimals converts the amounts given in a display denom with decimals, e.g. "1.5 OSMO",
+// into integer amounts of the base denom, e.g. "1500000uosmo", as the leaves are built of.
+#[derive(Debug, Default)]
+pub struct Decimals {
+    pub decimals: u32,
+    pub display_denom: Option<String>,
+    pub base_denom: Option<String>,
+}
+
+impl Decimals {
+    // to_base_units converts the amount in the display denom, or without a denom,
+    // into the integer amount followed by the base denom, if any. Amounts with
+    // more decimals than the display denom has are rejected rather than rounded.
+    pub fn to_base_units(&self, amount: &str) -> Result<String, String> {
+        let split = amount
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(amount.len());
+        let (number, denom) = (&amount[..split], amount[split..].trim());
+
+        let is_display_denom = match &self.display_denom {
+            Some(display_denom) => denom.eq_ignore_ascii_case(display_denom),
+            None => false,
+        };
+        if !denom.is_empty() && !is_display_denom {
+            return Err(format!(
+                "amount {:?} is not in the display denom {:?}",
+                amount,
+                self.display_denom.as_deref().unwrap_or_default()
+            ));
+        }
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+            return Err(format!("invalid decimal amount {:?}", amount));
+        }
+        if fraction.len() > self.decimals as usize {
+            return Err(format!(
+                "amount {:?} has more than {} decimals",
+                amount, self.decimals
+            ));
+        }
+
+        // the fraction is padded to the decimals, e.g. "5" is 500000 base units of 6 decimals.
+        let fraction = format!("{:0<width$}", fraction, width = self.decimals as usize);
+        let value = 10u128
+            .checked_pow(self.decimals)
+            .and_then(|scale| integer.parse::<u128>().ok()?.checked_mul(scale))
+            .and_then(|value| value.checked_add(fraction.parse::<u128>().unwrap_or_default()))
+            .ok_or_else(|| format!("amount {:?} overflows in base units", amount))?;
+
+        Ok(format!(
+            "{}{}",
+            value,
+            self.base_denom.as_deref().unwrap_or_default()
+        ))
+    }
+}
+
+// convert_records returns the records with their amounts converted to base units,
+// see Decimals::to_base_units, or the records as they are if no decimals are given.
+pub fn convert_records(
+    records: Vec<StringRecord>,
+    decimals: &Option<Decimals>,
+) -> Result<Vec<StringRecord>, String> {
+    let decimals = match decimals {
+        Some(decimals) => decimals,
+        None => return Ok(records),
+    };
+
+    records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let mut converted = StringRecord::new();
+            for (j, field) in record.iter().enumerate() {
+                if j == 1 {
+                    let amount = decimals.to_base_units(field).map_err(|err| {
+                        format!("row {} {}", crate::controller::row_number(i, record), err)
+                    })?;
+                    converted.push_field(&amount);
+                } else {
+                    converted.push_field(field);
+                }
+            }
+            converted.set_position(record.position().cloned());
+            Ok(converted)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn osmo() -> Decimals {
+        Decimals {
+            decimals: 6,
+            display_denom: Some(String::from("OSMO")),
+            base_denom: Some(String::from("uosmo")),
+        }
+    }
+
+    #[test]
+    fn to_base_units_success() {
+        let decimals = osmo();
+
+        assert_eq!("1500000uosmo", decimals.to_base_units("1.5 OSMO").unwrap());
+        assert_eq!("1500000uosmo", decimals.to_base_units("1.5osmo").unwrap());
+        assert_eq!("7uosmo", decimals.to_base_units("0.000007").unwrap());
+        assert_eq!("2000000uosmo", decimals.to_base_units("2").unwrap());
+
+        let decimals = Decimals {
+            base_denom: None,
+            ..osmo()
+        };
+        assert_eq!("1500000", decimals.to_base_units("1.5 OSMO").unwrap());
+    }
+
+    #[test]
+    fn to_base_units_errors() {
+        let decimals = osmo();
+
+        let err = decimals.to_base_units("1.0000001 OSMO").unwrap_err();
+        assert!(err.contains("more than 6 decimals"), "{}", err);
+
+        let err = decimals.to_base_units("1500000uosmo").unwrap_err();
+        assert!(err.contains("not in the display denom"), "{}", err);
+
+        for amount in ["", ".5", "1.5.0", "1,5 OSMO"] {
+            assert!(decimals.to_base_units(amount).is_err(), "{:?}", amount);
+        }
+    }
+}
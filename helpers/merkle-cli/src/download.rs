@@ -0,0 +1,141 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::CliError;
+
+// MAX_DOWNLOAD_SIZE is the maximum size in bytes of a csv downloaded from a URL,
+// so that a wrong URL cannot fill the memory of the CLI.
+pub const MAX_DOWNLOAD_SIZE: u64 = 512 * 1024 * 1024;
+
+// DOWNLOAD_TIMEOUT is the timeout of the whole download of a csv.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+// CSV_CONTENT_TYPES are the content types of a downloaded csv. Hosts often serve
+// csv files as plain text or binary. Others, e.g. the html of a login page, are rejected.
+const CSV_CONTENT_TYPES: &[&str] = &[
+    "text/csv",
+    "application/csv",
+    "text/plain",
+    "application/octet-stream",
+];
+
+// is_url returns true if the path is an http or https URL rather than a file path.
+pub fn is_url(path: &Path) -> bool {
+    path.to_str().map_or(false, |path| {
+        path.starts_with("http://") || path.starts_with("https://")
+    })
+}
+
+// open returns a reader of the csv at the path. If the path is an http or https URL,
+// the csv is downloaded and the body of the response is streamed into the reader.
+// Failures to open the file are csv errors as those of csv::Reader::from_path.
+pub fn open(path: &Path) -> Result<Box<dyn Read>, CliError> {
+    match path.to_str() {
+        Some(url) if is_url(path) => download(url, MAX_DOWNLOAD_SIZE),
+        _ => Ok(Box::new(fs::File::open(path).map_err(csv::Error::from)?)),
+    }
+}
+
+// download gets the csv at the url. A content length above max_size is rejected
+// before reading the body and a body without one fails once it exceeds max_size.
+fn download(url: &str, max_size: u64) -> Result<Box<dyn Read>, CliError> {
+    let agent = ureq::AgentBuilder::new().timeout(DOWNLOAD_TIMEOUT).build();
+    let response = agent
+        .get(url)
+        .call()
+        .map_err(|err| CliError::Failure(format!("failed to download {}: {}", url, err).into()))?;
+
+    let content_type = response.content_type().to_string();
+    if !CSV_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(CliError::Validation(
+            format!("{} has content type {:?}, not csv", url, content_type).into(),
+        ));
+    }
+
+    let content_length = response
+        .header("Content-Length")
+        .and_then(|length| length.parse::<u64>().ok());
+    if let Some(length) = content_length.filter(|length| *length > max_size) {
+        return Err(CliError::Validation(
+            format!(
+                "{} has {} bytes, more than the maximum of {}",
+                url, length, max_size
+            )
+            .into(),
+        ));
+    }
+
+    Ok(Box::new(LimitedReader::new(
+        response.into_reader(),
+        max_size,
+    )))
+}
+
+// LimitedReader reads at most limit bytes of the reader and fails if it has more.
+struct LimitedReader<R> {
+    inner: R,
+    limit: u64,
+    remaining: u64,
+}
+
+impl<R> LimitedReader<R> {
+    fn new(inner: R, limit: u64) -> Self {
+        LimitedReader {
+            inner,
+            limit,
+            remaining: limit,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            // the limit is only exceeded if there is more to read.
+            let mut byte = [0; 1];
+            return match self.inner.read(&mut byte)? {
+                0 => Ok(0),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("download exceeds the maximum of {} bytes", self.limit),
+                )),
+            };
+        }
+
+        let length = buf
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let read = self.inner.read(&mut buf[..length])?;
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_url_http_and_https() {
+        assert!(is_url(Path::new("http://localhost:8080/drop.csv")));
+        assert!(is_url(Path::new("https://example.com/drop.csv")));
+        assert!(!is_url(Path::new("testdata/address_amount.csv")));
+        assert!(!is_url(Path::new("ftp://example.com/drop.csv")));
+    }
+
+    #[test]
+    fn limited_reader() {
+        let mut data = String::new();
+        LimitedReader::new("address,amount\n".as_bytes(), 15)
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!("address,amount\n", data);
+
+        let err = LimitedReader::new("address,amount\n".as_bytes(), 14)
+            .read_to_string(&mut String::new())
+            .unwrap_err();
+        assert_eq!("download exceeds the maximum of 14 bytes", err.to_string());
+    }
+}
@@ -0,0 +1,176 @@
+use serde::Serialize;
+use thiserror::Error;
+
+// Exit codes of the CLI.
+// Distinct codes let CI pipelines branch on the class of failure.
+pub const EXIT_CODE_FAILURE: i32 = 1;
+pub const EXIT_CODE_PARSE: i32 = 2;
+pub const EXIT_CODE_VALIDATION: i32 = 3;
+pub const EXIT_CODE_VERIFICATION: i32 = 4;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// Failures to read or parse the csv input, with the position of the record.
+    #[error("{0}")]
+    Csv(#[from] csv::Error),
+    /// Parse failures of the csv or proof inputs.
+    #[error("{0}")]
+    Parse(Box<dyn std::error::Error>),
+    /// Validation failures of the arguments or the data set.
+    #[error("{0}")]
+    Validation(Box<dyn std::error::Error>),
+    /// Proof verification failures.
+    #[error("{0}")]
+    Verification(Box<dyn std::error::Error>),
+    /// Io failures other than reading the csv input, e.g. writing the output.
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    /// Any other failure.
+    #[error("{0}")]
+    Failure(Box<dyn std::error::Error>),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Csv(_) | CliError::Parse(_) => EXIT_CODE_PARSE,
+            CliError::Validation(_) => EXIT_CODE_VALIDATION,
+            CliError::Verification(_) => EXIT_CODE_VERIFICATION,
+            CliError::Io(_) | CliError::Failure(_) => EXIT_CODE_FAILURE,
+        }
+    }
+
+    /// kind returns the name of the error class, matching its exit code.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Csv(_) | CliError::Parse(_) => "parse",
+            CliError::Validation(_) => "validation",
+            CliError::Verification(_) => "verification",
+            CliError::Io(_) | CliError::Failure(_) => "failure",
+        }
+    }
+
+    /// line returns the line of the csv input the error occurred at, if known.
+    pub fn line(&self) -> Option<u64> {
+        let csv_error = match self {
+            CliError::Csv(err) => Some(err),
+            CliError::Parse(err) => err.downcast_ref::<csv::Error>(),
+            _ => None,
+        };
+        csv_error
+            .and_then(|err| err.position())
+            .map(|position| position.line())
+    }
+
+    /// to_json returns the error as {"error", "kind", "line"} for tools
+    /// wrapping the CLI. line is null if unknown.
+    pub fn to_json(&self) -> String {
+        let json_error = JsonError {
+            error: self.to_string(),
+            kind: self.kind(),
+            line: self.line(),
+        };
+        // serializing strings and numbers cannot fail.
+        serde_json_wasm::to_string(&json_error).unwrap()
+    }
+}
+
+#[derive(Serialize)]
+struct JsonError {
+    error: String,
+    kind: &'static str,
+    line: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::Hasher;
+    use std::path::PathBuf;
+
+    // ADDRESS_AMOUNT_ROOT is the root of "testdata/address_amount.csv".
+    const ADDRESS_AMOUNT_ROOT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+
+    fn testdata(file_name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("testdata")
+            .join(file_name)
+    }
+
+    #[test]
+    fn csv_error() {
+        let err = crate::read_csv_records(testdata("missing.csv")).unwrap_err();
+        assert!(matches!(err, CliError::Csv(_)), "{:?}", err);
+        assert_eq!(EXIT_CODE_PARSE, err.exit_code());
+
+        let err = crate::read_csv_records(testdata("malformed.csv")).unwrap_err();
+        assert!(matches!(err, CliError::Csv(_)), "{:?}", err);
+        assert_eq!(Some(3), err.line());
+    }
+
+    #[test]
+    fn parse_error() {
+        // a csv file is not a proof.
+        let err =
+            crate::convert_proof_cmd(testdata("address_amount.csv"), &crate::ProofFormat::Hex)
+                .unwrap_err();
+        assert!(matches!(err, CliError::Parse(_)), "{:?}", err);
+        assert_eq!(EXIT_CODE_PARSE, err.exit_code());
+        assert_eq!(None, err.line());
+    }
+
+    #[test]
+    fn validation_error() {
+        let err = crate::generate_root_cmd(
+            testdata("uosmo_only.csv"),
+            &Some(String::from("uion")),
+            &None,
+            false,
+            false,
+            Hasher::Sha3_256,
+            &None,
+            &crate::columns::Columns::default(),
+            &None,
+            false,
+            false,
+            &None,
+            false,
+            None,
+            merkle::leaf::LeafEncoding::DecimalString,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CliError::Validation(_)), "{:?}", err);
+        assert_eq!(EXIT_CODE_VALIDATION, err.exit_code());
+    }
+
+    #[test]
+    fn verification_error() {
+        let err = crate::verify_proof_cmd(
+            &String::from(ADDRESS_AMOUNT_ROOT),
+            testdata("proof_data_address_amount.json"),
+            String::from("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1"),
+            &None,
+            merkle::leaf::LeafEncoding::DecimalString,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CliError::Verification(_)), "{:?}", err);
+        assert_eq!(EXIT_CODE_VERIFICATION, err.exit_code());
+    }
+
+    #[test]
+    fn io_error() {
+        let err = crate::verify_proof_cmd(
+            &String::from(ADDRESS_AMOUNT_ROOT),
+            testdata("missing.json"),
+            String::from("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901"),
+            &None,
+            merkle::leaf::LeafEncoding::DecimalString,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CliError::Io(_)), "{:?}", err);
+        assert_eq!(EXIT_CODE_FAILURE, err.exit_code());
+        assert_eq!("failure", err.kind());
+    }
+}
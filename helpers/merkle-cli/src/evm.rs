@@ -0,0 +1,214 @@
+use sha3::{Digest, Keccak256};
+use std::error::Error;
+
+// ADDRESS_LENGTH is the length of an EVM address in bytes.
+const ADDRESS_LENGTH: usize = 20;
+
+// HEX_ADDRESS_LENGTH is the length of a 0x prefixed hex EVM address.
+pub const HEX_ADDRESS_LENGTH: usize = 2 + 2 * ADDRESS_LENGTH;
+
+// UINT256_LENGTH is the length of an abi encoded uint256 in bytes.
+const UINT256_LENGTH: usize = 32;
+
+pub type Hash = [u8; 32];
+
+pub fn keccak256(data: &[u8]) -> Hash {
+    Keccak256::digest(data).into()
+}
+
+// leaf returns keccak256(abi.encodePacked(address, amount)) where address
+// is a 0x prefixed hex address and amount is an integer encoded as uint256.
+pub fn leaf(address: &str, amount: &str) -> Result<Hash, Box<dyn Error>> {
+    let address_hex = address
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("address {:?} must be 0x prefixed", address))?;
+    let address_bytes = hex::decode(address_hex)?;
+    if address_bytes.len() != ADDRESS_LENGTH {
+        return Err(format!(
+            "address {:?} must be {} bytes long",
+            address, ADDRESS_LENGTH
+        )
+        .into());
+    }
+
+    let amount: u128 = amount.parse()?;
+    let mut amount_bytes = [0u8; UINT256_LENGTH];
+    amount_bytes[UINT256_LENGTH - 16..].copy_from_slice(&amount.to_be_bytes());
+
+    Ok(keccak256(
+        &[address_bytes.as_slice(), &amount_bytes].concat(),
+    ))
+}
+
+// hash_pair hashes the sorted pair as done by OpenZeppelin's MerkleProof.
+// Equal hashes, e.g. from duplicate entries, are a tie that needs no breaking:
+// either order concatenates to the same bytes. As proofs carry no side flags,
+// verify does not depend on the original order of the siblings either.
+fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    if a <= b {
+        keccak256(&[a.as_slice(), b.as_slice()].concat())
+    } else {
+        keccak256(&[b.as_slice(), a.as_slice()].concat())
+    }
+}
+
+// Tree is a keccak256 merkle tree with sorted leaves and sorted pair hashing.
+// The last node of a level with an odd number of nodes is promoted to the next level.
+// Its root and proofs match merkletreejs with { sort: true } and can be verified
+// with OpenZeppelin's MerkleProof.verify.
+pub struct Tree {
+    layers: Vec<Vec<Hash>>,
+}
+
+impl Tree {
+    pub fn new(mut leaves: Vec<Hash>) -> Self {
+        leaves.sort();
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next_layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next_layer);
+        }
+
+        Tree { layers }
+    }
+
+    pub fn root(&self) -> Option<Hash> {
+        self.layers.last().unwrap().first().copied()
+    }
+
+    // proof returns the sibling hashes from the leaf up to the root.
+    pub fn proof(&self, leaf: &Hash) -> Option<Vec<Hash>> {
+        let mut index = self.layers[0].binary_search(leaf).ok()?;
+
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+// verify verifies the proof as done by OpenZeppelin's MerkleProof.verify.
+pub fn verify(proof: &[Hash], root: &Hash, leaf: &Hash) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |hash, sibling| hash_pair(&hash, sibling));
+    computed == *root
+}
+
+pub fn to_hex(hash: &Hash) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The vector is computed independently with merkletreejs semantics:
+    // new MerkleTree(leaves, keccak256, { sort: true }).
+    const ENTRIES: [(&str, &str); 3] = [
+        ("0x1111111111111111111111111111111111111111", "100"),
+        ("0x2222222222222222222222222222222222222222", "200"),
+        ("0x3333333333333333333333333333333333333333", "300"),
+    ];
+    const ROOT: &str = "0x0dbe58dcdef8457ab435b5ac3e49782c2502b6356c884e1f747a9d8865abf73a";
+    const FIRST_LEAF: &str = "0x4f2aefca2998f6aa2ab6799857a78dad717148458baa694d613c74251a29f216";
+    const FIRST_PROOF: [&str; 2] = [
+        "0x7a10cfda5e9b2c0b4e2b98d253b1f1b38d27b63642bc2dfc72471e863437348f",
+        "0xc53ea9f1e3c465f361374ca53be5e8bc7c1d528dd756909bfe418326ca964de7",
+    ];
+    const LAST_PROOF: [&str; 1] =
+        ["0x46451dfcfffe56da3b9019027cda3f4cf0db85034ab47952da3a8db11d19dae3"];
+
+    fn leaves() -> Vec<Hash> {
+        ENTRIES
+            .iter()
+            .map(|(address, amount)| leaf(address, amount).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn keccak256_empty() {
+        assert_eq!(
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            to_hex(&keccak256(&[]))
+        );
+    }
+
+    #[test]
+    fn root_vector() {
+        let leaves = leaves();
+        assert_eq!(FIRST_LEAF, to_hex(&leaves[0]));
+
+        let tree = Tree::new(leaves);
+        assert_eq!(ROOT, to_hex(&tree.root().unwrap()));
+    }
+
+    #[test]
+    fn proof_vector() {
+        let leaves = leaves();
+        let tree = Tree::new(leaves.clone());
+        let root = tree.root().unwrap();
+
+        let first_proof = tree.proof(&leaves[0]).unwrap();
+        assert_eq!(
+            FIRST_PROOF.to_vec(),
+            first_proof.iter().map(to_hex).collect::<Vec<String>>()
+        );
+        assert!(verify(&first_proof, &root, &leaves[0]));
+
+        // the odd last leaf is promoted so its proof is shorter.
+        let last_proof = tree.proof(&leaves[2]).unwrap();
+        assert_eq!(
+            LAST_PROOF.to_vec(),
+            last_proof.iter().map(to_hex).collect::<Vec<String>>()
+        );
+        assert!(verify(&last_proof, &root, &leaves[2]));
+
+        assert!(!verify(&first_proof, &root, &leaves[1]));
+    }
+
+    #[test]
+    fn equal_siblings() {
+        let leaves = leaves();
+        let duplicated = vec![leaves[0], leaves[0], leaves[1], leaves[2]];
+
+        assert_eq!(
+            hash_pair(&leaves[0], &leaves[1]),
+            hash_pair(&leaves[1], &leaves[0])
+        );
+
+        // the duplicate leaves are siblings with equal hashes.
+        let tree = Tree::new(duplicated);
+        let root = tree.root().unwrap();
+
+        let proof = tree.proof(&leaves[0]).unwrap();
+        assert_eq!(leaves[0], proof[0]);
+        assert!(verify(&proof, &root, &leaves[0]));
+
+        for leaf in &leaves[1..] {
+            let proof = tree.proof(leaf).unwrap();
+            assert!(verify(&proof, &root, leaf));
+        }
+    }
+
+    #[test]
+    fn leaf_invalid_address() {
+        assert!(leaf("1111111111111111111111111111111111111111", "100").is_err());
+        assert!(leaf("0x11", "100").is_err());
+        assert!(leaf("0x1111111111111111111111111111111111111111", "100uosmo").is_err());
+    }
+}
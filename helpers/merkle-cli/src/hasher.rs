@@ -0,0 +1,92 @@
+use clap::ArgEnum;
+use merkle::hash::{HashFn, HASH_LENGTH};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+// Hasher selects the hash function of the tree. The contract verifies sha3-256
+// trees only, the other hashers target other on-chain verifiers.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hasher {
+    #[clap(name = "sha3-256")]
+    Sha3_256,
+    #[clap(name = "sha256")]
+    Sha256,
+    #[clap(name = "keccak256")]
+    Keccak256,
+    #[clap(name = "blake3")]
+    Blake3,
+}
+
+impl Hasher {
+    // name returns the name of the hasher as given to --hash.
+    pub fn name(&self) -> &'static str {
+        // CONTRACT: every hasher has a possible value.
+        self.to_possible_value().unwrap().get_name()
+    }
+
+    // hash_fn returns the hash function of the hasher for merkle::Tree.
+    pub fn hash_fn(&self) -> HashFn {
+        match self {
+            Hasher::Sha3_256 => merkle::hash::sha3_256,
+            Hasher::Sha256 => sha256,
+            Hasher::Keccak256 => keccak256,
+            Hasher::Blake3 => blake3,
+        }
+    }
+
+    // root_with_progress returns the root of the tree of the entries built with
+    // the hash function of the hasher, base64 encoded, and calls on_level after
+    // each level of the tree is combined. Returns None if there are no entries.
+    pub fn root_with_progress<F: FnMut()>(
+        &self,
+        entries: &[Vec<u8>],
+        on_level: F,
+    ) -> Option<String> {
+        merkle::Tree::new_with_hash_fn(entries, self.hash_fn(), on_level)
+            .get_root()
+            .map(|root| root.to_string())
+    }
+}
+
+fn sha256(data: &[u8]) -> [u8; HASH_LENGTH] {
+    Sha256::digest(data).into()
+}
+
+fn keccak256(data: &[u8]) -> [u8; HASH_LENGTH] {
+    Keccak256::digest(data).into()
+}
+
+fn blake3(data: &[u8]) -> [u8; HASH_LENGTH] {
+    blake3::hash(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address_amount_entries() -> Vec<Vec<u8>> {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("testdata")
+            .join("address_amount.csv");
+        crate::parse_csv(path).unwrap()
+    }
+
+    #[test]
+    fn sha3_256_matches_tree() {
+        let entries = address_amount_entries();
+
+        for count in 1..=entries.len() {
+            assert_eq!(
+                Some(
+                    merkle::Tree::new(&entries[..count])
+                        .root_or_default()
+                        .to_string()
+                ),
+                Hasher::Sha3_256.root_with_progress(&entries[..count], || {})
+            );
+        }
+        assert_eq!(None, Hasher::Sha3_256.root_with_progress(&[], || {}));
+    }
+}
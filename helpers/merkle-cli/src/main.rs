@@ -1,14 +1,56 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgEnum, CommandFactory, Parser, Subcommand};
+use merkle::entry::{Address, Amount, ClaimEntry};
+use merkle::leaf::LeafEncoding;
 use std::error::Error;
 use std::fs;
 use std::process;
 
+mod chain;
+mod columns;
 mod controller;
+mod decimals;
+mod download;
+mod error;
+mod evm;
+mod hasher;
+mod progress;
+mod serve;
+
+use columns::{ColumnMapping, Columns};
+use decimals::Decimals;
+use error::CliError;
+use progress::Progress;
+
+// ADDRESS_AMOUNT_COLUMN_COUNT is the number of columns of a record
+// with an address and an amount.
+const ADDRESS_AMOUNT_COLUMN_COUNT: usize = 2;
+
+// VESTING_COLUMN_COUNT is the number of columns of a record
+// with the vesting cliff and duration.
+const VESTING_COLUMN_COUNT: usize = 4;
+
+// DEFAULT_PORT is the port the proof server listens on by default.
+const DEFAULT_PORT: u16 = 8080;
+
+// DEFAULT_HOST is the address the proof server listens on by default,
+// the loopback interface so that it is not exposed unless asked to.
+const DEFAULT_HOST: &str = "127.0.0.1";
+
+// DEFAULT_GAS_PER_HASH is a rough estimate of the gas cost of hashing
+// a branch in the contract. Operators should tune it with --gas-per-hash.
+const DEFAULT_GAS_PER_HASH: u64 = 30_000;
 
 #[derive(Parser)]
 struct Cli {
     #[clap(subcommand)]
     command: Option<Commands>,
+
+    /// json_errors reports failures on stderr as json of the form
+    /// {"error": "...", "kind": "...", "line": N} where kind is the
+    /// class of the failure, matching the exit code, and line is the
+    /// line of the csv input if known.
+    #[clap(long, global = true)]
+    json_errors: bool,
 }
 
 #[derive(Subcommand)]
@@ -17,12 +59,128 @@ enum Commands {
     /// amounts in csv format at a given path
     /// the first column must be an address and second column is an amount
     /// in cosmos-sdk Coin string format.
+    /// Optionally, the third and fourth columns are the vesting cliff and
+    /// duration in seconds.
     /// prints the root hash to stdout, hex encoded.
     GenerateRoot {
         /// path the path to the file with accounts and amounts in csv format.
         /// See example in testdata.
         #[clap(parse(from_os_str))]
         path: std::path::PathBuf,
+
+        /// require_single_denom fails if the denom of any row's amount
+        /// differs from the given one. The contract mints a single denom
+        /// so rows with other denoms would be unclaimable.
+        #[clap(long)]
+        require_single_denom: Option<String>,
+
+        /// strict_columns fails if any row does not consist of exactly an address
+        /// and an amount, optionally followed by the vesting cliff and duration.
+        /// Otherwise, rows with other columns are hashed as the concatenation of
+        /// their fields which can hide shifted or truncated columns.
+        #[clap(long)]
+        strict_columns: bool,
+
+        /// salt the hex encoded salt prefixed to every leaf so that drops
+        /// with identical entries have different roots and proofs.
+        /// The contract must be instantiated with the same salt.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// evm_compat uses keccak256 with sorted pair hashing and
+        /// keccak256(abi.encodePacked(address, amount)) leaves to match
+        /// OpenZeppelin's MerkleProof. The addresses must be 0x prefixed
+        /// hex and the amounts integers. Outputs 0x prefixed hex.
+        #[clap(long, conflicts_with_all = &["salt", "require_single_denom"])]
+        evm_compat: bool,
+
+        /// address_only builds the tree of the addresses only for drops whose
+        /// contract is instantiated with address_only. The amounts are not part
+        /// of the root and must be set in the contract by the owner with SetAmounts.
+        #[clap(long, conflicts_with = "evm_compat")]
+        address_only: bool,
+
+        /// hash the hash function of the tree. The contract verifies sha3-256
+        /// trees only, the other hash functions target other on-chain verifiers
+        /// which must be told the hash function used.
+        #[clap(
+            long,
+            arg_enum,
+            default_value = "sha3-256",
+            conflicts_with = "evm_compat"
+        )]
+        hash: hasher::Hasher,
+
+        /// default_amount the amount, an integer or a coin, of the rows
+        /// with an address only. Useful for equal-distribution drops.
+        /// The amounts of the other rows are kept.
+        #[clap(long, conflicts_with = "evm_compat")]
+        default_amount: Option<String>,
+
+        /// progress shows the rows parsed and the tree levels combined on stderr
+        /// so that a long run over a huge data set is not mistaken for a hung one.
+        #[clap(long, conflicts_with = "evm_compat")]
+        progress: bool,
+
+        /// address_col the header name of the address column. By default, the
+        /// columns named address and amount are read, in any order.
+        #[clap(long, conflicts_with = "evm_compat")]
+        address_col: Option<String>,
+
+        /// amount_col the header name of the amount column.
+        #[clap(long, conflicts_with = "evm_compat")]
+        amount_col: Option<String>,
+
+        /// emit_checksum also prints the hex encoded sha256 of the sorted rows with
+        /// trimmed fields on a second line so that two parties can confirm that
+        /// they started from identical lists.
+        #[clap(long, conflicts_with = "evm_compat")]
+        emit_checksum: bool,
+
+        /// manifest the path to write the root, the checksum, the number of entries
+        /// and the hash function of the tree to in json format.
+        #[clap(long, parse(from_os_str), conflicts_with = "evm_compat")]
+        manifest: Option<std::path::PathBuf>,
+
+        /// emit_level_roots also prints the hash of each level of the tree, from
+        /// the leaves up to the root, one per line, so that two diverging runs can
+        /// be pinpointed to the level where they differ. sha3-256 trees only.
+        #[clap(long, conflicts_with = "evm_compat")]
+        emit_level_roots: bool,
+
+        /// max_depth fails if the depth of the tree, the number of entries of its
+        /// proofs, exceeds the given depth. Caps the gas spent verifying a claim
+        /// and catches an unexpectedly large list. See the contract's max_proof_depth.
+        #[clap(long, conflicts_with = "evm_compat")]
+        max_depth: Option<usize>,
+
+        /// leaf_encoding the encoding of the amounts in the leaves. u128-bytes hashes
+        /// each amount as its 16 big-endian bytes instead of its decimal string, for
+        /// verifiers that hash integers. The contract must be instantiated with the
+        /// matching leaf_encoding.
+        #[clap(
+            long,
+            arg_enum,
+            default_value = "decimal-string",
+            conflicts_with_all = &["evm_compat", "address_only"]
+        )]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts given in the display denom with up to the
+        /// given number of decimals, e.g. "1.5 OSMO", into integer amounts of the
+        /// base denom, e.g. "1500000uosmo", before building the leaves.
+        #[clap(long, conflicts_with = "evm_compat")]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, e.g. OSMO.
+        /// Amounts without a denom are in the display denom.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, e.g. uosmo.
+        /// The converted amounts are integers if not given.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
     },
 
     /// GenerateProof generates a Merkle proof for
@@ -49,6 +207,120 @@ enum Commands {
         /// If this flag is true
         #[clap(short, long)]
         print: bool,
+
+        /// salt the hex encoded salt prefixed to every leaf.
+        /// Must match the salt the contract is instantiated with.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// evm_compat generates the proof for the tree built with
+        /// GenerateRoot --evm-compat. proof_for is the 0x prefixed hex
+        /// address followed by the amount. The proof is a json array
+        /// of 0x prefixed hex hashes.
+        #[clap(long, conflicts_with = "salt")]
+        evm_compat: bool,
+
+        /// embed_root outputs the proof together with the address, the amount
+        /// and the root as {address, amount, root, proof} so that the proof
+        /// file is self-contained. proof_for must be an address and amount entry.
+        #[clap(long, conflicts_with = "evm_compat")]
+        embed_root: bool,
+
+        /// versioned outputs the proof together with the version of the leaf
+        /// encoding as {version, proof} so that the contract rejects proofs of
+        /// another version with a clear error. Unversioned proofs are accepted as is.
+        #[clap(long, conflicts_with_all = &["evm_compat", "embed_root"])]
+        versioned: bool,
+
+        /// address_only generates the proof for the tree built with
+        /// GenerateRoot --address-only. proof_for is the address.
+        #[clap(long, conflicts_with_all = &["evm_compat", "embed_root"])]
+        address_only: bool,
+
+        /// leaf_encoding the encoding of the amounts in the leaves.
+        /// Must match the leaf encoding the root is generated with.
+        #[clap(
+            long,
+            arg_enum,
+            default_value = "decimal-string",
+            conflicts_with_all = &["evm_compat", "address_only"]
+        )]
+        leaf_encoding: LeafEncodingArg,
+
+        /// default_amount the amount of the rows with an address only.
+        /// Must match the default amount the root is generated with.
+        #[clap(long, conflicts_with = "evm_compat")]
+        default_amount: Option<String>,
+
+        /// address_col the header name of the address column.
+        /// Must match the column the root is generated with.
+        #[clap(long, conflicts_with = "evm_compat")]
+        address_col: Option<String>,
+
+        /// amount_col the header name of the amount column.
+        /// Must match the column the root is generated with.
+        #[clap(long, conflicts_with = "evm_compat")]
+        amount_col: Option<String>,
+
+        /// decimals converts the amounts given in the display denom with up to the
+        /// given number of decimals, e.g. "1.5 OSMO", into integer amounts of the
+        /// base denom, e.g. "1500000uosmo", before building the leaves.
+        /// Must match the decimals the root is generated with.
+        #[clap(long, conflicts_with = "evm_compat")]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, e.g. OSMO.
+        /// Amounts without a denom are in the display denom.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, e.g. uosmo.
+        /// The converted amounts are integers if not given.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
+    },
+
+    /// GenerateProofsIndexed writes the proofs of all entries of the file consisting
+    /// of accounts and amounts in csv format at a given path to a single json object
+    /// mapping each address to {amount, proof}, which is easier to host and load in
+    /// a front-end than a file per address. Every proof is verified before writing.
+    /// Prints the number of proofs and the root.
+    GenerateProofsIndexed {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+
+        /// out the path to write the index of the proofs to in json format.
+        #[clap(long, parse(from_os_str))]
+        out: std::path::PathBuf,
+
+        /// salt the hex encoded salt prefixed to every leaf.
+        /// Must match the salt the contract is instantiated with.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// leaf_encoding the encoding of the amounts in the leaves.
+        /// Must match the leaf encoding the root is generated with.
+        #[clap(long, arg_enum, default_value = "decimal-string")]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts given in the display denom with up to the
+        /// given number of decimals, e.g. "1.5 OSMO", into integer amounts of the
+        /// base denom, e.g. "1500000uosmo", before building the leaves.
+        /// Must match the decimals the root is generated with.
+        #[clap(long)]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, e.g. OSMO.
+        /// Amounts without a denom are in the display denom.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, e.g. uosmo.
+        /// The converted amounts are integers if not given.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
     },
 
     /// VerifyProof verifies the given proof against the given root.
@@ -56,19 +328,391 @@ enum Commands {
     /// that produce the aforementioned required input data.s
     VerifyProof {
         /// root is the Merkle root hash to verify the proof against
-        /// must be base16 searialized.
-        #[clap()]
-        root: String,
+        /// must be base16 searialized. Not given with --contract, the
+        /// data to verify and the proof path follow the root otherwise.
+        root: Option<String>,
 
         /// to_verify data to verify.
-        to_verify: String,
+        to_verify: Option<String>,
 
         /// proof_path is the path to the file containing proof
         /// serialized as json.
         #[clap(parse(from_os_str))]
-        proof_path: std::path::PathBuf,
+        proof_path: Option<std::path::PathBuf>,
+
+        /// contract the address of the deployed merkle-drop contract whose
+        /// root to verify the proof against instead of the given root.
+        #[clap(long, requires = "rpc")]
+        contract: Option<String>,
+
+        /// rpc the tendermint rpc endpoint of a node to query the contract with.
+        #[clap(long, requires = "contract")]
+        rpc: Option<String>,
+
+        /// max_retries the number of times a query of the rpc endpoint failing with
+        /// a transient error, e.g. a timeout or a server error, is retried.
+        #[clap(long, default_value_t = chain::DEFAULT_MAX_RETRIES)]
+        max_retries: u32,
+
+        /// salt the hex encoded salt prefixed to every leaf.
+        /// Must match the salt the contract is instantiated with.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// leaf_encoding the encoding of the amounts in the leaves.
+        /// Must match the leaf encoding the root is generated with.
+        #[clap(long, arg_enum, default_value = "decimal-string")]
+        leaf_encoding: LeafEncodingArg,
+    },
+
+    /// VerifyProofsDir verifies every {address}.json proof of the directory, as written
+    /// by GenerateProof --embed-root, against the given root. The leaf of each proof is
+    /// rebuilt from the address of the file name and the embedded amount.
+    /// Prints the pass and fail counts and the failing files.
+    VerifyProofsDir {
+        /// dir the path to the directory of the proofs.
+        #[clap(parse(from_os_str))]
+        dir: std::path::PathBuf,
+
+        /// root is the Merkle root hash to verify the proofs against.
+        #[clap(long)]
+        root: String,
+
+        /// salt the hex encoded salt prefixed to every leaf.
+        /// Must match the salt the contract is instantiated with.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// leaf_encoding the encoding of the amounts in the leaves.
+        /// Must match the leaf encoding the root is generated with.
+        #[clap(long, arg_enum, default_value = "decimal-string")]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts given in the display denom with up to the
+        /// given number of decimals, e.g. "1.5 OSMO", into integer amounts of the
+        /// base denom, e.g. "1500000uosmo", before rebuilding the leaves of the embedded amounts.
+        /// Must match the decimals the root is generated with.
+        #[clap(long)]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, e.g. OSMO.
+        /// Amounts without a denom are in the display denom.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, e.g. uosmo.
+        /// The converted amounts are integers if not given.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
+    },
+
+    /// AuditDrop checks that every entry of the file consisting of accounts
+    /// and amounts in csv format at a given path can claim from the deployed
+    /// merkle-drop contract by verifying the proof of each entry against the
+    /// root of the contract. Prints the pass and fail counts and the failing rows.
+    AuditDrop {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+
+        /// contract the address of the deployed merkle-drop contract.
+        #[clap(long)]
+        contract: String,
+
+        /// rpc the tendermint rpc endpoint of a node to query the contract with.
+        #[clap(long)]
+        rpc: String,
+
+        /// max_retries the number of times a query of the rpc endpoint failing with
+        /// a transient error, e.g. a timeout or a server error, is retried.
+        #[clap(long, default_value_t = chain::DEFAULT_MAX_RETRIES)]
+        max_retries: u32,
+
+        /// salt the hex encoded salt prefixed to every leaf.
+        /// Must match the salt the contract is instantiated with.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// address_only builds the leaves of the addresses only.
+        /// Must match the address_only the contract is instantiated with.
+        #[clap(long)]
+        address_only: bool,
+
+        /// leaf_encoding the encoding of the amounts in the leaves.
+        /// Must match the leaf encoding the root is generated with.
+        #[clap(
+            long,
+            arg_enum,
+            default_value = "decimal-string",
+            conflicts_with = "address_only"
+        )]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts given in the display denom with up to the
+        /// given number of decimals, e.g. "1.5 OSMO", into integer amounts of the
+        /// base denom, e.g. "1500000uosmo", before building the leaves.
+        /// Must match the decimals the root is generated with.
+        #[clap(long)]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, e.g. OSMO.
+        /// Amounts without a denom are in the display denom.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, e.g. uosmo.
+        /// The converted amounts are integers if not given.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
+    },
+
+    /// ConvertProof converts the proof at the given path between
+    /// the json format produced by GenerateProof and the compact hex format.
+    /// The input is expected to be in the format opposite to the one
+    /// given by the --to flag. Prints the result to stdout.
+    ConvertProof {
+        /// input the path to the file containing the proof to convert.
+        #[clap(parse(from_os_str))]
+        input: std::path::PathBuf,
+
+        /// to the format to convert the proof to.
+        #[clap(long, arg_enum)]
+        to: ProofFormat,
+    },
+
+    /// Validate parses and validates the file consisting of accounts and
+    /// amounts in csv format at a given path without building the tree.
+    /// Checks that the addresses are valid bech32, that the amounts are valid
+    /// and that there are no duplicate rows.
+    /// prints the number of valid entries or the first error.
+    Validate {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+    },
+
+    /// ValidateInstantiate checks the root, the subdenom and the owner of the
+    /// contract's instantiate message before it is submitted. The root must be
+    /// valid hex or base64, the owner valid bech32 and the subdenom must obey
+    /// the tokenfactory naming rules. Prints every problem found.
+    ValidateInstantiate {
+        /// root the Merkle root of the instantiate message.
+        #[clap(long)]
+        root: String,
+
+        /// subdenom the tokenfactory subdenom the drop mints.
+        #[clap(long)]
+        subdenom: String,
+
+        /// owner the owner of the drop, the creator of the tokenfactory denom.
+        #[clap(long)]
+        owner: String,
+    },
+
+    /// Serve builds the tree from the file consisting of accounts and
+    /// amounts in csv format at a given path once and serves the proofs
+    /// over http. GET /proof/{address} returns the amount and the proof
+    /// of the address as json.
+    Serve {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+
+        /// port the port to listen on.
+        #[clap(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+
+        /// host the address to listen on, e.g. 0.0.0.0 to serve on all interfaces.
+        #[clap(long, default_value = DEFAULT_HOST)]
+        host: String,
+
+        /// leaf_encoding the encoding of the amounts in the leaves.
+        /// Must match the leaf encoding the root is generated with.
+        #[clap(long, arg_enum, default_value = "decimal-string")]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts given in the display denom with up to the
+        /// given number of decimals, e.g. "1.5 OSMO", into integer amounts of the
+        /// base denom, e.g. "1500000uosmo", before building the leaves.
+        /// Must match the decimals the root is generated with.
+        #[clap(long)]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, e.g. OSMO.
+        /// Amounts without a denom are in the display denom.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, e.g. uosmo.
+        /// The converted amounts are integers if not given.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
+    },
+
+    /// EstimateGas builds the tree from the file consisting of accounts and
+    /// amounts in csv format at a given path and reports the maximum and
+    /// average proof length and the estimated gas to verify a proof on chain.
+    /// Verifying a proof costs one branch hash per proof entry.
+    EstimateGas {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+
+        /// gas_per_hash the estimated gas cost of hashing a branch on chain.
+        #[clap(long, default_value_t = DEFAULT_GAS_PER_HASH)]
+        gas_per_hash: u64,
+    },
+
+    /// Stats reports the number of entries and the total and median amount of the
+    /// file consisting of accounts and amounts in csv format at a given path.
+    /// The amounts of all denoms are counted alike, so the file should have a
+    /// single denom.
+    Stats {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+    },
+
+    /// Exclude removes the entries of the given addresses from the file consisting
+    /// of accounts and amounts in csv format at a given path, e.g. for compliance
+    /// removals. Writes the remaining entries to the output file and prints the
+    /// number of removed entries and the roots before and after the removal.
+    Exclude {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+
+        /// addresses the path to the file with the addresses to remove, one per line.
+        #[clap(long, parse(from_os_str))]
+        addresses: std::path::PathBuf,
+
+        /// out the path to write the remaining entries to in csv format.
+        #[clap(long, parse(from_os_str))]
+        out: std::path::PathBuf,
+
+        /// salt the hex encoded salt of the drop, see generate-root.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// address_only builds the trees of the addresses only, see generate-root.
+        #[clap(long)]
+        address_only: bool,
+
+        /// hash the hash function of the tree, see generate-root.
+        #[clap(long, arg_enum, default_value = "sha3-256")]
+        hash: hasher::Hasher,
+
+        /// default_amount the amount of the rows with an address only, see generate-root.
+        /// The rows are written with the default amount.
+        #[clap(long)]
+        default_amount: Option<String>,
+
+        /// address_col the header name of the address column, see generate-root.
+        #[clap(long)]
+        address_col: Option<String>,
+
+        /// amount_col the header name of the amount column, see generate-root.
+        #[clap(long)]
+        amount_col: Option<String>,
+
+        /// leaf_encoding the encoding of the amounts in the leaves, see generate-root.
+        #[clap(
+            long,
+            arg_enum,
+            default_value = "decimal-string",
+            conflicts_with = "address_only"
+        )]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts before building the leaves, see generate-root.
+        /// The amounts are written as they are given.
+        #[clap(long)]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, see generate-root.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, see generate-root.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
+    },
+
+    /// Canonicalize writes the entries of the file consisting of accounts and amounts
+    /// in csv format at a given path with trimmed fields, amounts without leading
+    /// zeros, sorted and without duplicate rows, so that the same entries always
+    /// produce an identical file and root. Prints the number of entries written,
+    /// the number of duplicates removed and the root of the written entries.
+    Canonicalize {
+        /// path the path to the file with accounts and amounts in csv format.
+        /// See example in testdata.
+        #[clap(parse(from_os_str))]
+        path: std::path::PathBuf,
+
+        /// out the path to write the canonical entries to in csv format.
+        #[clap(long, parse(from_os_str))]
+        out: std::path::PathBuf,
+
+        /// salt the hex encoded salt of the drop, see generate-root.
+        #[clap(long)]
+        salt: Option<String>,
+
+        /// address_only builds the trees of the addresses only, see generate-root.
+        #[clap(long)]
+        address_only: bool,
+
+        /// hash the hash function of the tree, see generate-root.
+        #[clap(long, arg_enum, default_value = "sha3-256")]
+        hash: hasher::Hasher,
+
+        /// default_amount the amount of the rows with an address only, see generate-root.
+        /// The rows are written with the default amount.
+        #[clap(long)]
+        default_amount: Option<String>,
+
+        /// address_col the header name of the address column, see generate-root.
+        #[clap(long)]
+        address_col: Option<String>,
+
+        /// amount_col the header name of the amount column, see generate-root.
+        #[clap(long)]
+        amount_col: Option<String>,
+
+        /// leaf_encoding the encoding of the amounts in the leaves, see generate-root.
+        #[clap(
+            long,
+            arg_enum,
+            default_value = "decimal-string",
+            conflicts_with = "address_only"
+        )]
+        leaf_encoding: LeafEncodingArg,
+
+        /// decimals converts the amounts before building the leaves, see generate-root.
+        /// The amounts are written as they are given.
+        #[clap(long)]
+        decimals: Option<u32>,
+
+        /// display_denom the denom of the decimal amounts, see generate-root.
+        #[clap(long, requires = "decimals")]
+        display_denom: Option<String>,
+
+        /// base_denom the denom of the converted amounts, see generate-root.
+        #[clap(long, requires = "decimals")]
+        base_denom: Option<String>,
     },
 
+    /// SelfTest rebuilds the trees of the test vectors shipped with the merkle
+    /// library and checks their roots and proofs, so that users can confirm that
+    /// their build of the binary produces the expected hashes. Prints the number
+    /// of vectors and of mismatches, and each mismatch.
+    SelfTest {},
+
     /// Hash hashes the given data with the same hasher as
     /// is used in the underlying Merkle tree.  Outputs the result to stdout.\
     /// Primarily used for debugging purposes and has no production use case.
@@ -79,22 +723,173 @@ enum Commands {
     },
 }
 
-fn generate_root_cmd(path: std::path::PathBuf) -> Result<(), Box<dyn Error>> {
-    let entries = parse_csv(path)?;
-    let hash = controller::generate_root(&entries);
+// LeafEncodingArg selects the merkle::leaf::LeafEncoding of the amounts in the leaves.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum LeafEncodingArg {
+    DecimalString,
+    U128Bytes,
+}
+
+impl From<LeafEncodingArg> for LeafEncoding {
+    fn from(arg: LeafEncodingArg) -> Self {
+        match arg {
+            LeafEncodingArg::DecimalString => LeafEncoding::DecimalString,
+            LeafEncodingArg::U128Bytes => LeafEncoding::U128Bytes,
+        }
+    }
+}
+
+#[derive(ArgEnum, Clone)]
+enum ProofFormat {
+    Json,
+    Hex,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn generate_root_cmd(
+    path: std::path::PathBuf,
+    require_single_denom: &Option<String>,
+    salt: &Option<String>,
+    strict_columns: bool,
+    address_only: bool,
+    hasher: hasher::Hasher,
+    default_amount: &Option<String>,
+    columns: &Columns,
+    decimals: &Option<Decimals>,
+    progress: bool,
+    emit_checksum: bool,
+    manifest: &Option<std::path::PathBuf>,
+    emit_level_roots: bool,
+    max_depth: Option<usize>,
+    leaf_encoding: LeafEncoding,
+) -> Result<(), CliError> {
+    let salt = decode_salt(salt).map_err(CliError::Validation)?;
+    let progress = Progress::new(progress);
+    let records = read_records_with_default_amount(path, default_amount, columns, &progress)?;
+    let records = decimals::convert_records(records, decimals)
+        .map_err(|err| CliError::Validation(err.into()))?;
+
+    if strict_columns {
+        controller::validate_column_counts(
+            &records,
+            &[ADDRESS_AMOUNT_COLUMN_COUNT, VESTING_COLUMN_COUNT],
+        )
+        .map_err(CliError::Validation)?;
+    }
+
+    if let Some(denom) = require_single_denom {
+        controller::validate_single_denom(&records, denom).map_err(CliError::Validation)?;
+    }
+
+    let entries = if address_only {
+        to_address_only_entries(&records)
+    } else {
+        to_entries(&records)
+    }
+    .map_err(CliError::Parse)?;
+    if entries.is_empty() {
+        return Err(CliError::Validation("the data set is empty".into()));
+    }
+    if emit_level_roots && hasher != hasher::Hasher::Sha3_256 {
+        return Err(CliError::Validation(
+            "--emit-level-roots supports sha3-256 trees only".into(),
+        ));
+    }
+
+    let depth = merkle::Tree::level_count(entries.len());
+    if let Some(max_depth) = max_depth {
+        if depth > max_depth {
+            return Err(CliError::Validation(
+                format!(
+                    "tree depth {} of {} entries exceeds the maximum depth of {}",
+                    depth,
+                    entries.len(),
+                    max_depth
+                )
+                .into(),
+            ));
+        }
+    }
+
+    let salted_entries = salt_entries(&salt, &encode_entries(leaf_encoding, &entries)?);
+    progress.start_tree(depth);
+    let on_level = || progress.level_combined();
+    // CONTRACT: entries is not empty.
+    let hash = hasher
+        .root_with_progress(&salted_entries, on_level)
+        .unwrap();
+    progress.finish();
     println!("{}", hash);
+    let checksum = controller::checksum(&records).map_err(CliError::Failure)?;
+    if emit_checksum {
+        println!("{}", checksum);
+    }
+    if let Some(manifest) = manifest {
+        let serialized = serde_json_wasm::to_string(&controller::Manifest {
+            root: hash.clone(),
+            checksum,
+            entry_count: entries.len(),
+            hash: String::from(hasher.name()),
+        })
+        .map_err(|err| CliError::Failure(err.into()))?;
+        fs::write(manifest, serialized)?;
+    }
+    if emit_level_roots {
+        for (level, level_root) in controller::level_roots(&salted_entries).iter().enumerate() {
+            println!("level {}: {}", level, level_root);
+        }
+    }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_proof_cmd(
     path: std::path::PathBuf,
     proof_for: &String,
     proof_out_path: &Option<std::path::PathBuf>,
     print: bool,
-) -> Result<(), Box<dyn Error>> {
-    let entries = parse_csv(path)?;
+    salt: &Option<String>,
+    embed_root: bool,
+    versioned: bool,
+    address_only: bool,
+    default_amount: &Option<String>,
+    columns: &Columns,
+    decimals: &Option<Decimals>,
+    leaf_encoding: LeafEncoding,
+) -> Result<(), CliError> {
+    let salt = decode_salt(salt).map_err(CliError::Validation)?;
+    let records =
+        read_records_with_default_amount(path, default_amount, columns, &Progress::hidden())?;
+    let records = decimals::convert_records(records, decimals)
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let entries = if address_only {
+        to_address_only_entries(&records)
+    } else {
+        to_entries(&records)
+    }
+    .map_err(CliError::Parse)?;
 
-    let proof = controller::get_proof(&entries, &proof_for.as_bytes().to_vec())?;
+    let salted_entries = salt_entries(&salt, &encode_entries(leaf_encoding, &entries)?);
+    let encoded_proof_for = merkle::leaf::with_encoding(leaf_encoding, proof_for.as_bytes())
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let salted_proof_for = merkle::leaf::with_salt(&salt, &encoded_proof_for);
+
+    let (index, proof) = if embed_root {
+        let (address, amount) =
+            find_address_amount(&records, &entries, proof_for).map_err(CliError::Validation)?;
+        controller::get_embedded_proof(&salted_entries, &salted_proof_for, address, &amount)
+    } else if versioned {
+        controller::get_versioned_proof(&salted_entries, &salted_proof_for, leaf_encoding)
+    } else {
+        controller::get_proof(&salted_entries, &salted_proof_for)
+    }
+    .map_err(CliError::Validation)?;
+
+    // stdout is reserved for the proof.
+    eprintln!(
+        "matched entry at row {}",
+        controller::row_number(index, &records[index])
+    );
 
     if print {
         println!("{}", proof);
@@ -107,17 +902,115 @@ fn generate_proof_cmd(
     Ok(())
 }
 
+fn generate_proofs_indexed_cmd(
+    path: std::path::PathBuf,
+    out: std::path::PathBuf,
+    salt: &Option<String>,
+    leaf_encoding: LeafEncoding,
+    decimals: &Option<Decimals>,
+) -> Result<(), CliError> {
+    let salt = decode_salt(salt).map_err(CliError::Validation)?;
+    let records = read_csv_records(path)?;
+    let records = decimals::convert_records(records, decimals)
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let entries = to_entries(&records).map_err(CliError::Parse)?;
+
+    // the amount is normalized as it is given to the contract's claim.
+    let addresses_amounts = records
+        .iter()
+        .map(|record| match record.len() {
+            ADDRESS_AMOUNT_COLUMN_COUNT => Ok((
+                record[0].to_string(),
+                merkle::leaf::normalize_amount(&record[1]),
+            )),
+            _ => Err(CliError::Validation(
+                format!(
+                    "record {:?} must have an address and an amount",
+                    record.as_slice()
+                )
+                .into(),
+            )),
+        })
+        .collect::<Result<Vec<(String, String)>, CliError>>()?;
+
+    let salted_entries = salt_entries(&salt, &encode_entries(leaf_encoding, &entries)?);
+    let (root, index) = controller::get_proof_index(&salted_entries, &addresses_amounts)
+        .map_err(CliError::Validation)?;
+    let serialized =
+        serde_json_wasm::to_string(&index).map_err(|err| CliError::Failure(err.into()))?;
+    fs::write(out, serialized)?;
+
+    println!("Proofs: {}", index.len());
+    println!("Root: {}", root);
+    Ok(())
+}
+
+fn evm_root_cmd(path: std::path::PathBuf) -> Result<(), CliError> {
+    let records = read_csv_records(path)?;
+    let leaves = to_evm_leaves(&records).map_err(CliError::Parse)?;
+
+    let root = evm::Tree::new(leaves)
+        .root()
+        .ok_or_else(|| CliError::Validation("the data set is empty".into()))?;
+    println!("{}", evm::to_hex(&root));
+    Ok(())
+}
+
+fn evm_proof_cmd(
+    path: std::path::PathBuf,
+    proof_for: &str,
+    proof_out_path: &Option<std::path::PathBuf>,
+    print: bool,
+) -> Result<(), CliError> {
+    let records = read_csv_records(path)?;
+    let leaves = to_evm_leaves(&records).map_err(CliError::Parse)?;
+
+    if proof_for.len() < evm::HEX_ADDRESS_LENGTH
+        || !proof_for.is_char_boundary(evm::HEX_ADDRESS_LENGTH)
+    {
+        return Err(CliError::Validation(
+            format!("{:?} must be an address followed by an amount", proof_for).into(),
+        ));
+    }
+    let (address, amount) = proof_for.split_at(evm::HEX_ADDRESS_LENGTH);
+    let leaf = evm::leaf(address, amount).map_err(CliError::Validation)?;
+
+    let proof = evm::Tree::new(leaves).proof(&leaf).ok_or_else(|| {
+        CliError::Validation(format!("failed to find proof for {:?}", proof_for).into())
+    })?;
+    let proof_hex: Vec<String> = proof.iter().map(evm::to_hex).collect();
+    let proof = serde_json_wasm::to_string(&proof_hex).map_err(|e| CliError::Failure(e.into()))?;
+
+    if print {
+        println!("{}", proof);
+    }
+
+    if let Some(proof_out_path) = proof_out_path {
+        fs::write(proof_out_path, proof)?;
+    }
+
+    Ok(())
+}
+
 fn verify_proof_cmd(
     root: &String,
     proof_path: std::path::PathBuf,
     to_verify: String,
-) -> Result<(), Box<dyn Error>> {
+    salt: &Option<String>,
+    leaf_encoding: LeafEncoding,
+) -> Result<(), CliError> {
+    let salt = decode_salt(salt).map_err(CliError::Validation)?;
     let data = fs::read_to_string(&proof_path)?;
 
-    let is_valid = controller::verify_proof(root, &data, to_verify)?;
+    let to_verify = merkle::leaf::with_encoding(leaf_encoding, to_verify.as_bytes())
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let to_verify = merkle::leaf::with_salt(&salt, &to_verify);
+    let is_valid = controller::verify_proof(root, &data, &to_verify).map_err(CliError::Parse)?;
 
     if !is_valid {
-        return Err("The proof is invalid. End result did not match the root hash".into());
+        return Err(CliError::Verification(
+            "The proof is invalid. End result did not match the root hash".into(),
+        ));
     }
 
     if is_valid {
@@ -127,6 +1020,332 @@ fn verify_proof_cmd(
     Ok(())
 }
 
+fn verify_proofs_dir_cmd(
+    dir: std::path::PathBuf,
+    root: &str,
+    salt: &Option<String>,
+    leaf_encoding: LeafEncoding,
+    decimals: &Option<Decimals>,
+) -> Result<(), CliError> {
+    let salt = decode_salt(salt).map_err(CliError::Validation)?;
+    let root_hash = merkle::parse_root(root).map_err(|err| CliError::Validation(err.into()))?;
+
+    let mut paths = fs::read_dir(&dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "json"));
+    paths.sort();
+    if paths.is_empty() {
+        return Err(CliError::Validation(
+            format!("no json proofs found in {}", dir.display()).into(),
+        ));
+    }
+
+    let mut failures = Vec::new();
+    for path in paths.iter() {
+        // CONTRACT: the path is a file with the json extension.
+        let address = path.file_stem().unwrap().to_string_lossy();
+        let res = fs::read_to_string(path)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|data| {
+                controller::verify_embedded_proof(
+                    &data,
+                    &address,
+                    &salt,
+                    leaf_encoding,
+                    decimals,
+                    &root_hash,
+                )
+            });
+        match res {
+            Ok(true) => {}
+            Ok(false) => failures.push((path, String::from("does not verify against the root"))),
+            Err(err) => failures.push((path, err.to_string())),
+        }
+    }
+
+    println!("Proofs: {}", paths.len());
+    println!("Passed: {}", paths.len() - failures.len());
+    println!("Failed: {}", failures.len());
+    for (path, reason) in failures.iter() {
+        println!("{}: {}", path.display(), reason);
+    }
+
+    if !failures.is_empty() {
+        return Err(CliError::Verification(
+            format!(
+                "{} proofs do not verify against root {}",
+                failures.len(),
+                root
+            )
+            .into(),
+        ));
+    }
+    Ok(())
+}
+
+// verify_proof_args returns the root, the data to verify and the proof path of
+// verify-proof. The root is the first positional argument unless the contract is
+// given instead, in which case the data to verify and the proof path come first.
+fn verify_proof_args(
+    root: &Option<String>,
+    to_verify: &Option<String>,
+    proof_path: &Option<std::path::PathBuf>,
+    contract: &Option<String>,
+) -> Result<(Option<String>, String, std::path::PathBuf), CliError> {
+    match (root, to_verify, proof_path, contract) {
+        (Some(root), Some(to_verify), Some(proof_path), None) => Ok((
+            Some(root.clone()),
+            to_verify.clone(),
+            proof_path.clone(),
+        )),
+        (Some(to_verify), Some(proof_path), None, Some(_)) => Ok((
+            None,
+            to_verify.clone(),
+            std::path::PathBuf::from(proof_path),
+        )),
+        (_, _, Some(_), Some(_)) => Err(CliError::Validation(
+            "the root cannot be given with --contract, its root is queried".into(),
+        )),
+        _ => Err(CliError::Validation(
+            "please provide the root, or --contract and --rpc, the data to verify and the proof path"
+                .into(),
+        )),
+    }
+}
+
+// resolve_root returns the given root or, if a contract is given instead,
+// the root of the deployed contract queried over the rpc endpoint.
+fn resolve_root(
+    root: &Option<String>,
+    contract: &Option<String>,
+    rpc: &Option<String>,
+    max_retries: u32,
+) -> Result<String, CliError> {
+    match (root, contract, rpc) {
+        (Some(root), _, _) => Ok(root.clone()),
+        (None, Some(contract), Some(rpc)) => {
+            let querier = chain::RetryingQuerier::new(chain::RpcClient::new(rpc), max_retries);
+            chain::query_root(&querier, contract).map_err(CliError::Failure)
+        }
+        _ => Err(CliError::Validation(
+            "please provide the root or the contract and rpc to query it from".into(),
+        )),
+    }
+}
+
+fn audit_drop_cmd(
+    path: std::path::PathBuf,
+    contract: &str,
+    rpc: &str,
+    max_retries: u32,
+    tree_options: &TreeOptions,
+) -> Result<(), CliError> {
+    let records = read_csv_records(path)?;
+    let entries = tree_leaves(&records, tree_options)?;
+
+    let querier = chain::RetryingQuerier::new(chain::RpcClient::new(rpc), max_retries);
+    let root = chain::query_root(&querier, contract).map_err(CliError::Failure)?;
+    let failures = controller::audit(&entries, &root).map_err(CliError::Parse)?;
+
+    println!("Entries: {}", entries.len());
+    println!("Passed: {}", entries.len() - failures.len());
+    println!("Failed: {}", failures.len());
+    for &i in failures.iter() {
+        println!(
+            "row {} {:?} cannot claim",
+            controller::row_number(i, &records[i]),
+            records[i].as_slice()
+        );
+    }
+
+    if !failures.is_empty() {
+        return Err(CliError::Verification(
+            format!(
+                "{} entries cannot claim against root {}",
+                failures.len(),
+                root
+            )
+            .into(),
+        ));
+    }
+    Ok(())
+}
+
+fn convert_proof_cmd(input: std::path::PathBuf, to: &ProofFormat) -> Result<(), CliError> {
+    let data = fs::read_to_string(&input)?;
+
+    let converted = match to {
+        ProofFormat::Json => controller::proof_hex_to_json(data.trim()),
+        ProofFormat::Hex => controller::proof_json_to_hex(data.trim()),
+    }
+    .map_err(CliError::Parse)?;
+
+    println!("{}", converted);
+    Ok(())
+}
+
+fn validate_cmd(path: std::path::PathBuf) -> Result<(), CliError> {
+    let records = read_csv_records(path)?;
+
+    // Make sure the records can be converted into tree entries.
+    to_entries(&records).map_err(CliError::Parse)?;
+
+    let count = controller::validate_records(&records).map_err(CliError::Validation)?;
+    println!("OK, {} valid entries", count);
+    Ok(())
+}
+
+fn validate_instantiate_cmd(root: &str, subdenom: &str, owner: &str) -> Result<(), CliError> {
+    let problems = controller::validate_instantiate(root, subdenom, owner);
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+
+    if !problems.is_empty() {
+        return Err(CliError::Validation(
+            format!("the instantiate message has {} problems", problems.len()).into(),
+        ));
+    }
+    println!("OK");
+    Ok(())
+}
+
+fn serve_cmd(
+    path: std::path::PathBuf,
+    host: &str,
+    port: u16,
+    leaf_encoding: LeafEncoding,
+    decimals: &Option<Decimals>,
+) -> Result<(), CliError> {
+    let records = read_csv_records(path)?;
+    let records = decimals::convert_records(records, decimals)
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let entries = encode_entries(
+        leaf_encoding,
+        &to_entries(&records).map_err(CliError::Parse)?,
+    )?;
+
+    let service = serve::ProofService::new(&records, &entries).map_err(CliError::Parse)?;
+    let server = tiny_http::Server::http((host, port))
+        .map_err(|err| CliError::Failure(err.to_string().into()))?;
+
+    eprintln!("serving proofs on {}:{}", host, port);
+    serve::serve(&service, &server).map_err(CliError::Failure)
+}
+
+fn estimate_gas_cmd(path: std::path::PathBuf, gas_per_hash: u64) -> Result<(), CliError> {
+    let entries = parse_csv(path)?;
+
+    let stats = controller::proof_stats(&entries);
+    let max_gas = stats.max_proof_length * gas_per_hash as u128;
+    let average_gas = stats.average_proof_length * gas_per_hash as f64;
+
+    println!("Entries: {}", stats.entry_count);
+    println!("Max proof length: {}", stats.max_proof_length);
+    println!("Average proof length: {:.2}", stats.average_proof_length);
+    println!("Max verification gas: {}", max_gas);
+    println!("Average verification gas: {:.0}", average_gas);
+    Ok(())
+}
+
+fn stats_cmd(path: std::path::PathBuf) -> Result<(), CliError> {
+    let records = read_csv_records(path)?;
+    let amounts = records
+        .iter()
+        .map(|record| Ok(Amount::parse(record.get(1).unwrap_or_default())?.value()))
+        .collect::<Result<Vec<u128>, Box<dyn Error>>>()
+        .map_err(CliError::Parse)?;
+
+    let total = amounts
+        .iter()
+        .try_fold(0u128, |total, amount| total.checked_add(*amount))
+        .ok_or_else(|| CliError::Validation("the total amount overflows".into()))?;
+    let entry_count = amounts.len();
+    let median = controller::median(amounts)
+        .map_or_else(|| String::from("N/A"), |median| median.to_string());
+
+    println!("Entries: {}", entry_count);
+    println!("Total amount: {}", total);
+    println!("Median amount: {}", median);
+    Ok(())
+}
+
+fn self_test_cmd() -> Result<(), CliError> {
+    let vectors = merkle::test_vectors::VECTORS;
+    let mismatches = controller::self_test(vectors);
+
+    println!("Vectors: {}", vectors.len());
+    println!("Mismatches: {}", mismatches.len());
+    for mismatch in mismatches.iter() {
+        println!("{}", mismatch);
+    }
+
+    if !mismatches.is_empty() {
+        return Err(CliError::Verification(
+            format!("{} mismatches with the test vectors", mismatches.len()).into(),
+        ));
+    }
+    Ok(())
+}
+
+fn exclude_cmd(
+    path: std::path::PathBuf,
+    addresses_path: std::path::PathBuf,
+    out: std::path::PathBuf,
+    default_amount: &Option<String>,
+    columns: &Columns,
+    tree_options: &TreeOptions,
+) -> Result<(), CliError> {
+    let (headers, records) =
+        read_csv_headers_and_records(path, default_amount, columns, &Progress::hidden())?;
+    let addresses: Vec<String> = fs::read_to_string(addresses_path)?
+        .lines()
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(String::from)
+        .collect();
+
+    let (kept, not_found) = controller::exclude_addresses(&records, &addresses);
+    if kept.is_empty() {
+        return Err(CliError::Validation("all entries are excluded".into()));
+    }
+    for address in not_found.iter() {
+        eprintln!("address {} has no entry", address);
+    }
+
+    let root_before = tree_root(&records, tree_options)?;
+    let root_after = tree_root(&kept, tree_options)?;
+
+    write_csv_records(out, &headers, &kept).map_err(CliError::Failure)?;
+
+    println!("Removed: {}", records.len() - kept.len());
+    println!("Root before: {}", root_before);
+    println!("Root after: {}", root_after);
+    Ok(())
+}
+
+fn canonicalize_cmd(
+    path: std::path::PathBuf,
+    out: std::path::PathBuf,
+    default_amount: &Option<String>,
+    columns: &Columns,
+    tree_options: &TreeOptions,
+) -> Result<(), CliError> {
+    let (headers, records) =
+        read_csv_headers_and_records(path, default_amount, columns, &Progress::hidden())?;
+
+    let canonical = controller::canonicalize_records(&records);
+    let root = tree_root(&canonical, tree_options)?;
+
+    write_csv_records(out, &headers, &canonical).map_err(CliError::Failure)?;
+
+    println!("Entries: {}", canonical.len());
+    println!("Duplicates removed: {}", records.len() - canonical.len());
+    println!("Root: {}", root);
+    Ok(())
+}
+
 fn hash_cmd(data: &String) {
     let hash = controller::hash(data);
     println!("Data: {}", data);
@@ -134,30 +1353,375 @@ fn hash_cmd(data: &String) {
     println!("Data Hash: {}", hash);
 }
 
-fn parse_csv(path: std::path::PathBuf) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+// to_evm_leaves converts each record of an address and an amount into an EVM compatible leaf.
+fn to_evm_leaves(records: &[csv::StringRecord]) -> Result<Vec<evm::Hash>, Box<dyn Error>> {
+    let mut leaves: Vec<evm::Hash> = Vec::new();
+
+    for record in records.iter() {
+        if record.len() != ADDRESS_AMOUNT_COLUMN_COUNT {
+            return Err(format!(
+                "record {:?} must have an address and an amount",
+                record.as_slice()
+            )
+            .into());
+        }
+        leaves.push(evm::leaf(record[0].trim(), record[1].trim())?);
+    }
+    Ok(leaves)
+}
+
+// find_address_amount returns the address and the normalized amount
+// of the record that is encoded into the given entry data.
+fn find_address_amount<'a>(
+    records: &'a [csv::StringRecord],
+    entries: &[Vec<u8>],
+    entry: &str,
+) -> Result<(&'a str, String), Box<dyn Error>> {
+    let record = entries
+        .iter()
+        .position(|e| e.as_slice() == entry.as_bytes())
+        .map(|i| &records[i])
+        .filter(|record| record.len() >= ADDRESS_AMOUNT_COLUMN_COUNT);
+
+    match record {
+        Some(record) => Ok((&record[0], merkle::leaf::normalize_amount(&record[1]))),
+        None => Err(format!("{:?} is not an address and amount entry", entry).into()),
+    }
+}
+
+// TreeOptions are the options of generate-root that change the root of the records,
+// given to the commands that print the root of records they write.
+struct TreeOptions {
+    salt: Option<String>,
+    address_only: bool,
+    hasher: hasher::Hasher,
+    leaf_encoding: LeafEncoding,
+    decimals: Option<Decimals>,
+}
+
+// tree_root returns the root of the records built as generate-root builds it, so that
+// it is the root the contract must be instantiated or updated with.
+fn tree_root(records: &[csv::StringRecord], options: &TreeOptions) -> Result<String, CliError> {
+    options
+        .hasher
+        .root_with_progress(&tree_leaves(records, options)?, || {})
+        .ok_or_else(|| CliError::Validation("the data set is empty".into()))
+}
+
+// tree_leaves returns the leaf data of the records, one per record, built as
+// generate-root builds them. The records are not changed, the amounts are
+// converted for the leaves only.
+fn tree_leaves(
+    records: &[csv::StringRecord],
+    options: &TreeOptions,
+) -> Result<Vec<Vec<u8>>, CliError> {
+    let salt = decode_salt(&options.salt).map_err(CliError::Validation)?;
+    let records = decimals::convert_records(records.to_vec(), &options.decimals)
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let entries = if options.address_only {
+        to_address_only_entries(&records)
+    } else {
+        to_entries(&records)
+    }
+    .map_err(CliError::Parse)?;
+
+    Ok(salt_entries(
+        &salt,
+        &encode_entries(options.leaf_encoding, &entries)?,
+    ))
+}
+
+// to_decimals returns the Decimals of the --decimals, --display-denom and --base-denom
+// arguments, or None if no decimals are given.
+fn to_decimals(
+    decimals: &Option<u32>,
+    display_denom: &Option<String>,
+    base_denom: &Option<String>,
+) -> Option<Decimals> {
+    decimals.map(|decimals| Decimals {
+        decimals,
+        display_denom: display_denom.clone(),
+        base_denom: base_denom.clone(),
+    })
+}
+
+// decode_salt decodes the hex encoded salt. No salt is the same as an empty salt.
+fn decode_salt(salt: &Option<String>) -> Result<Vec<u8>, Box<dyn Error>> {
+    match salt {
+        Some(salt) => Ok(hex::decode(salt)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+// encode_entries encodes the amounts of the leaves of the entries in the leaf encoding.
+fn encode_entries(
+    leaf_encoding: LeafEncoding,
+    entries: &[Vec<u8>],
+) -> Result<Vec<Vec<u8>>, CliError> {
+    entries
+        .iter()
+        .map(|entry| merkle::leaf::with_encoding(leaf_encoding, entry))
+        .collect::<Result<_, _>>()
+        .map_err(|err| CliError::Parse(err.into()))
+}
+
+// salt_entries prefixes each entry with the salt.
+fn salt_entries(salt: &[u8], entries: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    entries
+        .iter()
+        .map(|entry| merkle::leaf::with_salt(salt, entry))
+        .collect()
+}
+
+fn parse_csv(path: std::path::PathBuf) -> Result<Vec<Vec<u8>>, CliError> {
+    let records = read_csv_records(path)?;
+    to_entries(&records).map_err(CliError::Parse)
+}
+
+// read_csv_records reads all records of the csv file at the given path,
+// or downloaded from it if it is an http or https URL, see download::open.
+// Invalid utf-8 and io errors part way through the file are returned as errors.
+// As all records are read before any output, no partial result is ever printed.
+fn read_csv_records(path: std::path::PathBuf) -> Result<Vec<csv::StringRecord>, CliError> {
+    read_csv_records_with(path, &Columns::default(), &Progress::hidden())
+}
+
+// read_csv_records_with is the same as read_csv_records but finds the address
+// and amount columns by the given names and reports each parsed record to the progress.
+fn read_csv_records_with(
+    path: std::path::PathBuf,
+    columns: &Columns,
+    progress: &Progress,
+) -> Result<Vec<csv::StringRecord>, CliError> {
     // Build the CSV reader and iterate over each record.
-    let mut csv_reader = csv::Reader::from_path(path)?;
+    read_records(
+        csv::Reader::from_reader(download::open(&path)?),
+        columns,
+        progress,
+    )
+}
 
-    let mut entries: Vec<Vec<u8>> = Vec::<Vec<u8>>::new();
+// write_csv_records writes the header and the records to the csv file at the given path.
+fn write_csv_records(
+    path: std::path::PathBuf,
+    headers: &csv::StringRecord,
+    records: &[csv::StringRecord],
+) -> Result<(), Box<dyn Error>> {
+    let mut csv_writer = csv::Writer::from_path(path)?;
+    csv_writer.write_record(headers)?;
+    for record in records.iter() {
+        csv_writer.write_record(record)?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+// read_records reads the records with the address and amount columns first,
+// see ColumnMapping.
+fn read_records<R: std::io::Read>(
+    csv_reader: csv::Reader<R>,
+    columns: &Columns,
+    progress: &Progress,
+) -> Result<Vec<csv::StringRecord>, CliError> {
+    read_headers_and_records(csv_reader, columns, progress).map(|(_, records)| records)
+}
+
+// read_headers_and_records is the same as read_records but also returns the cleaned
+// header record in the order of the fields of the records. Both are read in a single
+// pass so that a downloaded file is fetched once and they come from the same response.
+fn read_headers_and_records<R: std::io::Read>(
+    mut csv_reader: csv::Reader<R>,
+    columns: &Columns,
+    progress: &Progress,
+) -> Result<(csv::StringRecord, Vec<csv::StringRecord>), CliError> {
+    let mapping = ColumnMapping::from_headers(csv_reader.headers()?, columns)
+        .map_err(|err| CliError::Validation(err.into()))?;
+    let headers = mapping.apply(&clean_record(csv_reader.headers()?));
+    let mut records: Vec<csv::StringRecord> = Vec::<csv::StringRecord>::new();
 
     for str_record in csv_reader.records() {
         // The iterator yields Result<StringRecord, Error>, so we check the
         // error here.
-        let entry = str_record?;
+        let record = clean_record(&str_record?);
+        // blank rows, e.g. the trailing empty rows of spreadsheet exports, are
+        // skipped rather than hashed into a leaf of empty data.
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        records.push(mapping.apply(&record));
+        progress.row_parsed();
+    }
+    Ok((headers, records))
+}
+
+// read_records_with_default_amount reads the records of the csv file at the given path.
+// If a default amount is given, records with an address only are accepted and
+// get the default amount while the amounts of the other records are kept.
+fn read_records_with_default_amount(
+    path: std::path::PathBuf,
+    default_amount: &Option<String>,
+    columns: &Columns,
+    progress: &Progress,
+) -> Result<Vec<csv::StringRecord>, CliError> {
+    read_csv_headers_and_records(path, default_amount, columns, progress)
+        .map(|(_, records)| records)
+}
+
+// read_csv_headers_and_records is the same as read_records_with_default_amount
+// but also returns the header record, see read_headers_and_records.
+fn read_csv_headers_and_records(
+    path: std::path::PathBuf,
+    default_amount: &Option<String>,
+    columns: &Columns,
+    progress: &Progress,
+) -> Result<(csv::StringRecord, Vec<csv::StringRecord>), CliError> {
+    let default_amount = match default_amount {
+        Some(default_amount) => default_amount,
+        None => {
+            return read_headers_and_records(
+                csv::Reader::from_reader(download::open(&path)?),
+                columns,
+                progress,
+            )
+        }
+    };
+    controller::validate_amount(default_amount)
+        .map_err(|err| CliError::Validation(format!("default amount {}", err).into()))?;
 
-        entries.push(Vec::<u8>::from(entry.as_slice()));
+    // records with an address only have fewer fields than the header.
+    let (headers, mut records) = read_headers_and_records(
+        csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(download::open(&path)?),
+        columns,
+        progress,
+    )?;
+    for record in records.iter_mut() {
+        if record.len() == 1 {
+            record.push_field(default_amount);
+        }
+    }
+    Ok((headers, records))
+}
+
+// UTF8_BOM is the byte order mark that spreadsheet exports often prefix to the first field.
+const UTF8_BOM: char = '\u{feff}';
+
+// clean_record strips the byte order mark and the surrounding whitespace of every field.
+// An untrimmed address produces a different leaf, leaving the entry unclaimable.
+fn clean_record(record: &csv::StringRecord) -> csv::StringRecord {
+    record.iter().map(clean_field).collect()
+}
+
+// clean_field strips the byte order mark and the surrounding whitespace of the field.
+fn clean_field(field: &str) -> &str {
+    field.trim_start_matches(UTF8_BOM).trim()
+}
+
+// to_entries converts each record into a tree entry.
+// Records with an address and an amount and, optionally, vesting columns
+// are encoded as a merkle::entry::ClaimEntry, any other record by concatenating its fields.
+fn to_entries(records: &[csv::StringRecord]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+    let mut entries: Vec<Vec<u8>> = Vec::<Vec<u8>>::new();
+
+    for record in records.iter() {
+        let vesting = if record.len() == VESTING_COLUMN_COUNT {
+            let cliff: u64 = record[2].trim().parse()?;
+            let duration: u64 = record[3].trim().parse()?;
+            Some((cliff, duration))
+        } else if record.len() == ADDRESS_AMOUNT_COLUMN_COUNT {
+            None
+        } else {
+            entries.push(Vec::<u8>::from(record.as_slice()));
+            continue;
+        };
+
+        let entry = ClaimEntry {
+            address: Address::new(&record[0])?,
+            amount: Amount::parse(&record[1])?,
+            vesting,
+        };
+        entries.push(entry.to_leaf_bytes());
     }
     Ok(entries)
 }
 
+// to_address_only_entries converts each record into the tree entry of its address.
+// The amounts of an address-only drop are set in the contract instead.
+fn to_address_only_entries(records: &[csv::StringRecord]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+    records
+        .iter()
+        .map(|record| match record.get(0) {
+            Some(address) if !address.is_empty() => {
+                Ok(merkle::leaf::encode_address_only(address).into_bytes())
+            }
+            _ => Err(format!("record {:?} has no address", record.as_slice()).into()),
+        })
+        .collect()
+}
+
+// exit_with_error reports the error on stderr, prefixed with the context
+// unless json_errors is set, and exits with the exit code of its class.
+fn exit_with_error(context: &str, err: CliError, json_errors: bool) -> ! {
+    if json_errors {
+        eprintln!("{}", err.to_json());
+    } else {
+        eprintln!("{}: {}", context, err);
+    }
+    process::exit(err.exit_code())
+}
+
 fn main() {
     let cli = Cli::parse();
 
     match &cli.command {
-        Some(Commands::GenerateRoot { path }) => {
-            if let Err(err) = generate_root_cmd(path.to_path_buf()) {
-                eprintln!("error generating merkle root: {}", err);
-                process::exit(1);
+        Some(Commands::GenerateRoot {
+            path,
+            require_single_denom,
+            salt,
+            strict_columns,
+            evm_compat,
+            address_only,
+            hash,
+            default_amount,
+            progress,
+            address_col,
+            amount_col,
+            emit_checksum,
+            manifest,
+            emit_level_roots,
+            max_depth,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            let res = if *evm_compat {
+                evm_root_cmd(path.to_path_buf())
+            } else {
+                generate_root_cmd(
+                    path.to_path_buf(),
+                    require_single_denom,
+                    salt,
+                    *strict_columns,
+                    *address_only,
+                    *hash,
+                    default_amount,
+                    &Columns {
+                        address: address_col.clone(),
+                        amount: amount_col.clone(),
+                    },
+                    &to_decimals(decimals, display_denom, base_denom),
+                    *progress,
+                    *emit_checksum,
+                    manifest,
+                    *emit_level_roots,
+                    *max_depth,
+                    (*leaf_encoding).into(),
+                )
+            };
+            if let Err(err) = res {
+                exit_with_error("error generating merkle root", err, cli.json_errors);
             }
         }
         Some(Commands::GenerateProof {
@@ -165,46 +1729,315 @@ fn main() {
             proof_for: data,
             proof_out_path,
             print,
+            salt,
+            evm_compat,
+            embed_root,
+            versioned,
+            address_only,
+            leaf_encoding,
+            default_amount,
+            address_col,
+            amount_col,
+            decimals,
+            display_denom,
+            base_denom,
         }) => {
             if proof_out_path.is_none() && !print {
-                eprintln!("please provide a proof_out_path argument or set --print flag to true");
-                process::exit(1);
+                exit_with_error(
+                    "invalid arguments",
+                    CliError::Validation(
+                        "please provide a proof_out_path argument or set --print flag to true"
+                            .into(),
+                    ),
+                    cli.json_errors,
+                );
             }
 
-            if let Err(err) = generate_proof_cmd(path.to_path_buf(), data, proof_out_path, *print) {
-                eprintln!("error generating merkle proof: {}", err);
-                process::exit(1);
+            let res = if *evm_compat {
+                evm_proof_cmd(path.to_path_buf(), data, proof_out_path, *print)
+            } else {
+                generate_proof_cmd(
+                    path.to_path_buf(),
+                    data,
+                    proof_out_path,
+                    *print,
+                    salt,
+                    *embed_root,
+                    *versioned,
+                    *address_only,
+                    default_amount,
+                    &Columns {
+                        address: address_col.clone(),
+                        amount: amount_col.clone(),
+                    },
+                    &to_decimals(decimals, display_denom, base_denom),
+                    (*leaf_encoding).into(),
+                )
+            };
+            if let Err(err) = res {
+                exit_with_error("error generating merkle proof", err, cli.json_errors);
+            }
+        }
+        Some(Commands::GenerateProofsIndexed {
+            path,
+            out,
+            salt,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            if let Err(err) = generate_proofs_indexed_cmd(
+                path.to_path_buf(),
+                out.to_path_buf(),
+                salt,
+                (*leaf_encoding).into(),
+                &to_decimals(decimals, display_denom, base_denom),
+            ) {
+                exit_with_error("error generating merkle proofs", err, cli.json_errors);
             }
         }
         Some(Commands::VerifyProof {
             root,
+            contract,
+            rpc,
+            max_retries,
             proof_path,
             to_verify,
+            salt,
+            leaf_encoding,
         }) => {
+            let (root, to_verify, proof_path) =
+                match verify_proof_args(root, to_verify, proof_path, contract) {
+                    Ok(args) => args,
+                    Err(err) => exit_with_error("invalid arguments", err, cli.json_errors),
+                };
+            let root = match resolve_root(&root, contract, rpc, *max_retries) {
+                Ok(root) => root,
+                Err(err) => {
+                    exit_with_error("error querying merkle root", err, cli.json_errors);
+                }
+            };
             if root.is_empty() {
-                eprintln!("root was empty, please provide the Merkle root hash base16 encoded");
-                process::exit(1);
+                exit_with_error(
+                    "invalid arguments",
+                    CliError::Validation(
+                        "root was empty, please provide the Merkle root hash base16 encoded".into(),
+                    ),
+                    cli.json_errors,
+                );
             }
             if !proof_path.is_file() {
-                eprintln!("given path does not point to a file, please verify its correctness");
-                process::exit(1);
+                exit_with_error(
+                    "invalid arguments",
+                    CliError::Validation(
+                        "given path does not point to a file, please verify its correctness".into(),
+                    ),
+                    cli.json_errors,
+                );
             }
 
             if let Err(err) =
-                verify_proof_cmd(root, proof_path.to_path_buf(), to_verify.to_string())
+                verify_proof_cmd(&root, proof_path, to_verify, salt, (*leaf_encoding).into())
             {
-                eprintln!("error verifyin merkle proof: {}", err);
-                process::exit(1);
+                exit_with_error("error verifyin merkle proof", err, cli.json_errors);
+            }
+        }
+        Some(Commands::VerifyProofsDir {
+            dir,
+            root,
+            salt,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            if let Err(err) = verify_proofs_dir_cmd(
+                dir.to_path_buf(),
+                root,
+                salt,
+                (*leaf_encoding).into(),
+                &to_decimals(decimals, display_denom, base_denom),
+            ) {
+                exit_with_error("error verifying merkle proofs", err, cli.json_errors);
+            }
+        }
+        Some(Commands::AuditDrop {
+            path,
+            contract,
+            rpc,
+            max_retries,
+            salt,
+            address_only,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            if let Err(err) = audit_drop_cmd(
+                path.to_path_buf(),
+                contract,
+                rpc,
+                *max_retries,
+                &TreeOptions {
+                    salt: salt.clone(),
+                    address_only: *address_only,
+                    // the contract verifies sha3-256 trees only.
+                    hasher: hasher::Hasher::Sha3_256,
+                    leaf_encoding: (*leaf_encoding).into(),
+                    decimals: to_decimals(decimals, display_denom, base_denom),
+                },
+            ) {
+                exit_with_error("error auditing drop", err, cli.json_errors);
+            }
+        }
+        Some(Commands::ConvertProof { input, to }) => {
+            if !input.is_file() {
+                exit_with_error(
+                    "invalid arguments",
+                    CliError::Validation(
+                        "given path does not point to a file, please verify its correctness".into(),
+                    ),
+                    cli.json_errors,
+                );
+            }
+
+            if let Err(err) = convert_proof_cmd(input.to_path_buf(), to) {
+                exit_with_error("error converting merkle proof", err, cli.json_errors);
+            }
+        }
+        Some(Commands::Validate { path }) => {
+            if let Err(err) = validate_cmd(path.to_path_buf()) {
+                exit_with_error("error validating data set", err, cli.json_errors);
+            }
+        }
+        Some(Commands::ValidateInstantiate {
+            root,
+            subdenom,
+            owner,
+        }) => {
+            if let Err(err) = validate_instantiate_cmd(root, subdenom, owner) {
+                exit_with_error("error validating instantiate message", err, cli.json_errors);
+            }
+        }
+        Some(Commands::Serve {
+            path,
+            port,
+            host,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            if let Err(err) = serve_cmd(
+                path.to_path_buf(),
+                host,
+                *port,
+                (*leaf_encoding).into(),
+                &to_decimals(decimals, display_denom, base_denom),
+            ) {
+                exit_with_error("error serving proofs", err, cli.json_errors);
+            }
+        }
+        Some(Commands::SelfTest {}) => {
+            if let Err(err) = self_test_cmd() {
+                exit_with_error("error running self-test", err, cli.json_errors);
+            }
+        }
+        Some(Commands::Stats { path }) => {
+            if let Err(err) = stats_cmd(path.to_path_buf()) {
+                exit_with_error("error computing stats", err, cli.json_errors);
+            }
+        }
+        Some(Commands::EstimateGas { path, gas_per_hash }) => {
+            if let Err(err) = estimate_gas_cmd(path.to_path_buf(), *gas_per_hash) {
+                exit_with_error("error estimating gas", err, cli.json_errors);
+            }
+        }
+        Some(Commands::Exclude {
+            path,
+            addresses,
+            out,
+            salt,
+            address_only,
+            hash,
+            default_amount,
+            address_col,
+            amount_col,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            if let Err(err) = exclude_cmd(
+                path.to_path_buf(),
+                addresses.to_path_buf(),
+                out.to_path_buf(),
+                default_amount,
+                &Columns {
+                    address: address_col.clone(),
+                    amount: amount_col.clone(),
+                },
+                &TreeOptions {
+                    salt: salt.clone(),
+                    address_only: *address_only,
+                    hasher: *hash,
+                    leaf_encoding: (*leaf_encoding).into(),
+                    decimals: to_decimals(decimals, display_denom, base_denom),
+                },
+            ) {
+                exit_with_error("error excluding addresses", err, cli.json_errors);
+            }
+        }
+        Some(Commands::Canonicalize {
+            path,
+            out,
+            salt,
+            address_only,
+            hash,
+            default_amount,
+            address_col,
+            amount_col,
+            leaf_encoding,
+            decimals,
+            display_denom,
+            base_denom,
+        }) => {
+            if let Err(err) = canonicalize_cmd(
+                path.to_path_buf(),
+                out.to_path_buf(),
+                default_amount,
+                &Columns {
+                    address: address_col.clone(),
+                    amount: amount_col.clone(),
+                },
+                &TreeOptions {
+                    salt: salt.clone(),
+                    address_only: *address_only,
+                    hasher: *hash,
+                    leaf_encoding: (*leaf_encoding).into(),
+                    decimals: to_decimals(decimals, display_denom, base_denom),
+                },
+            ) {
+                exit_with_error("error canonicalizing csv", err, cli.json_errors);
             }
         }
         Some(Commands::Hash { data }) => {
             if data.is_empty() {
-                eprintln!("data was empty, please provide something to hash");
-                process::exit(1);
+                exit_with_error(
+                    "invalid arguments",
+                    CliError::Validation("data was empty, please provide something to hash".into()),
+                    cli.json_errors,
+                );
             }
 
             hash_cmd(data)
         }
-        None => {}
+        None => {
+            // print_help only fails if stdout is closed,
+            // in which case there is nowhere to report it.
+            let _ = Cli::command().print_help();
+            process::exit(error::EXIT_CODE_VALIDATION);
+        }
     }
 }
@@ -0,0 +1,55 @@
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+// Progress reports the rows parsed and the tree levels combined while
+// building the tree of a huge data set so that a long run is not mistaken
+// for a hung one. It is drawn on stderr to keep stdout clean for the root
+// and does nothing unless enabled.
+pub struct Progress {
+    bar: ProgressBar,
+}
+
+impl Progress {
+    pub fn new(enabled: bool) -> Self {
+        if !enabled {
+            return Progress::hidden();
+        }
+
+        // the position counts the parsed rows until the tree is built.
+        let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr());
+        bar.set_style(ProgressStyle::with_template("{spinner} parsed {pos} rows").unwrap());
+        Progress { bar }
+    }
+
+    // hidden returns a progress that draws nothing, for commands without --progress.
+    pub fn hidden() -> Self {
+        Progress {
+            bar: ProgressBar::hidden(),
+        }
+    }
+
+    pub fn row_parsed(&self) {
+        self.bar.inc(1);
+    }
+
+    // start_tree switches from counting the rows to a bar of the
+    // level_count levels of the tree to combine.
+    pub fn start_tree(&self, level_count: usize) {
+        self.bar
+            .set_message(format!("parsed {} rows", self.bar.position()));
+        self.bar.set_style(
+            ProgressStyle::with_template("{msg}, [{bar:40}] {pos}/{len} levels combined")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        self.bar.set_length(level_count as u64);
+        self.bar.set_position(0);
+    }
+
+    pub fn level_combined(&self) {
+        self.bar.inc(1);
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
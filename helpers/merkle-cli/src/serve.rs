@@ -0,0 +1,179 @@
+use merkle::proof::Proof;
+use merkle::Tree;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+// PROOF_PATH_PREFIX is the prefix of the path of proof requests, followed by the address.
+const PROOF_PATH_PREFIX: &str = "/proof/";
+
+// ProofResponse is the body of the response to a proof request.
+// The vesting parameters are only given if the entry vests.
+#[derive(Serialize)]
+struct ProofResponse<'a> {
+    address: &'a str,
+    amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cliff: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u64>,
+    proof: Proof,
+}
+
+// ErrorResponse is the body of the response to a failed request.
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+// error_body returns the body of the response to a failed request with the error.
+fn error_body(error: String) -> String {
+    // CONTRACT: a struct of a string always serializes.
+    serde_json_wasm::to_string(&ErrorResponse { error }).unwrap()
+}
+
+// ProofService serves the proofs of a data set by address.
+// The responses are built once so that requests are only lookups.
+pub struct ProofService {
+    responses: HashMap<String, String>,
+}
+
+impl ProofService {
+    // new builds the tree of the entries and the response of each record
+    // with an address and an amount. If an address has several entries,
+    // the first one is served.
+    pub fn new(records: &[csv::StringRecord], entries: &[Vec<u8>]) -> Result<Self, Box<dyn Error>> {
+        let tree = Tree::new(entries);
+
+        let mut responses: HashMap<String, String> = HashMap::new();
+        for (record, entry) in records.iter().zip(entries.iter()) {
+            if record.len() < crate::ADDRESS_AMOUNT_COLUMN_COUNT
+                || responses.contains_key(&record[0])
+            {
+                continue;
+            }
+
+            let (cliff, duration) = if record.len() == crate::VESTING_COLUMN_COUNT {
+                (Some(record[2].parse()?), Some(record[3].parse()?))
+            } else {
+                (None, None)
+            };
+
+            let response = ProofResponse {
+                address: &record[0],
+                amount: merkle::leaf::normalize_amount(&record[1]),
+                cliff,
+                duration,
+                // a single entry tree has an empty proof.
+                proof: tree.find_proof(entry).unwrap_or_default(),
+            };
+            responses.insert(
+                record[0].to_string(),
+                serde_json_wasm::to_string(&response)?,
+            );
+        }
+
+        Ok(ProofService { responses })
+    }
+
+    // respond returns the status code and the body of the response to a GET of the url.
+    fn respond(&self, url: &str) -> (u16, String) {
+        let address = match url.strip_prefix(PROOF_PATH_PREFIX) {
+            Some(address) if !address.is_empty() => address,
+            _ => return (404, error_body(String::from("not found"))),
+        };
+
+        match self.responses.get(address) {
+            Some(response) => (200, response.clone()),
+            None => (404, error_body(format!("no entry for address {}", address))),
+        }
+    }
+}
+
+// serve answers the requests to the server until it is shut down. A response
+// that cannot be sent, e.g. to a client that disconnected, is logged and the
+// next requests are answered.
+pub fn serve(service: &ProofService, server: &tiny_http::Server) -> Result<(), Box<dyn Error>> {
+    let content_type =
+        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+            .map_err(|_| "invalid content type header")?;
+
+    for request in server.incoming_requests() {
+        let (status, body) = if *request.method() == tiny_http::Method::Get {
+            service.respond(request.url())
+        } else {
+            (405, error_body(String::from("method not allowed")))
+        };
+
+        let response = tiny_http::Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(err) = request.respond(response) {
+            eprintln!("error responding to request: {}", err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+
+    fn address_amount_service() -> ProofService {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("testdata")
+            .join("address_amount.csv");
+        let records = crate::read_csv_records(path).unwrap();
+        let entries = crate::to_entries(&records).unwrap();
+
+        ProofService::new(&records, &entries).unwrap()
+    }
+
+    fn get(addr: std::net::SocketAddr, path: &str) -> (u16, String) {
+        match ureq::get(&format!("http://{}{}", addr, path)).call() {
+            Ok(response) => (response.status(), response.into_string().unwrap()),
+            Err(ureq::Error::Status(status, response)) => (status, response.into_string().unwrap()),
+            Err(err) => panic!("request failed: {}", err),
+        }
+    }
+
+    #[test]
+    fn serve_known_and_unknown_address() {
+        let service = address_amount_service();
+
+        // the address is escaped in the json body.
+        assert_eq!(
+            (
+                404,
+                String::from("{\"error\":\"no entry for address osmo1\\\"}\"}")
+            ),
+            service.respond(&format!("{}osmo1\"}}", PROOF_PATH_PREFIX))
+        );
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr();
+
+        std::thread::spawn(move || serve(&service, &server).unwrap());
+
+        let (status, body) = get(addr, &format!("{}{}", PROOF_PATH_PREFIX, ADDRESS));
+        assert_eq!(200, status);
+        assert!(
+            body.starts_with(&format!(
+                "{{\"address\":\"{}\",\"amount\":\"1421901\",\"proof\":[",
+                ADDRESS
+            )),
+            "{}",
+            body
+        );
+
+        let (status, _) = get(addr, &format!("{}osmo1unknown", PROOF_PATH_PREFIX));
+        assert_eq!(404, status);
+
+        let (status, _) = get(addr, "/other");
+        assert_eq!(404, status);
+    }
+}
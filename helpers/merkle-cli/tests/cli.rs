@@ -0,0 +1,1180 @@
+use assert_cmd::Command;
+use proptest::prelude::*;
+use std::path::PathBuf;
+
+// ADDRESS_AMOUNT_ROOT is the root of "testdata/address_amount.csv".
+const ADDRESS_AMOUNT_ROOT: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+
+fn testdata(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("testdata")
+        .join(file_name)
+}
+
+// golden returns the expected output stored in the tests/golden directory.
+// The root and proofs are consumed verbatim by deployment scripts
+// so any change to the output format must be deliberate.
+fn golden(file_name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(file_name);
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn merkle_cli() -> Command {
+    Command::cargo_bin("merkle-cli").unwrap()
+}
+
+#[test]
+fn no_command_prints_help() {
+    merkle_cli()
+        .assert()
+        .failure()
+        .code(3)
+        .stdout(predicates::str::contains("USAGE"));
+}
+
+#[test]
+fn generate_root_success() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+}
+
+#[test]
+fn generate_root_emit_level_roots() {
+    // the level roots of the fixture are golden so that a change of the tree
+    // building is caught at the first level it changes.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--emit-level-roots")
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}\n{}",
+            ADDRESS_AMOUNT_ROOT,
+            "level 0: Nx1RTOSx5C+of46or2Mx6vayKnLSlAwB53NFudZRJr0=\n\
+             level 1: JUkeOgMeBuJk2Wy0gxyKoRc+LtuDxNe2rhyfS0BSdCY=\n\
+             level 2: er0c4gZlJxL8WLyvXEyw7AfzmBEffe3snp5OODVH+Sk=\n\
+             level 3: A90JrY3koc59CLXVMC5E/Zz4Yq4oMbtZVIbTYlQ+748=\n\
+             level 4: 32/jBs3jDqAby8hGRShvgo++NTwzMWZ1Kf/GlTVlUYw=\n"
+        ));
+}
+
+#[test]
+fn generate_root_emit_checksum() {
+    #[derive(serde::Deserialize)]
+    struct Manifest {
+        root: String,
+        checksum: String,
+        entry_count: usize,
+        hash: String,
+    }
+
+    let generate_root = |path: &PathBuf| -> (String, String) {
+        let output = merkle_cli()
+            .arg("generate-root")
+            .arg(path)
+            .arg("--emit-checksum")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(2, lines.len(), "{}", stdout);
+        (lines[0].to_string(), lines[1].to_string())
+    };
+
+    let (root, checksum) = generate_root(&testdata("address_amount.csv"));
+    assert_eq!(ADDRESS_AMOUNT_ROOT, root);
+    assert_eq!(64, checksum.len());
+
+    // the padded fixture only adds a byte order mark and whitespace.
+    assert_eq!(
+        (root.clone(), checksum.clone()),
+        generate_root(&testdata("address_amount_padded.csv"))
+    );
+
+    // a zero padded amount has the same leaf but not the same checksum.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let padded_path = temp_dir.path().join("checksum_padded.csv");
+    let data = std::fs::read_to_string(testdata("address_amount.csv")).unwrap();
+    std::fs::write(
+        &padded_path,
+        data.replacen(
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4,10",
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4,010",
+            1,
+        ),
+    )
+    .unwrap();
+    let (padded_root, padded_checksum) = generate_root(&padded_path);
+    assert_eq!(root, padded_root);
+    assert_ne!(checksum, padded_checksum);
+
+    let manifest_path = temp_dir.path().join("manifest.json");
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    let manifest: Manifest =
+        serde_json_wasm::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    assert_eq!(ADDRESS_AMOUNT_ROOT, manifest.root);
+    assert_eq!(checksum, manifest.checksum);
+    assert_eq!(12, manifest.entry_count);
+    assert_eq!("sha3-256", manifest.hash);
+}
+
+#[test]
+fn generate_root_max_depth() {
+    // the 12 entries of the fixture make a tree of depth 4.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--max-depth")
+        .arg("4")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--max-depth")
+        .arg("3")
+        .assert()
+        .code(3)
+        .stdout("")
+        .stderr(predicates::str::contains(
+            "tree depth 4 of 12 entries exceeds the maximum depth of 3",
+        ));
+}
+
+#[test]
+fn generate_root_leaf_encoding() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--leaf-encoding")
+        .arg("decimal-string")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    // the amounts hashed as u128 bytes make another root of the same entries.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--leaf-encoding")
+        .arg("u128-bytes")
+        .assert()
+        .success()
+        .stdout("I/4RLirzczzmE8wjt/MrgUd2cmMvCKzghSKkOdo+QYo=\n");
+
+    merkle_cli()
+        .arg("generate-proof")
+        .arg(testdata("address_amount.csv"))
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+        .arg("--print")
+        .arg("--leaf-encoding")
+        .arg("u128-bytes")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "[{\"is_left_sibling\":true,\"hash\":[99,71,80,74,",
+        ));
+
+    // the proof verifies in the leaf encoding it is generated with only.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let proof_path = temp_dir.path().join("u128_bytes_proof.json");
+    merkle_cli()
+        .arg("generate-proof")
+        .arg(testdata("address_amount.csv"))
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+        .arg(&proof_path)
+        .arg("--leaf-encoding")
+        .arg("u128-bytes")
+        .assert()
+        .success();
+    for (leaf_encoding, code) in [("u128-bytes", 0), ("decimal-string", 4)] {
+        merkle_cli()
+            .arg("verify-proof")
+            .arg("I/4RLirzczzmE8wjt/MrgUd2cmMvCKzghSKkOdo+QYo=")
+            .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+            .arg(&proof_path)
+            .arg("--leaf-encoding")
+            .arg(leaf_encoding)
+            .assert()
+            .code(code);
+    }
+}
+
+// serve_csv serves the body with the content type to the given number of requests
+// on a local port and returns the URL of the csv.
+fn serve_csv(body: String, content_type: &'static str, requests: usize) -> String {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/drop.csv", server.server_addr());
+    std::thread::spawn(move || {
+        for request in server.incoming_requests().take(requests) {
+            let header =
+                tiny_http::Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+                    .unwrap();
+            let response = tiny_http::Response::from_string(body.clone()).with_header(header);
+            request.respond(response).unwrap();
+        }
+    });
+    url
+}
+
+#[test]
+fn generate_root_from_url() {
+    let body = std::fs::read_to_string(testdata("address_amount.csv")).unwrap();
+
+    merkle_cli()
+        .arg("generate-root")
+        .arg(serve_csv(body.clone(), "text/csv", 1))
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    // e.g. the html of a login page instead of the csv.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(serve_csv(body, "text/html", 1))
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains("not csv"));
+}
+
+#[test]
+fn generate_root_swapped_columns() {
+    // the swapped fixture is address_amount.csv with the amount column first.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("swapped_columns.csv"))
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+}
+
+#[test]
+fn generate_root_custom_columns() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("custom_columns.csv"))
+        .arg("--address-col")
+        .arg("wallet")
+        .arg("--amount-col")
+        .arg("tokens")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("custom_columns.csv"))
+        .arg("--address-col")
+        .arg("address")
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains("not found in the header"));
+}
+
+#[test]
+fn generate_root_bom_and_padding() {
+    // the padded fixture is address_amount.csv with a byte order mark
+    // and whitespace around the fields.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount_padded.csv"))
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+}
+
+#[test]
+fn generate_root_trailing_blank_rows() {
+    // the fixture is address_amount.csv followed by an empty line
+    // and a row of empty fields.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount_trailing_blank.csv"))
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    merkle_cli()
+        .arg("validate")
+        .arg(testdata("address_amount_trailing_blank.csv"))
+        .assert()
+        .success()
+        .stdout("OK, 12 valid entries\n");
+}
+
+#[test]
+fn generate_root_address_only() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_amount.csv"))
+        .arg("--address-only")
+        .assert()
+        .success()
+        .stdout("XKiLxZImGLVijmPL7EW4qubCmpMtJnhWwieD6wwZxAU=\n");
+}
+
+#[test]
+fn generate_root_hash() {
+    for (hash, root) in [
+        ("sha3-256", ADDRESS_AMOUNT_ROOT),
+        ("sha256", "jfnn+l7iqbxY9eK4HGxo7IEpWi6WkAnzPqucvSbkKek="),
+        ("keccak256", "JCHOLu3P0liiCyhMdHuaBNd/FPAqBLYDe3xve8CAJas="),
+        ("blake3", "G/Rt9ACMSJBmmu3qkQ1Seo+TaQkx5khXWncCVUf1uU8="),
+    ] {
+        merkle_cli()
+            .arg("generate-root")
+            .arg(testdata("address_amount.csv"))
+            .arg("--hash")
+            .arg(hash)
+            .assert()
+            .success()
+            .stdout(format!("{}\n", root));
+    }
+}
+
+#[test]
+fn generate_root_evm_compat() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("evm.csv"))
+        .arg("--evm-compat")
+        .assert()
+        .success()
+        .stdout("0x0dbe58dcdef8457ab435b5ac3e49782c2502b6356c884e1f747a9d8865abf73a\n");
+}
+
+#[test]
+fn generate_root_progress() {
+    // the progress is drawn on stderr so the root on stdout is unchanged.
+    for hash in ["sha3-256", "blake3"] {
+        let root = merkle_cli()
+            .arg("generate-root")
+            .arg(testdata("address_amount.csv"))
+            .arg("--hash")
+            .arg(hash)
+            .output()
+            .unwrap()
+            .stdout;
+
+        merkle_cli()
+            .arg("generate-root")
+            .arg(testdata("address_amount.csv"))
+            .arg("--hash")
+            .arg(hash)
+            .arg("--progress")
+            .assert()
+            .success()
+            .stdout(String::from_utf8(root).unwrap());
+    }
+}
+
+#[test]
+fn generate_root_parse_error_exit_code() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("malformed.csv"))
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn generate_root_parse_error_json() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("malformed.csv"))
+        .arg("--json-errors")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicates::str::starts_with("{\"error\":\""))
+        .stderr(predicates::str::ends_with(
+            "\",\"kind\":\"parse\",\"line\":3}\n",
+        ));
+}
+
+#[test]
+fn generate_root_invalid_utf8_no_partial_root() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("invalid_utf8.csv"))
+        .arg("--json-errors")
+        .assert()
+        .failure()
+        .code(2)
+        .stdout("")
+        .stderr(predicates::str::ends_with(
+            "\",\"kind\":\"parse\",\"line\":3}\n",
+        ));
+}
+
+#[test]
+fn generate_root_validation_error_exit_code() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("uosmo_only.csv"))
+        .arg("--require-single-denom")
+        .arg("uion")
+        .assert()
+        .failure()
+        .code(3);
+}
+
+#[test]
+fn generate_root_strict_columns() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("three_columns.csv"))
+        .assert()
+        .success();
+
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("three_columns.csv"))
+        .arg("--strict-columns")
+        .assert()
+        .failure()
+        .code(3)
+        .stderr(predicates::str::contains("row 2 has 3 columns"));
+}
+
+#[test]
+fn generate_root_default_amount() {
+    // the fixture is address_amount.csv with the amount 100 of one row omitted.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_default_amount.csv"))
+        .arg("--default-amount")
+        .arg("100")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_default_amount.csv"))
+        .assert()
+        .failure()
+        .code(2);
+
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("address_default_amount.csv"))
+        .arg("--default-amount")
+        .arg("100 uosmo")
+        .assert()
+        .failure()
+        .code(3)
+        .stderr(predicates::str::contains("default amount"));
+}
+
+#[test]
+fn verify_proof_root_encodings() {
+    for root in [
+        ADDRESS_AMOUNT_ROOT,
+        "cb13cf6e3fe4e865c082ce93a357a6289042de3ab88527920dc8613acf63fede",
+        "0xcb13cf6e3fe4e865c082ce93a357a6289042de3ab88527920dc8613acf63fede",
+    ] {
+        merkle_cli()
+            .arg("verify-proof")
+            .arg(root)
+            .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+            .arg(testdata("proof_data_address_amount.json"))
+            .assert()
+            .success();
+    }
+
+    merkle_cli()
+        .arg("verify-proof")
+        .arg("0xcb13cf")
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+        .arg(testdata("proof_data_address_amount.json"))
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicates::str::contains("invalid root length"));
+}
+
+#[test]
+fn verify_proof_verification_error_exit_code() {
+    merkle_cli()
+        .arg("verify-proof")
+        .arg(ADDRESS_AMOUNT_ROOT)
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1")
+        .arg(testdata("proof_data_address_amount.json"))
+        .assert()
+        .failure()
+        .code(4);
+}
+
+#[test]
+fn verify_proof_root_conflicts_with_contract() {
+    merkle_cli()
+        .arg("verify-proof")
+        .arg(ADDRESS_AMOUNT_ROOT)
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+        .arg(testdata("proof_data_address_amount.json"))
+        .arg("--contract")
+        .arg("osmo1contract")
+        .arg("--rpc")
+        .arg("http://127.0.0.1:1")
+        .assert()
+        .failure()
+        .code(3)
+        .stderr(predicates::str::contains(
+            "the root cannot be given with --contract",
+        ));
+}
+
+#[test]
+fn generate_root_golden() {
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("uosmo_only.csv"))
+        .assert()
+        .success()
+        .stdout(golden("generate_root_uosmo_only.txt"));
+}
+
+#[test]
+fn generate_root_decimals() {
+    // the fixture is uosmo_only.csv with the amounts in OSMO of 6 decimals.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("uosmo_only_decimals.csv"))
+        .arg("--decimals")
+        .arg("6")
+        .arg("--display-denom")
+        .arg("OSMO")
+        .arg("--base-denom")
+        .arg("uosmo")
+        .assert()
+        .success()
+        .stdout(golden("generate_root_uosmo_only.txt"));
+
+    // the amounts have more decimals than given.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("uosmo_only_decimals.csv"))
+        .arg("--decimals")
+        .arg("5")
+        .arg("--display-denom")
+        .arg("OSMO")
+        .arg("--base-denom")
+        .arg("uosmo")
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains("has more than 5 decimals"));
+}
+
+#[test]
+fn generate_proofs_indexed_decimals() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_path = temp_dir.path().join("proofs_index_decimals.json");
+
+    // the indexed proofs are built from the converted amounts.
+    merkle_cli()
+        .arg("generate-proofs-indexed")
+        .arg(testdata("uosmo_only_decimals.csv"))
+        .arg("--out")
+        .arg(&out_path)
+        .arg("--decimals")
+        .arg("6")
+        .arg("--display-denom")
+        .arg("OSMO")
+        .arg("--base-denom")
+        .arg("uosmo")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!(
+            "Root: {}",
+            golden("generate_root_uosmo_only.txt")
+        )));
+}
+
+#[test]
+fn generate_proof_golden() {
+    merkle_cli()
+        .arg("generate-proof")
+        .arg(testdata("uosmo_only.csv"))
+        .arg("osmo1003cay8wpc456n3adq785xn0r0pqvmfxlakpxh|9442uosmo")
+        .arg("--print")
+        .assert()
+        .success()
+        .stdout(golden("generate_proof_uosmo_only.txt"))
+        .stderr("matched entry at row 17\n");
+}
+
+#[test]
+fn generate_proof_embed_root() {
+    merkle_cli()
+        .arg("generate-proof")
+        .arg(testdata("address_amount.csv"))
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+        .arg("--print")
+        .arg("--embed-root")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(format!(
+            "{{\"address\":\"osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj\",\"amount\":\"1421901\",\"root\":\"{}\",\"proof\":[",
+            ADDRESS_AMOUNT_ROOT
+        )));
+}
+
+#[test]
+fn generate_proof_versioned() {
+    merkle_cli()
+        .arg("generate-proof")
+        .arg(testdata("address_amount.csv"))
+        .arg("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj|1421901")
+        .arg("--print")
+        .arg("--versioned")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "{\"version\":2,\"leaf_encoding\":\"decimal_string\",\"proof\":[{\"is_left_sibling\":",
+        ));
+}
+
+#[test]
+fn validate_instantiate() {
+    let owner = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
+
+    merkle_cli()
+        .arg("validate-instantiate")
+        .arg("--root")
+        .arg(ADDRESS_AMOUNT_ROOT)
+        .arg("--subdenom")
+        .arg("mydenom")
+        .arg("--owner")
+        .arg(owner)
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    // every invalid field is reported.
+    merkle_cli()
+        .arg("validate-instantiate")
+        .arg("--root")
+        .arg("not a root")
+        .arg("--subdenom")
+        .arg("my denom")
+        .arg("--owner")
+        .arg("osmo1invalid")
+        .assert()
+        .code(3)
+        .stdout(predicates::str::contains("root \"not a root\" is invalid"))
+        .stdout(predicates::str::contains(
+            "owner \"osmo1invalid\" is invalid",
+        ))
+        .stdout(predicates::str::contains("subdenom \"my denom\""));
+}
+
+#[test]
+fn validate_success() {
+    merkle_cli()
+        .arg("validate")
+        .arg(testdata("address_amount.csv"))
+        .assert()
+        .success()
+        .stdout("OK, 12 valid entries\n");
+}
+
+#[test]
+fn validate_invalid_address_exit_code() {
+    merkle_cli()
+        .arg("validate")
+        .arg(testdata("invalid_address.csv"))
+        .assert()
+        .failure()
+        .code(3);
+}
+
+#[test]
+fn salted_proof_fails_unsalted_root() {
+    let salt = "deadbeef";
+    let data = "osmo1003cay8wpc456n3adq785xn0r0pqvmfxlakpxh|9442uosmo";
+
+    let output = merkle_cli()
+        .arg("generate-root")
+        .arg(testdata("uosmo_only.csv"))
+        .arg("--salt")
+        .arg(salt)
+        .output()
+        .unwrap();
+    let salted_root = String::from_utf8(output.stdout).unwrap();
+    let unsalted_root = golden("generate_root_uosmo_only.txt");
+    assert_ne!(unsalted_root, salted_root);
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let proof_path = temp_dir.path().join("salted_proof.json");
+    merkle_cli()
+        .arg("generate-proof")
+        .arg(testdata("uosmo_only.csv"))
+        .arg(data)
+        .arg(&proof_path)
+        .arg("--salt")
+        .arg(salt)
+        .assert()
+        .success();
+
+    merkle_cli()
+        .arg("verify-proof")
+        .arg(salted_root.trim())
+        .arg(data)
+        .arg(&proof_path)
+        .arg("--salt")
+        .arg(salt)
+        .assert()
+        .success();
+
+    merkle_cli()
+        .arg("verify-proof")
+        .arg(unsalted_root.trim())
+        .arg(data)
+        .arg(&proof_path)
+        .arg("--salt")
+        .arg(salt)
+        .assert()
+        .failure()
+        .code(4);
+}
+
+#[test]
+fn estimate_gas() {
+    // 29 entries are 5 levels deep, so every proof has 5 entries.
+    merkle_cli()
+        .arg("estimate-gas")
+        .arg(testdata("uosmo_only.csv"))
+        .arg("--gas-per-hash")
+        .arg("100")
+        .assert()
+        .success()
+        .stdout(
+            "Entries: 29\n\
+             Max proof length: 5\n\
+             Average proof length: 5.00\n\
+             Max verification gas: 500\n\
+             Average verification gas: 500\n",
+        );
+}
+
+#[test]
+fn self_test() {
+    merkle_cli()
+        .arg("self-test")
+        .assert()
+        .success()
+        .stdout("Vectors: 4\nMismatches: 0\n");
+}
+
+#[test]
+fn stats() {
+    // the median of the 12 amounts is the average of 200 and 1219, rounded down.
+    merkle_cli()
+        .arg("stats")
+        .arg(testdata("address_amount.csv"))
+        .assert()
+        .success()
+        .stdout(
+            "Entries: 12\n\
+             Total amount: 1456255\n\
+             Median amount: 709\n",
+        );
+}
+
+#[test]
+fn stats_empty() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("stats_empty.csv");
+    std::fs::write(&path, "address,amount\n").unwrap();
+
+    merkle_cli()
+        .arg("stats")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(
+            "Entries: 0\n\
+             Total amount: 0\n\
+             Median amount: N/A\n",
+        );
+}
+
+#[test]
+fn exclude_address() {
+    let excluded = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let addresses_path = temp_dir.path().join("exclude_addresses.txt");
+    let out_path = temp_dir.path().join("exclude_out.csv");
+    std::fs::write(&addresses_path, format!("{}\n", excluded)).unwrap();
+
+    let output = merkle_cli()
+        .arg("exclude")
+        .arg(testdata("address_amount.csv"))
+        .arg("--addresses")
+        .arg(&addresses_path)
+        .arg("--out")
+        .arg(&out_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!("Removed: 1", lines[0]);
+    assert_eq!(format!("Root before: {}", ADDRESS_AMOUNT_ROOT), lines[1]);
+    let root_after = lines[2].strip_prefix("Root after: ").unwrap();
+    assert_ne!(ADDRESS_AMOUNT_ROOT, root_after);
+
+    let out = std::fs::read_to_string(&out_path).unwrap();
+    assert!(!out.contains(excluded), "{}", out);
+
+    // the new root is the root of the written entries.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(&out_path)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", root_after));
+}
+
+#[test]
+fn exclude_address_salted() {
+    let excluded = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let addresses_path = temp_dir.path().join("exclude_addresses.txt");
+    let out_path = temp_dir.path().join("exclude_out.csv");
+    std::fs::write(&addresses_path, format!("{}\n", excluded)).unwrap();
+
+    let generate_root = |path: &std::path::Path| {
+        let output = merkle_cli()
+            .arg("generate-root")
+            .arg(path)
+            .arg("--salt")
+            .arg("deadbeef")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    };
+
+    let output = merkle_cli()
+        .arg("exclude")
+        .arg(testdata("address_amount.csv"))
+        .arg("--addresses")
+        .arg(&addresses_path)
+        .arg("--out")
+        .arg(&out_path)
+        .arg("--salt")
+        .arg("deadbeef")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // the roots are the salted roots the contract is given.
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    let root_before = generate_root(&testdata("address_amount.csv"));
+    assert_ne!(ADDRESS_AMOUNT_ROOT, root_before);
+    assert_eq!(format!("Root before: {}", root_before), lines[1]);
+    assert_eq!(
+        format!("Root after: {}", generate_root(&out_path)),
+        lines[2]
+    );
+}
+
+#[test]
+fn canonicalize_idempotent() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_path = temp_dir.path().join("canonical.csv");
+    let out_path2 = temp_dir.path().join("canonical2.csv");
+
+    // the padded fixture is address_amount.csv with a byte order mark
+    // and whitespace around the fields.
+    merkle_cli()
+        .arg("canonicalize")
+        .arg(testdata("address_amount_padded.csv"))
+        .arg("--out")
+        .arg(&out_path)
+        .assert()
+        .success()
+        .stdout(format!(
+            "Entries: 12\nDuplicates removed: 0\nRoot: {}\n",
+            ADDRESS_AMOUNT_ROOT
+        ));
+
+    // the root of the canonical file is the root of the original file.
+    merkle_cli()
+        .arg("generate-root")
+        .arg(&out_path)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", ADDRESS_AMOUNT_ROOT));
+
+    merkle_cli()
+        .arg("canonicalize")
+        .arg(&out_path)
+        .arg("--out")
+        .arg(&out_path2)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&out_path).unwrap(),
+        std::fs::read_to_string(&out_path2).unwrap()
+    );
+
+    // the root is built with the tree flags of generate-root.
+    let output = merkle_cli()
+        .arg("generate-root")
+        .arg(&out_path)
+        .arg("--salt")
+        .arg("deadbeef")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let salted_root = String::from_utf8(output.stdout).unwrap();
+    merkle_cli()
+        .arg("canonicalize")
+        .arg(testdata("address_amount_padded.csv"))
+        .arg("--out")
+        .arg(&out_path2)
+        .arg("--salt")
+        .arg("deadbeef")
+        .assert()
+        .success()
+        .stdout(format!(
+            "Entries: 12\nDuplicates removed: 0\nRoot: {}",
+            salted_root
+        ));
+}
+
+#[test]
+fn generate_proofs_indexed_round_trip() {
+    #[derive(serde::Deserialize)]
+    struct IndexedProof {
+        amount: String,
+        proof: merkle::proof::Proof,
+    }
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_path = temp_dir.path().join("proofs_index.json");
+
+    merkle_cli()
+        .arg("generate-proofs-indexed")
+        .arg(testdata("address_amount.csv"))
+        .arg("--out")
+        .arg(&out_path)
+        .assert()
+        .success()
+        .stdout(format!("Proofs: 12\nRoot: {}\n", ADDRESS_AMOUNT_ROOT));
+
+    let index: std::collections::BTreeMap<String, IndexedProof> =
+        serde_json_wasm::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+    assert_eq!(12, index.len());
+
+    let root = merkle::parse_root(ADDRESS_AMOUNT_ROOT).unwrap();
+    for address in [
+        "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4",
+        "osmo10009zx2uyaw2zkj7ye5zmmxsnu2d9dulh6fd8d",
+        "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj",
+    ] {
+        let indexed = &index[address];
+        let claim = format!("{}|{}", address, indexed.amount);
+        assert!(indexed.proof.verify(&claim, &root), "{}", address);
+    }
+}
+
+#[test]
+fn verify_proofs_dir_reports_tampered_proof() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+
+    for (address, amount) in [
+        ("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4", "10"),
+        ("osmo10009zx2uyaw2zkj7ye5zmmxsnu2d9dulh6fd8d", "100"),
+        ("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj", "1421901"),
+    ] {
+        merkle_cli()
+            .arg("generate-proof")
+            .arg(testdata("address_amount.csv"))
+            .arg(format!("{}|{}", address, amount))
+            .arg(dir.join(format!("{}.json", address)))
+            .arg("--embed-root")
+            .assert()
+            .success();
+    }
+
+    merkle_cli()
+        .arg("verify-proofs-dir")
+        .arg(dir)
+        .arg("--root")
+        .arg(ADDRESS_AMOUNT_ROOT)
+        .assert()
+        .success()
+        .stdout("Proofs: 3\nPassed: 3\nFailed: 0\n");
+
+    // the amount of one proof is raised.
+    let tampered = dir.join("osmo10009zx2uyaw2zkj7ye5zmmxsnu2d9dulh6fd8d.json");
+    let proof = std::fs::read_to_string(&tampered).unwrap();
+    std::fs::write(
+        &tampered,
+        proof.replace("\"amount\":\"100\"", "\"amount\":\"1000\""),
+    )
+    .unwrap();
+
+    merkle_cli()
+        .arg("verify-proofs-dir")
+        .arg(dir)
+        .arg("--root")
+        .arg(ADDRESS_AMOUNT_ROOT)
+        .assert()
+        .code(4)
+        .stdout(predicates::str::contains("Passed: 2\nFailed: 1\n"))
+        .stdout(predicates::str::contains(format!(
+            "{}: does not verify against the root",
+            tampered.display()
+        )));
+}
+
+// CsvEntry is an entry as written in the csv given to the CLI.
+#[derive(Debug)]
+struct CsvEntry {
+    value: u128,
+    leading_zeros: usize,
+    denom: String,
+    vesting: (u64, u64),
+    padding: usize,
+}
+
+fn csv_entry() -> impl Strategy<Value = CsvEntry> {
+    (
+        any::<u128>(),
+        0..3usize,
+        prop_oneof![Just(String::new()), "[a-z][a-z0-9/]{2,16}"],
+        (any::<u64>(), any::<u64>()),
+        0..2usize,
+    )
+        .prop_map(|(value, leading_zeros, denom, vesting, padding)| CsvEntry {
+            value,
+            leading_zeros,
+            denom,
+            vesting,
+            padding,
+        })
+}
+
+proptest! {
+    // every case runs the binary, so that fewer cases than the default are run.
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    // The proofs the CLI generates from the csv of arbitrary entries verify the leaves of
+    // merkle::entry::ClaimEntry of the same entries, the leaves the contract encodes its
+    // claims with, see its claim_leaf. The csv goes through the CLI's reading, column
+    // mapping, field cleaning and amount parsing, so that these cannot diverge from the
+    // contract for inputs other than the fixtures.
+    #[test]
+    fn generate_proofs_indexed_verify_claim_entries(
+        entries in prop::collection::btree_map("osmo1[a-z0-9]{38}", csv_entry(), 1..32),
+        has_vesting in any::<bool>(),
+        swap_columns in any::<bool>(),
+        salt in prop::option::of(prop::collection::vec(any::<u8>(), 1..16)),
+    ) {
+        #[derive(serde::Deserialize)]
+        struct IndexedProof {
+            proof: merkle::proof::Proof,
+        }
+
+        let mut csv = String::from(if swap_columns { "amount,address" } else { "address,amount" });
+        if has_vesting {
+            csv.push_str(",cliff,duration");
+        }
+        csv.push('\n');
+        for (address, entry) in entries.iter() {
+            let padding = " ".repeat(entry.padding);
+            let address = format!("{}{}{}", padding, address, padding);
+            let amount = format!(
+                "{}{}{}{}",
+                "0".repeat(entry.leading_zeros),
+                entry.value,
+                entry.denom,
+                padding
+            );
+            let (first, second) = if swap_columns { (amount, address) } else { (address, amount) };
+            csv.push_str(&format!("{},{}", first, second));
+            if has_vesting {
+                csv.push_str(&format!(",{},{}", entry.vesting.0, entry.vesting.1));
+            }
+            csv.push('\n');
+        }
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let csv_path = temp_dir.path().join("entries.csv");
+        let out_path = temp_dir.path().join("proofs_index.json");
+        std::fs::write(&csv_path, csv).unwrap();
+
+        let mut cmd = merkle_cli();
+        cmd.arg("generate-proofs-indexed")
+            .arg(&csv_path)
+            .arg("--out")
+            .arg(&out_path);
+        if let Some(salt) = &salt {
+            cmd.arg("--salt").arg(hex::encode(salt));
+        }
+        let output = cmd.output().unwrap();
+        prop_assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let root = stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("Root: "))
+            .unwrap();
+        let root = merkle::parse_root(root).unwrap();
+
+        let index: std::collections::BTreeMap<String, IndexedProof> =
+            serde_json_wasm::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+        for (address, entry) in entries.iter() {
+            let claim = merkle::entry::ClaimEntry {
+                address: merkle::entry::Address::new(address).unwrap(),
+                amount: merkle::entry::Amount::new(entry.value, &entry.denom).unwrap(),
+                vesting: Some(entry.vesting).filter(|_| has_vesting),
+            }
+            .to_leaf_bytes();
+            let leaf = match &salt {
+                Some(salt) => merkle::leaf::with_salt(salt, &claim),
+                None => claim,
+            };
+
+            prop_assert!(
+                index[address].proof.verify(&leaf, &root),
+                "{} {:?}",
+                address,
+                entry
+            );
+        }
+    }
+}
@@ -31,6 +31,23 @@ pub fn search<T: Ord>(items: &[T], num_first_items: u128, item: &T) -> Option<u1
     None
 }
 
+/*
+search_first searches for the first occurrence of item in the given first
+number of elements within items. Unlike search, the index is deterministic
+when items contain duplicates.
+*/
+pub fn search_first<T: Ord>(items: &[T], num_first_items: u128, item: &T) -> Option<u128> {
+    let index = search(items, num_first_items, item)?;
+
+    // items are sorted so duplicates are adjacent.
+    let first_index = items[..index as usize]
+        .iter()
+        .rposition(|cur_item| *cur_item != *item)
+        .map_or(0, |i| i + 1);
+
+    Some(first_index as u128)
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -153,4 +170,16 @@ mod tests {
         assert_eq!(true, actual_result.is_some());
         assert_eq!(expected_result, actual_result.unwrap());
     }
+
+    #[test]
+    fn search_first_duplicates() {
+        let items = vec![1, 2, 2, 2, 3, 3, 5];
+        let num_first_items: u128 = 7;
+
+        assert_eq!(Some(0), search_first(&items, num_first_items, &1));
+        assert_eq!(Some(1), search_first(&items, num_first_items, &2));
+        assert_eq!(Some(4), search_first(&items, num_first_items, &3));
+        assert_eq!(Some(6), search_first(&items, num_first_items, &5));
+        assert_eq!(None, search_first(&items, num_first_items, &4));
+    }
 }
@@ -1,11 +1,16 @@
 use crate::hash;
 
 /// TODO: spec and tests
-pub fn build_leaf_level<T: AsRef<[u8]>>(items: &[T]) -> Vec<hash::Hash> {
+/// CONTRACT: hash_length is validated with hash::validate_length.
+pub fn build_leaf_level<T: AsRef<[u8]>>(
+    items: &[T],
+    hash_length: usize,
+    hash_fn: hash::HashFn,
+) -> Vec<hash::Hash> {
     let mut nodes: Vec<hash::Hash> = Vec::new();
     for item in items.iter() {
         let item = item.as_ref();
-        let hash = hash::leaf(item);
+        let hash = hash::leaf_with_hash_fn(item, hash_length, hash_fn);
         nodes.push(hash)
     }
 
@@ -20,6 +25,17 @@ pub fn build_leaf_level<T: AsRef<[u8]>>(items: &[T]) -> Vec<hash::Hash> {
 // mutates the parameter by pushing the new nodes onto it.
 // CONTRACT: nodes are sorted in incrasing order.
 pub fn build_branch_levels(nodes: &mut Vec<hash::Hash>) {
+    build_branch_levels_with_progress(nodes, hash::sha3_256, || {});
+}
+
+// build_branch_levels_with_progress is the same as build_branch_levels
+// but hashes with hash_fn and calls on_level after each branch level is built.
+// CONTRACT: nodes are sorted in incrasing order.
+pub fn build_branch_levels_with_progress<F: FnMut()>(
+    nodes: &mut Vec<hash::Hash>,
+    hash_fn: hash::HashFn,
+    mut on_level: F,
+) {
     let mut previous_level_length = nodes.len() as u128;
     let mut current_level_length = get_next_level_length(previous_level_length);
     let mut previous_level_start = 0;
@@ -35,9 +51,11 @@ pub fn build_branch_levels(nodes: &mut Vec<hash::Hash>) {
                 &nodes[(nodes_index + 1) as usize]
             };
 
-            let hash = hash::branch(left_sibling, right_sibling);
+            let hash = hash::branch_with_hash_fn(left_sibling, right_sibling, hash_fn);
             nodes.push(hash);
         }
+        on_level();
+
         previous_level_start += previous_level_length;
         previous_level_length = current_level_length;
         current_level_length = get_next_level_length(current_level_length);
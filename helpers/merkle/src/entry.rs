@@ -0,0 +1,210 @@
+use std::fmt;
+
+use crate::leaf;
+
+/// MAX_SUBDENOM_LENGTH is the maximum length of a tokenfactory subdenom.
+pub const MAX_SUBDENOM_LENGTH: usize = 44;
+
+/// Address is the address of a claim entry. It is not checked to be valid
+/// bech32, since the CLI also builds trees of other address formats, but
+/// must be a non-empty ascii alphanumeric string so that whitespace or
+/// separators cannot end up in the leaf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address(String);
+
+impl Address {
+    pub fn new(address: &str) -> Result<Self, String> {
+        if address.is_empty() {
+            return Err(String::from("address is empty"));
+        }
+        if !address.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("address {:?} is not ascii alphanumeric", address));
+        }
+        Ok(Address(address.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Amount is the amount of a claim entry, optionally in a denom, the tokenfactory
+/// subdenom of the owner minted for the entry. An empty denom is the drop's denom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Amount {
+    value: u128,
+    denom: String,
+}
+
+impl Amount {
+    /// new returns the amount of the value in the denom, which must be
+    /// empty or a valid subdenom, see is_valid_subdenom.
+    pub fn new(value: u128, denom: &str) -> Result<Self, String> {
+        if !denom.is_empty() && !is_valid_subdenom(denom) {
+            return Err(format!("invalid denom {:?}", denom));
+        }
+        Ok(Amount {
+            value,
+            denom: denom.to_string(),
+        })
+    }
+
+    /// parse parses an integer or a coin in cosmos-sdk Coin string format,
+    /// e.g. "100uosmo". Leading zeros of the integer are accepted.
+    pub fn parse(amount: &str) -> Result<Self, String> {
+        let denom = amount.trim_start_matches(|c: char| c.is_ascii_digit());
+        let number = &amount[..amount.len() - denom.len()];
+
+        let value = number
+            .parse::<u128>()
+            .map_err(|_| format!("invalid amount {:?}", amount))?;
+        Amount::new(value, denom)
+    }
+
+    pub fn value(&self) -> u128 {
+        self.value
+    }
+
+    pub fn denom(&self) -> &str {
+        &self.denom
+    }
+}
+
+// Display formats the amount as a coin string, e.g. "100uosmo",
+// as the contract's Coin does.
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.denom)
+    }
+}
+
+/// ClaimEntry is an entry of a drop. The CLI builds the tree of the leaves of
+/// the entries and the contract verifies the leaf of the claimed entry, so both
+/// must encode entries through ClaimEntry for proofs to verify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimEntry {
+    pub address: Address,
+    pub amount: Amount,
+    /// vesting is the (cliff, duration) of the entry in seconds, if it vests.
+    pub vesting: Option<(u64, u64)>,
+}
+
+impl ClaimEntry {
+    /// to_leaf_bytes returns the leaf data of the entry, see leaf::encode.
+    pub fn to_leaf_bytes(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+}
+
+// Display formats the entry as its leaf data.
+impl fmt::Display for ClaimEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            leaf::encode(
+                self.address.as_str(),
+                &self.amount.to_string(),
+                self.vesting
+            )
+        )
+    }
+}
+
+/// is_valid_subdenom returns whether the denom is a tokenfactory subdenom: at most
+/// MAX_SUBDENOM_LENGTH characters of the cosmos-sdk denom charset, so that the full
+/// "factory/{owner}/{subdenom}" denom is valid. It must start with a letter so that
+/// the coin string of an amount, e.g. "100u", parses back into the same amount.
+pub fn is_valid_subdenom(denom: &str) -> bool {
+    let starts_with_letter = denom.starts_with(|c: char| c.is_ascii_alphabetic());
+    let has_valid_chars = denom
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+    starts_with_letter && has_valid_chars && denom.len() <= MAX_SUBDENOM_LENGTH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+
+    #[test]
+    fn address_new() {
+        assert_eq!(ADDRESS, Address::new(ADDRESS).unwrap().as_str());
+        assert_eq!(
+            "0x1111111111111111111111111111111111111111",
+            Address::new("0x1111111111111111111111111111111111111111")
+                .unwrap()
+                .as_str()
+        );
+
+        for address in ["", " osmo1", "osmo1 ", "osmo1:10", "osmo1,10", "osmo1|10"] {
+            assert!(Address::new(address).is_err(), "{:?}", address);
+        }
+    }
+
+    #[test]
+    fn amount_new() {
+        assert_eq!("100", Amount::new(100, "").unwrap().to_string());
+        assert_eq!("100uosmo", Amount::new(100, "uosmo").unwrap().to_string());
+        assert_eq!(
+            "1gamm/pool/1",
+            Amount::new(1, "gamm/pool/1").unwrap().to_string()
+        );
+
+        // short subdenoms are valid.
+        assert_eq!("100u", Amount::new(100, "u").unwrap().to_string());
+        assert_eq!("100uo", Amount::new(100, "uo").unwrap().to_string());
+
+        for denom in ["1uosmo", "u osmo", "uosmo!"] {
+            assert!(Amount::new(100, denom).is_err(), "{:?}", denom);
+        }
+        assert!(Amount::new(100, &"u".repeat(MAX_SUBDENOM_LENGTH)).is_ok());
+        assert!(Amount::new(100, &"u".repeat(MAX_SUBDENOM_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn amount_parse() {
+        let amount = Amount::parse("0100uosmo").unwrap();
+        assert_eq!(100, amount.value());
+        assert_eq!("uosmo", amount.denom());
+        assert_eq!(Amount::new(100, "").unwrap(), Amount::parse("100").unwrap());
+        assert_eq!(
+            u128::MAX,
+            Amount::parse(&u128::MAX.to_string()).unwrap().value()
+        );
+
+        for amount in ["", "uosmo", "-1", "1.5uosmo", "100 uosmo"] {
+            assert!(Amount::parse(amount).is_err(), "{:?}", amount);
+        }
+        // u128::MAX + 1
+        assert!(Amount::parse("340282366920938463463374607431768211456").is_err());
+    }
+
+    #[test]
+    fn claim_entry_leaf_bytes() {
+        let entry = ClaimEntry {
+            address: Address::new(ADDRESS).unwrap(),
+            amount: Amount::parse("0100uosmo").unwrap(),
+            vesting: None,
+        };
+        assert_eq!(
+            format!("{}|100uosmo", ADDRESS).into_bytes(),
+            entry.to_leaf_bytes()
+        );
+        assert_eq!(
+            leaf::encode(ADDRESS, "0100uosmo", None).into_bytes(),
+            entry.to_leaf_bytes()
+        );
+
+        let entry = ClaimEntry {
+            vesting: Some((10, 3600)),
+            ..entry
+        };
+        assert_eq!(
+            format!("{}|100uosmo:10:3600", ADDRESS).into_bytes(),
+            entry.to_leaf_bytes()
+        );
+    }
+}
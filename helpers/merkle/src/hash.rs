@@ -1,5 +1,6 @@
 use std::fmt;
 
+#[cfg(feature = "serde")]
 use serde::{de, de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 use sha3::{Digest, Sha3_256};
 
@@ -10,13 +11,56 @@ use sha3::{Digest, Sha3_256};
 const LEAF_NODE_PREFIX: &[u8] = &[0];
 const BRANCH_NODE_PREFIX: &[u8] = &[1];
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Default)]
-pub struct Hash(pub(crate) [u8; 32]);
+// HASH_LENGTH is the length of a full, non-truncated hash in bytes.
+pub const HASH_LENGTH: usize = 32;
+
+// MIN_TRUNCATED_HASH_LENGTH is the minimum length in bytes that hashes
+// can be truncated to. Truncation shrinks proofs at the cost of collision
+// resistance: finding a collision for an n byte hash takes roughly 2^(4n)
+// work due to the birthday bound. 20 bytes keep it at 2^80, the same
+// security level as Ethereum addresses. Only use truncated hashes for
+// bandwidth-sensitive drops where this trade-off is acceptable.
+pub const MIN_TRUNCATED_HASH_LENGTH: usize = 20;
+
+// Hash is the output of the hasher, possibly truncated.
+// The unused trailing bytes of a truncated hash are always zero.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Hash {
+    bytes: [u8; HASH_LENGTH],
+    length: usize,
+}
+
+impl Hash {
+    /// get_length returns the length of the hash in bytes.
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    // truncate returns the hash truncated to the given length.
+    // CONTRACT: length is at most HASH_LENGTH.
+    fn truncate(mut self, length: usize) -> Hash {
+        for byte in self.bytes[length..].iter_mut() {
+            *byte = 0;
+        }
+        self.length = length;
+        self
+    }
+}
+
+impl Default for Hash {
+    fn default() -> Self {
+        Hash {
+            bytes: [0; HASH_LENGTH],
+            length: HASH_LENGTH,
+        }
+    }
+}
 
 // Serialize for Entry is the custom serialization implementation.
 // Since plain-text SHA3 hash might not be exclusive to the ASCII set,
 // we need to first base64 encoded it before serializing. This is what
 // this implementation achieves.
+#[cfg(feature = "serde")]
 impl Serialize for Hash {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -29,8 +73,10 @@ impl Serialize for Hash {
     }
 }
 
+#[cfg(feature = "serde")]
 struct HashVisitor;
 
+#[cfg(feature = "serde")]
 impl<'de> Visitor<'de> for HashVisitor {
     type Value = Hash;
 
@@ -47,10 +93,15 @@ impl<'de> Visitor<'de> for HashVisitor {
             let value_b: u8 = value;
             new_obj.push(value_b);
         }
-        Ok(Hash::from(base64::decode(&new_obj).unwrap()))
+        // the bytes are user supplied, e.g. the proof of a claim, so that
+        // malformed bytes must be an error rather than a panic.
+        let decoded = base64::decode(&new_obj).map_err(de::Error::custom)?;
+        validate_length(decoded.len()).map_err(de::Error::custom)?;
+        Ok(Hash::from(decoded))
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Hash {
     fn deserialize<D>(deserializer: D) -> Result<Hash, D::Error>
     where
@@ -60,59 +111,112 @@ impl<'de> Deserialize<'de> for Hash {
     }
 }
 
+// JsonSchema describes Hash as the sequence of bytes it is serialized to.
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Hash {
+    fn schema_name() -> String {
+        String::from("Hash")
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        <Vec<u8>>::json_schema(gen)
+    }
+}
+
+// Display is the canonical string representation of a Hash.
+// It is base64 encoded to match the root stored by the contract
+// and printed by the CLI.
 impl fmt::Display for Hash {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.0)
+        write!(f, "{}", base64::encode(self))
     }
 }
 
 impl AsRef<[u8]> for Hash {
     fn as_ref(&self) -> &[u8] {
-        &self.0[..]
+        &self.bytes[..self.length]
     }
 }
 
+// From panics if the given bytes are empty or longer than HASH_LENGTH.
 impl From<Vec<u8>> for Hash {
     fn from(item: Vec<u8>) -> Self {
-        Hash(<[u8; 32]>::try_from(item.as_slice()).unwrap())
+        assert!(
+            !item.is_empty() && item.len() <= HASH_LENGTH,
+            "invalid hash length ({})",
+            item.len()
+        );
+        let mut bytes = [0; HASH_LENGTH];
+        bytes[..item.len()].copy_from_slice(&item);
+        Hash {
+            bytes,
+            length: item.len(),
+        }
     }
 }
 
-#[derive(Clone, Default)]
-struct Hasher {
-    hasher: Sha3_256,
-}
+/// HashFn is the hash function of a tree. The contract verifies sha3_256 trees only,
+/// other hash functions build trees for other on-chain verifiers.
+pub type HashFn = fn(&[u8]) -> [u8; HASH_LENGTH];
 
-impl Hasher {
-    fn update(&mut self, val: &[u8]) {
-        self.hasher.update(val);
-    }
+/// sha3_256 is the default hash function of the trees.
+pub fn sha3_256(data: &[u8]) -> [u8; HASH_LENGTH] {
+    // At the time of this writing, the sha2 library is stuck on an old version
+    // of generic_array (0.9.0). Decouple ourselves with a clone to our version.
+    <[u8; HASH_LENGTH]>::try_from(Sha3_256::digest(data).as_slice()).unwrap()
+}
 
-    fn result(self) -> Hash {
-        // At the time of this writing, the sha2 library is stuck on an old version
-        // of generic_array (0.9.0). Decouple ourselves with a clone to our version.
-        Hash(<[u8; 32]>::try_from(self.hasher.finalize().as_slice()).unwrap())
+/// validate_length returns an error if hashes cannot be truncated to the given length.
+pub fn validate_length(length: usize) -> Result<(), String> {
+    if !(MIN_TRUNCATED_HASH_LENGTH..=HASH_LENGTH).contains(&length) {
+        return Err(format!(
+            "hash length ({}) must be between {} and {}",
+            length, MIN_TRUNCATED_HASH_LENGTH, HASH_LENGTH
+        ));
     }
+    Ok(())
 }
 
 pub fn leaf(data: &[u8]) -> Hash {
-    hash(&[LEAF_NODE_PREFIX, data])
+    leaf_with_length(data, HASH_LENGTH)
+}
+
+/// leaf_with_length hashes the leaf data and truncates the result to the given length.
+/// CONTRACT: length is validated with validate_length.
+pub fn leaf_with_length(data: &[u8], length: usize) -> Hash {
+    leaf_with_hash_fn(data, length, sha3_256)
 }
 
+/// leaf_with_hash_fn is the same as leaf_with_length but hashes with the given hash function.
+/// CONTRACT: length is validated with validate_length.
+pub fn leaf_with_hash_fn(data: &[u8], length: usize, hash_fn: HashFn) -> Hash {
+    hash(hash_fn, &[LEAF_NODE_PREFIX, data]).truncate(length)
+}
+
+// branch truncates the result to the length of its children
+// so that the truncation of the leaves propagates up to the root.
 pub fn branch(left_child: &Hash, right_child: &Hash) -> Hash {
-    hash(&[
-        BRANCH_NODE_PREFIX,
-        left_child.as_ref(),
-        right_child.as_ref(),
-    ])
+    branch_with_hash_fn(left_child, right_child, sha3_256)
 }
 
-fn hash(values: &[&[u8]]) -> Hash {
-    let mut hasher = Hasher::default();
-    for value in values {
-        hasher.update(value);
+/// branch_with_hash_fn is the same as branch but hashes with the given hash function.
+pub fn branch_with_hash_fn(left_child: &Hash, right_child: &Hash, hash_fn: HashFn) -> Hash {
+    hash(
+        hash_fn,
+        &[
+            BRANCH_NODE_PREFIX,
+            left_child.as_ref(),
+            right_child.as_ref(),
+        ],
+    )
+    .truncate(left_child.get_length())
+}
+
+fn hash(hash_fn: HashFn, values: &[&[u8]]) -> Hash {
+    Hash {
+        bytes: hash_fn(&values.concat()),
+        length: HASH_LENGTH,
     }
-    hasher.result()
 }
 
 #[cfg(test)]
@@ -121,6 +225,7 @@ mod tests {
     use crate::test_util;
 
     #[test]
+    #[cfg(feature = "serde")]
     fn custom_serialization_works() {
         let test_entry = leaf(test_util::OSMO);
 
@@ -129,4 +234,66 @@ mod tests {
 
         assert_eq!(test_entry, deserialized);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialize_malformed_hash_is_err() {
+        // not base64.
+        assert!(serde_json_wasm::from_str::<Hash>("[33,33,33,33]").is_err());
+
+        // base64 of fewer bytes than a truncated hash, and of more than a full hash.
+        for length in [0, MIN_TRUNCATED_HASH_LENGTH - 1, HASH_LENGTH + 1] {
+            let serialized =
+                serde_json_wasm::to_string(base64::encode(vec![1u8; length]).as_bytes()).unwrap();
+            assert!(
+                serde_json_wasm::from_str::<Hash>(&serialized).is_err(),
+                "{}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn display_is_base64() {
+        let test_entry = leaf(test_util::OSMO);
+
+        assert_eq!(base64::encode(test_entry), test_entry.to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn custom_serialization_truncated_works() {
+        let test_entry = leaf_with_length(test_util::OSMO, MIN_TRUNCATED_HASH_LENGTH);
+
+        let serialized = serde_json_wasm::to_string(&test_entry).unwrap();
+        let deserialized: Hash = serde_json_wasm::from_str(&serialized).unwrap();
+
+        assert_eq!(test_entry, deserialized);
+        assert_eq!(MIN_TRUNCATED_HASH_LENGTH, deserialized.get_length());
+    }
+
+    #[test]
+    fn leaf_truncated_is_prefix_of_full() {
+        let full = leaf(test_util::OSMO);
+        let truncated = leaf_with_length(test_util::OSMO, MIN_TRUNCATED_HASH_LENGTH);
+
+        assert_eq!(HASH_LENGTH, full.get_length());
+        assert_eq!(MIN_TRUNCATED_HASH_LENGTH, truncated.get_length());
+        assert_eq!(
+            &full.as_ref()[..MIN_TRUNCATED_HASH_LENGTH],
+            truncated.as_ref()
+        );
+        assert_ne!(full, truncated);
+    }
+
+    #[test]
+    fn validate_length_bounds() {
+        assert_eq!(true, validate_length(HASH_LENGTH).is_ok());
+        assert_eq!(true, validate_length(MIN_TRUNCATED_HASH_LENGTH).is_ok());
+        assert_eq!(
+            true,
+            validate_length(MIN_TRUNCATED_HASH_LENGTH - 1).is_err()
+        );
+        assert_eq!(true, validate_length(HASH_LENGTH + 1).is_err());
+    }
 }
@@ -0,0 +1,355 @@
+use crate::hash;
+use crate::proof::Proof;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// AMOUNT_SEPARATOR separates the address from the amount in the leaf data.
+// Without it, trailing digits of an address could be shifted into the amount,
+// e.g. "osmo1...x5" with "10" and "osmo1...x" with "510" would share a leaf,
+// as would their claim keys.
+pub const AMOUNT_SEPARATOR: char = '|';
+
+// VESTING_SEPARATOR precedes each vesting parameter in the leaf data.
+// Without it, digits of the cliff could be shifted into the amount
+// allowing a claimer to forge a larger amount.
+pub const VESTING_SEPARATOR: char = ':';
+
+// U128_BYTES_LENGTH is the length of an amount encoded as u128 bytes.
+const U128_BYTES_LENGTH: usize = 16;
+
+/// LeafEncoding is the encoding of the amount in the leaf data. The tree of a drop
+/// and the contract verifying its proofs must use the same encoding.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum LeafEncoding {
+    /// DecimalString encodes the amount as its decimal string, see encode.
+    #[default]
+    DecimalString,
+    /// U128Bytes encodes the amount as its 16 big-endian bytes, as done by
+    /// verifiers that hash amounts as integers rather than strings.
+    U128Bytes,
+}
+
+/// encode returns the leaf data of a claim entry.
+/// The CLI and the contract must encode entries identically for proofs to verify.
+/// The data is the address followed by the normalized amount, separated by
+/// AMOUNT_SEPARATOR. If the entry vests,
+/// vesting is given as (cliff, duration) and both are appended to the data.
+pub fn encode(address: &str, amount: &str, vesting: Option<(u64, u64)>) -> String {
+    let amount = normalize_amount(amount);
+    match vesting {
+        Some((cliff, duration)) => format!(
+            "{}{}{}{}{}{}{}",
+            address,
+            AMOUNT_SEPARATOR,
+            amount,
+            VESTING_SEPARATOR,
+            cliff,
+            VESTING_SEPARATOR,
+            duration
+        ),
+        None => format!("{}{}{}", address, AMOUNT_SEPARATOR, amount),
+    }
+}
+
+/// encode_address_only returns the leaf data of an entry of an address-only drop.
+/// The data is the address only. The amount of the entry is kept off the tree,
+/// so it can change without changing the root, but is not proven by it.
+pub fn encode_address_only(address: &str) -> String {
+    address.to_string()
+}
+
+/// with_encoding returns the leaf data produced by encode with its amount in the
+/// given encoding. With U128Bytes, the decimal amount following AMOUNT_SEPARATOR
+/// is replaced by its 16 big-endian bytes, so that it has no formatting to agree on.
+/// The denom and the vesting, if any, follow the fixed-width amount as is.
+/// Leaves without an amount, e.g. of address-only drops, are returned unchanged.
+pub fn with_encoding(encoding: LeafEncoding, data: &[u8]) -> Result<Vec<u8>, String> {
+    let separator = AMOUNT_SEPARATOR as u8;
+    let position = match data.iter().position(|b| *b == separator) {
+        Some(position) if encoding == LeafEncoding::U128Bytes => position,
+        _ => return Ok(data.to_vec()),
+    };
+
+    let (address, rest) = (&data[..position], &data[position + 1..]);
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    let amount = std::str::from_utf8(&rest[..digits])
+        .ok()
+        .and_then(|amount| amount.parse::<u128>().ok())
+        .ok_or_else(|| format!("invalid amount in leaf {:?}", String::from_utf8_lossy(data)))?;
+
+    let mut encoded = Vec::with_capacity(data.len() + U128_BYTES_LENGTH);
+    encoded.extend_from_slice(address);
+    encoded.push(separator);
+    encoded.extend_from_slice(&amount.to_be_bytes());
+    encoded.extend_from_slice(&rest[digits..]);
+    Ok(encoded)
+}
+
+/// with_salt prefixes the leaf data with the length of the salt, as 4 big-endian
+/// bytes, followed by the salt. Salting the leaves of a drop makes its root and proofs
+/// differ from those of a drop with the same entries, so that proofs cannot be replayed
+/// across drops. The length keeps the salt apart from the data, so that the leaf of a
+/// salt cannot be that of another salt with other data. An empty salt leaves the data as is.
+pub fn with_salt(salt: &[u8], data: &[u8]) -> Vec<u8> {
+    if salt.is_empty() {
+        return data.to_vec();
+    }
+    [&(salt.len() as u32).to_be_bytes()[..], salt, data].concat()
+}
+
+/// normalize_amount strips the leading zeros of the amount, e.g. "0100uosmo"
+/// becomes "100uosmo", so that it matches the amount as stringified by the
+/// contract's Uint128. The denom, if any, is kept as is. Amounts that do not
+/// start with a valid u128 are returned unchanged.
+pub fn normalize_amount(amount: &str) -> String {
+    let denom = amount.trim_start_matches(|c: char| c.is_ascii_digit());
+    let number = &amount[..amount.len() - denom.len()];
+
+    match number.parse::<u128>() {
+        Ok(number) => format!("{}{}", number, denom),
+        Err(_) => amount.to_string(),
+    }
+}
+
+/// verify_claim returns true if the proof in the compact hex encoding
+/// proves that the claim of amount by address is in the tree with the
+/// hex encoded root. The proof hashes are expected to have the length of the root.
+pub fn verify_claim(
+    root_hex: &str,
+    proof_hex: &str,
+    address: &str,
+    amount: &str,
+) -> Result<bool, String> {
+    let root_bytes = match hex::decode(root_hex) {
+        Ok(bytes) => bytes,
+        Err(e) => return Err(format!("failed to decode root hex: {}", e)),
+    };
+    hash::validate_length(root_bytes.len())?;
+    let root = hash::Hash::from(root_bytes);
+
+    let proof = Proof::from_hex_string_with_length(proof_hex, root.get_length())?;
+
+    Ok(proof.verify(&encode(address, amount, None), &root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tree;
+
+    const ADDRESS: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+    const OTHER_ADDRESS: &str = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
+
+    #[test]
+    fn encode_without_vesting() {
+        assert_eq!(format!("{}|100", ADDRESS), encode(ADDRESS, "100", None));
+    }
+
+    #[test]
+    fn encode_address_digits_not_shifted_into_amount() {
+        // without the separator, both entries would be "osmo1a510".
+        let shorter = encode("osmo1a", "510", None);
+        let longer = encode("osmo1a5", "10", None);
+        assert_ne!(shorter, longer);
+
+        let mt = Tree::new(&[shorter.clone(), encode(OTHER_ADDRESS, "200", None)]);
+        let proof = mt.find_proof(&shorter).unwrap();
+        assert_eq!(false, proof.verify(&longer, &mt.get_root().unwrap()));
+    }
+
+    #[test]
+    fn encode_address_only_is_address() {
+        assert_eq!(ADDRESS, encode_address_only(ADDRESS));
+    }
+
+    #[test]
+    fn encode_with_vesting() {
+        assert_eq!(
+            format!("{}|100:10:3600", ADDRESS),
+            encode(ADDRESS, "100", Some((10, 3600)))
+        );
+    }
+
+    #[test]
+    fn tampered_vesting_fails_verification() {
+        let leaf = encode(ADDRESS, "1000", Some((5, 100)));
+        let items = vec![leaf.clone(), encode(OTHER_ADDRESS, "200", None)];
+
+        let mt = Tree::new(&items);
+        let root = mt.get_root().unwrap();
+        let proof = mt.find_proof(&leaf).unwrap();
+
+        assert_eq!(true, proof.verify(&leaf, &root));
+
+        // tampered cliff
+        let tampered = encode(ADDRESS, "1000", Some((0, 100)));
+        assert_eq!(false, proof.verify(&tampered, &root));
+
+        // tampered duration
+        let tampered = encode(ADDRESS, "1000", Some((5, 0)));
+        assert_eq!(false, proof.verify(&tampered, &root));
+
+        // vesting dropped
+        let tampered = encode(ADDRESS, "1000", None);
+        assert_eq!(false, proof.verify(&tampered, &root));
+
+        // cliff digits shifted into the amount
+        let tampered = encode(ADDRESS, "10005", Some((100, 0)));
+        assert_eq!(false, proof.verify(&tampered, &root));
+    }
+
+    #[test]
+    fn with_encoding_u128_bytes() {
+        let leaf = encode(ADDRESS, "0100uosmo", Some((10, 3600)));
+        let encoded = with_encoding(LeafEncoding::U128Bytes, leaf.as_bytes()).unwrap();
+
+        let mut expected = format!("{}|", ADDRESS).into_bytes();
+        expected.extend_from_slice(&100u128.to_be_bytes());
+        expected.extend_from_slice(b"uosmo:10:3600");
+        assert_eq!(expected, encoded);
+
+        // the decimal encoding is the leaf as is, as are leaves without an amount.
+        assert_eq!(
+            leaf.as_bytes(),
+            with_encoding(LeafEncoding::DecimalString, leaf.as_bytes()).unwrap()
+        );
+        let address_only = encode_address_only(ADDRESS);
+        assert_eq!(
+            address_only.as_bytes(),
+            with_encoding(LeafEncoding::U128Bytes, address_only.as_bytes()).unwrap()
+        );
+
+        assert!(with_encoding(LeafEncoding::U128Bytes, b"osmo1a|uosmo").is_err());
+        assert!(with_encoding(
+            LeafEncoding::U128Bytes,
+            format!("osmo1a|{}0", u128::MAX).as_bytes()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn u128_bytes_and_decimal_roots_differ() {
+        let leaves = vec![
+            encode(ADDRESS, "1000uosmo", None),
+            encode(OTHER_ADDRESS, "200uosmo", None),
+        ];
+        let u128_leaves: Vec<Vec<u8>> = leaves
+            .iter()
+            .map(|leaf| with_encoding(LeafEncoding::U128Bytes, leaf.as_bytes()).unwrap())
+            .collect();
+
+        let decimal_tree = Tree::new(&leaves);
+        let u128_tree = Tree::new(&u128_leaves);
+        let decimal_root = decimal_tree.get_root().unwrap();
+        let u128_root = u128_tree.get_root().unwrap();
+        assert_ne!(decimal_root, u128_root);
+
+        // each tree proves its own encoding of the leaf only.
+        let decimal_proof = decimal_tree.find_proof(&leaves[0]).unwrap();
+        let u128_proof = u128_tree.find_proof(&u128_leaves[0]).unwrap();
+        assert_eq!(true, decimal_proof.verify(&leaves[0], &decimal_root));
+        assert_eq!(true, u128_proof.verify(&u128_leaves[0], &u128_root));
+        assert_eq!(false, u128_proof.verify(&leaves[0], &u128_root));
+        assert_eq!(false, decimal_proof.verify(&u128_leaves[0], &decimal_root));
+
+        // leading zeros are gone in either encoding.
+        let zeros = encode(ADDRESS, "001000uosmo", None);
+        assert_eq!(
+            u128_leaves[0],
+            with_encoding(LeafEncoding::U128Bytes, zeros.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn normalize_amount_strips_leading_zeros() {
+        assert_eq!("100uosmo", normalize_amount("0100uosmo"));
+        assert_eq!("100", normalize_amount("000100"));
+        assert_eq!("0", normalize_amount("000"));
+        assert_eq!("100uosmo", normalize_amount("100uosmo"));
+
+        // not a number, left unchanged.
+        assert_eq!("uosmo", normalize_amount("uosmo"));
+        assert_eq!("", normalize_amount(""));
+    }
+
+    #[test]
+    fn with_salt_length_prefix() {
+        let leaf = encode(ADDRESS, "1000", None);
+
+        let mut expected = vec![0, 0, 0, 4, 0xde, 0xad, 0xbe, 0xef];
+        expected.extend_from_slice(leaf.as_bytes());
+        assert_eq!(
+            expected,
+            with_salt(&[0xde, 0xad, 0xbe, 0xef], leaf.as_bytes())
+        );
+
+        // the salt cannot be shifted into the data.
+        assert_ne!(with_salt(b"ab", b"c"), with_salt(b"a", b"bc"));
+
+        // an empty salt does not change the leaf.
+        assert_eq!(leaf.as_bytes(), with_salt(&[], leaf.as_bytes()).as_slice());
+    }
+
+    #[test]
+    fn leading_zeros_verify() {
+        let leaf = encode(ADDRESS, "0100uosmo", None);
+        let items = vec![leaf.clone(), encode(OTHER_ADDRESS, "200uosmo", None)];
+
+        let mt = Tree::new(&items);
+        let root = mt.get_root().unwrap();
+        let proof = mt.find_proof(&leaf).unwrap();
+
+        assert_eq!(
+            true,
+            proof.verify(&encode(ADDRESS, "100uosmo", None), &root)
+        );
+        assert_eq!(
+            true,
+            proof.verify(&encode(ADDRESS, "00100uosmo", None), &root)
+        );
+    }
+
+    #[test]
+    fn verify_claim_hex() {
+        let leaf = encode(ADDRESS, "1000uosmo", None);
+        let items = vec![leaf.clone(), encode(OTHER_ADDRESS, "200uosmo", None)];
+
+        let mt = Tree::new(&items);
+        let root_hex = hex::encode(mt.get_root().unwrap());
+        let proof_hex = mt.find_proof(&leaf).unwrap().to_hex_string();
+
+        assert_eq!(
+            Ok(true),
+            verify_claim(&root_hex, &proof_hex, ADDRESS, "1000uosmo")
+        );
+        assert_eq!(
+            Ok(false),
+            verify_claim(&root_hex, &proof_hex, ADDRESS, "1001uosmo")
+        );
+        assert_eq!(
+            Ok(false),
+            verify_claim(&root_hex, &proof_hex, OTHER_ADDRESS, "1000uosmo")
+        );
+
+        assert!(verify_claim("zz", &proof_hex, ADDRESS, "1000uosmo").is_err());
+        assert!(verify_claim(&root_hex, "zz", ADDRESS, "1000uosmo").is_err());
+    }
+
+    #[test]
+    fn verify_claim_truncated_hex() {
+        let leaf = encode(ADDRESS, "1000uosmo", None);
+        let items = vec![leaf.clone(), encode(OTHER_ADDRESS, "200uosmo", None)];
+
+        let mt = Tree::new_with_hash_length(&items, hash::MIN_TRUNCATED_HASH_LENGTH).unwrap();
+        let root_hex = hex::encode(mt.get_root().unwrap());
+        let proof_hex = mt.find_proof(&leaf).unwrap().to_hex_string();
+
+        assert_eq!(
+            Ok(true),
+            verify_claim(&root_hex, &proof_hex, ADDRESS, "1000uosmo")
+        );
+    }
+}
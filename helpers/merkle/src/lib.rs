@@ -1,34 +1,167 @@
 mod binary_search;
 mod builder;
+pub mod entry;
 pub mod hash;
+pub mod leaf;
 pub mod proof;
+mod root;
+pub mod test_vectors;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use root::{parse_root, RootError};
 
 #[derive(Debug)]
 pub struct Tree {
     leaf_count: u128,
     nodes: Vec<hash::Hash>,
+    hash_length: usize,
+    hash_fn: hash::HashFn,
+}
+
+// PartialEq compares the nodes and the hash functions of the trees, so that trees
+// whose proofs verify differently are never equal. The hash functions are compared
+// by address: the same function may have several addresses, e.g. across codegen
+// units, which makes equal trees compare unequal but never the other way around.
+impl PartialEq for Tree {
+    fn eq(&self, other: &Self) -> bool {
+        self.leaf_count == other.leaf_count
+            && self.nodes == other.nodes
+            && self.hash_length == other.hash_length
+            && self.hash_fn as usize == other.hash_fn as usize
+    }
 }
 
 impl Tree {
     pub fn new<T: AsRef<[u8]>>(items: &[T]) -> Self {
+        Tree::build(items, hash::HASH_LENGTH, hash::sha3_256, || {})
+    }
+
+    /// new_with_progress is the same as new but calls on_level after each
+    /// branch level of the tree is built, e.g. to report the progress of
+    /// building the tree of a huge data set. See level_count for the number
+    /// of calls.
+    pub fn new_with_progress<T: AsRef<[u8]>, F: FnMut()>(items: &[T], on_level: F) -> Self {
+        Tree::build(items, hash::HASH_LENGTH, hash::sha3_256, on_level)
+    }
+
+    /// new_with_hash_fn is the same as new_with_progress but hashes the leaves and
+    /// branches with hash_fn instead of sha3-256, e.g. for on-chain verifiers of
+    /// other hash functions. The contract verifies sha3-256 trees only.
+    pub fn new_with_hash_fn<T: AsRef<[u8]>, F: FnMut()>(
+        items: &[T],
+        hash_fn: hash::HashFn,
+        on_level: F,
+    ) -> Self {
+        Tree::build(items, hash::HASH_LENGTH, hash_fn, on_level)
+    }
+
+    /// level_count returns the number of branch levels of the tree of
+    /// leaf_count leaves.
+    pub fn level_count(leaf_count: usize) -> usize {
+        let mut level_length = leaf_count as u128;
+        let mut level_count = 0;
+        while level_length > 1 {
+            level_length = builder::get_next_level_length(level_length);
+            level_count += 1;
+        }
+        level_count
+    }
+
+    /// new_with_hash_length builds a tree with all hashes truncated to
+    /// the given length in bytes. This shrinks proofs but reduces
+    /// collision resistance. See hash::MIN_TRUNCATED_HASH_LENGTH for
+    /// the security implications.
+    pub fn new_with_hash_length<T: AsRef<[u8]>>(
+        items: &[T],
+        hash_length: usize,
+    ) -> Result<Self, String> {
+        hash::validate_length(hash_length)?;
+        Ok(Tree::build(items, hash_length, hash::sha3_256, || {}))
+    }
+
+    /// from_leaf_hashes builds a tree from leaf hashes computed elsewhere,
+    /// e.g. with hash::leaf, when the leaf preimages are not available.
+    /// The hashes are sorted but not hashed again, so the tree equals the
+    /// tree built with new from the preimages.
+    /// CONTRACT: all hashes have the same length.
+    pub fn from_leaf_hashes(mut hashes: Vec<hash::Hash>) -> Self {
+        let hash_length = hashes
+            .first()
+            .map_or(hash::HASH_LENGTH, |hash| hash.get_length());
+        if hashes.is_empty() {
+            return Tree {
+                leaf_count: 0,
+                nodes: hashes,
+                hash_length,
+                hash_fn: hash::sha3_256,
+            };
+        }
+
+        // sort the leaves so that we can binary search them
+        // when finding proofs.
+        pdqsort::sort_by(&mut hashes, |a, b| a.cmp(b));
+        let leaf_count = hashes.len() as u128;
+
+        builder::build_branch_levels(&mut hashes);
+
+        Tree {
+            leaf_count,
+            nodes: hashes,
+            hash_length,
+            hash_fn: hash::sha3_256,
+        }
+    }
+
+    /// from_roots builds the top tree of a layered drop with the roots of the
+    /// sub-trees as its leaves. Each sub-list of a layered drop is proven
+    /// independently with a proof::LayeredProof. The hashes are truncated
+    /// to the length of the roots. Returns None if there are no roots.
+    /// CONTRACT: all roots have the same length.
+    pub fn from_roots(roots: &[hash::Hash]) -> Option<Self> {
+        let hash_length = roots.first()?.get_length();
+        Some(Tree::build(roots, hash_length, hash::sha3_256, || {}))
+    }
+
+    /// combine_roots returns the root of the top tree built with from_roots.
+    /// Returns None if there are no roots.
+    /// CONTRACT: all roots have the same length.
+    pub fn combine_roots(roots: &[hash::Hash]) -> Option<hash::Hash> {
+        Tree::from_roots(roots)?.get_root()
+    }
+
+    // CONTRACT: hash_length is validated with hash::validate_length.
+    fn build<T: AsRef<[u8]>, F: FnMut()>(
+        items: &[T],
+        hash_length: usize,
+        hash_fn: hash::HashFn,
+        on_level: F,
+    ) -> Self {
         if items.is_empty() {
             return Tree {
                 leaf_count: 0,
                 nodes: Vec::<hash::Hash>::new(),
+                hash_length,
+                hash_fn,
             };
         }
 
-        let mut nodes: Vec<hash::Hash> = builder::build_leaf_level(items);
+        let mut nodes: Vec<hash::Hash> = builder::build_leaf_level(items, hash_length, hash_fn);
         let leaf_count = nodes.len() as u128;
 
-        builder::build_branch_levels(&mut nodes);
+        builder::build_branch_levels_with_progress(&mut nodes, hash_fn, on_level);
 
         Tree {
             leaf_count,
             nodes: nodes,
+            hash_length,
+            hash_fn,
         }
     }
 
+    /// get_root returns the root of the tree or None if the tree is empty.
+    /// Use the Display implementation of the returned Hash to
+    /// get its string representation.
     pub fn get_root(&self) -> Option<hash::Hash> {
         let node_count = self.nodes.len();
         if self.leaf_count == 0 {
@@ -37,16 +170,61 @@ impl Tree {
         Some(self.nodes[node_count - 1])
     }
 
+    /// levels returns the nodes of each level of the tree, from the sorted
+    /// leaves up to the root, e.g. to compare two builds of the same data set
+    /// level by level. An empty tree has no levels.
+    pub fn levels(&self) -> Vec<&[hash::Hash]> {
+        let mut levels = vec![];
+        let mut level_length = self.leaf_count;
+        let mut level_start = 0;
+        while level_length > 0 {
+            levels.push(&self.nodes[level_start as usize..(level_start + level_length) as usize]);
+            level_start += level_length;
+            level_length = builder::get_next_level_length(level_length);
+        }
+        levels
+    }
+
+    /// root_or_default returns the root of the tree or the all-zero hash
+    /// of the tree's hash length if the tree is empty. The zero hash is not
+    /// the root of any data set, use get_root to tell an empty tree apart.
+    pub fn root_or_default(&self) -> hash::Hash {
+        self.get_root()
+            .unwrap_or_else(|| hash::Hash::from(vec![0; self.hash_length]))
+    }
+
+    /// contains returns true if the item is a leaf of the tree.
+    /// Cheaper than find_proof for membership checks as no proof is built.
+    pub fn contains<T: AsRef<[u8]>>(&self, item: &T) -> bool {
+        let hash_to_search_for =
+            hash::leaf_with_hash_fn(item.as_ref(), self.hash_length, self.hash_fn);
+
+        // leaves are sorted by hash
+        binary_search::search(&self.nodes, self.leaf_count, &hash_to_search_for).is_some()
+    }
+
+    /// find_proof returns the proof of the item if it is a leaf of the tree. The proof
+    /// of a tree built with another hash function than sha3-256 verifies with
+    /// Proof::verify_with_hash_fn and the hash function of the tree only.
     pub fn find_proof<T: AsRef<[u8]>>(&self, item: &T) -> Option<proof::Proof> {
+        self.find_proof_with_index(item).map(|(_, proof)| proof)
+    }
+
+    /// find_proof_with_index returns the proof of the item together with the
+    /// index of the leaf it matched. Leaves are sorted by hash, so the index is
+    /// the position among the sorted leaves rather than among the given items.
+    /// If the item occurs more than once, the first matching leaf is used.
+    pub fn find_proof_with_index<T: AsRef<[u8]>>(&self, item: &T) -> Option<(usize, proof::Proof)> {
         if self.leaf_count <= 1 {
             return None;
         }
 
         let item_ref = item.as_ref();
-        let hash_to_search_for = hash::leaf(item_ref);
+        let hash_to_search_for = hash::leaf_with_hash_fn(item_ref, self.hash_length, self.hash_fn);
 
         // binary search leaves
-        let proof_index = binary_search::search(&self.nodes, self.leaf_count, &hash_to_search_for)?;
+        let proof_index =
+            binary_search::search_first(&self.nodes, self.leaf_count, &hash_to_search_for)?;
 
         let mut proof = proof::Proof::default();
 
@@ -78,7 +256,7 @@ impl Tree {
             current_index /= 2;
         }
 
-        Some(proof)
+        Some((proof_index as usize, proof))
     }
 
     #[allow(dead_code)]
@@ -141,6 +319,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn new_with_progress() {
+        for leaf_count in 1..=9 {
+            let items: Vec<String> = (0..leaf_count).map(|i| i.to_string()).collect();
+
+            let mut levels_built = 0;
+            let mt = Tree::new_with_progress(&items, || levels_built += 1);
+
+            assert_eq!(
+                Tree::level_count(leaf_count),
+                levels_built,
+                "{}",
+                leaf_count
+            );
+            assert_eq!(Tree::new(&items), mt);
+        }
+        assert_eq!(0, Tree::level_count(0));
+        assert_eq!(20, Tree::level_count(1_000_000));
+    }
+
+    #[test]
+    fn new_with_hash_fn() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+        assert_eq!(
+            Tree::new(&items),
+            Tree::new_with_hash_fn(&items, hash::sha3_256, || {})
+        );
+
+        // reversing the bytes of sha3-256 is another hash function.
+        fn reversed(data: &[u8]) -> [u8; hash::HASH_LENGTH] {
+            let mut hash = hash::sha3_256(data);
+            hash.reverse();
+            hash
+        }
+        let mt = Tree::new_with_hash_fn(&items, reversed, || {});
+        assert_ne!(Tree::new(&items).get_root(), mt.get_root());
+
+        // the leaves are found and the proofs verified with the hash function of the tree.
+        assert!(mt.contains(&test_util::ION));
+        let proof = mt.find_proof(&test_util::ION).unwrap();
+        assert!(proof.verify_with_hash_fn(&test_util::ION, &mt.get_root().unwrap(), reversed));
+
+        // trees of different hash functions differ even without nodes.
+        let empty: Vec<&[u8]> = vec![];
+        assert_ne!(
+            Tree::new(&empty),
+            Tree::new_with_hash_fn(&empty, reversed, || {})
+        );
+    }
+
+    #[test]
+    fn levels() {
+        assert!(Tree::new(&Vec::<&[u8]>::new()).levels().is_empty());
+
+        for leaf_count in 1..=9 {
+            let items: Vec<String> = (0..leaf_count).map(|i| i.to_string()).collect();
+            let mt = Tree::new(&items);
+            let levels = mt.levels();
+
+            assert_eq!(Tree::level_count(leaf_count) + 1, levels.len());
+            assert_eq!(leaf_count, levels[0].len());
+            assert_eq!(
+                mt.get_node_count() as usize,
+                levels.iter().map(|level| level.len()).sum::<usize>()
+            );
+            assert_eq!(&[mt.get_root().unwrap()], levels[levels.len() - 1]);
+        }
+    }
+
+    #[test]
+    fn root_or_default() {
+        let mt = Tree::new(&Vec::<&[u8]>::new());
+        assert_eq!(hash::Hash::default(), mt.root_or_default());
+
+        let mt = Tree::new_with_hash_length(&Vec::<&[u8]>::new(), hash::MIN_TRUNCATED_HASH_LENGTH)
+            .unwrap();
+        assert_eq!(
+            hash::Hash::from(vec![0; hash::MIN_TRUNCATED_HASH_LENGTH]),
+            mt.root_or_default()
+        );
+
+        let mt = Tree::new(&[test_util::OSMO, test_util::ION]);
+        assert_eq!(mt.get_root().unwrap(), mt.root_or_default());
+        assert_ne!(hash::Hash::default(), mt.root_or_default());
+    }
+
     #[test]
     fn new_merkle_tree_one_element() {
         let items: Vec<&[u8]> = vec![test_util::OSMO];
@@ -300,6 +564,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn new_merkle_tree_truncated_hash_length() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let full_mt = Tree::new(&items);
+        let truncated_mt =
+            Tree::new_with_hash_length(&items, hash::MIN_TRUNCATED_HASH_LENGTH).unwrap();
+
+        let full_root = full_mt.get_root().unwrap();
+        let truncated_root = truncated_mt.get_root().unwrap();
+
+        assert_eq!(hash::HASH_LENGTH, full_root.get_length());
+        assert_eq!(hash::MIN_TRUNCATED_HASH_LENGTH, truncated_root.get_length());
+        assert_ne!(full_root, truncated_root);
+
+        for item in items.iter() {
+            let full_proof = full_mt.find_proof(item).unwrap();
+            let truncated_proof = truncated_mt.find_proof(item).unwrap();
+
+            assert_eq!(true, full_proof.verify(item, &full_root));
+            assert_eq!(true, truncated_proof.verify(item, &truncated_root));
+
+            // proofs are not interchangeable between the trees.
+            assert_eq!(false, full_proof.verify(item, &truncated_root));
+            assert_eq!(false, truncated_proof.verify(item, &full_root));
+        }
+    }
+
+    #[test]
+    fn new_merkle_tree_invalid_hash_length() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION];
+
+        assert_eq!(
+            true,
+            Tree::new_with_hash_length(&items, hash::MIN_TRUNCATED_HASH_LENGTH - 1).is_err()
+        );
+        assert_eq!(
+            true,
+            Tree::new_with_hash_length(&items, hash::HASH_LENGTH + 1).is_err()
+        );
+    }
+
     #[test]
     fn find_proof_one() {
         let items: Vec<&[u8]> = vec![test_util::OSMO];
@@ -447,6 +759,86 @@ mod tests {
             *actual_proof.get_entry_at(2)
         );
     }
+
+    #[test]
+    fn contains() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+
+        let mt = Tree::new(&items);
+
+        for item in items.iter() {
+            assert_eq!(true, mt.contains(item));
+        }
+        assert_eq!(false, mt.contains(&"usdc"));
+
+        // a single leaf tree
+        let mt = Tree::new(&[test_util::OSMO]);
+        assert_eq!(true, mt.contains(&test_util::OSMO));
+        assert_eq!(false, mt.contains(&test_util::ION));
+
+        // an empty tree
+        let mt = Tree::new(&Vec::<&[u8]>::new());
+        assert_eq!(false, mt.contains(&test_util::OSMO));
+    }
+
+    #[test]
+    fn find_proof_with_index_duplicates() {
+        // Sorted by hash: OSMO, OSMO, ION, ION, WETH
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::OSMO,
+            test_util::WETH,
+            test_util::ION,
+        ];
+
+        let mt = Tree::new(&items);
+        let root = mt.get_root().unwrap();
+
+        for (item, expected_index) in [
+            (test_util::OSMO, 0),
+            (test_util::ION, 2),
+            (test_util::WETH, 4),
+        ] {
+            // the first of the duplicate leaves is matched.
+            let (index, proof) = mt.find_proof_with_index(&item).unwrap();
+
+            assert_eq!(expected_index, index);
+            assert_eq!(true, proof.verify(&item, &root));
+            assert_eq!(Some(proof), mt.find_proof(&item));
+        }
+
+        assert_eq!(None, mt.find_proof_with_index(&test_util::USDC));
+    }
+
+    #[test]
+    fn from_roots_empty() {
+        assert_eq!(None, Tree::from_roots(&[]));
+        assert_eq!(None, Tree::combine_roots(&[]));
+    }
+
+    #[test]
+    fn from_leaf_hashes() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        for count in 0..=items.len() {
+            let items = &items[..count];
+            let hashes: Vec<hash::Hash> = items.iter().map(|item| hash::leaf(item)).collect();
+
+            let mt = Tree::from_leaf_hashes(hashes);
+
+            assert_eq!(Tree::new(items), mt);
+            for item in items.iter() {
+                assert_eq!(true, mt.contains(item));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,11 @@
 use crate::hash;
+use crate::leaf::LeafEncoding;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Entry {
     pub is_left_sibling: bool,
     pub hash: hash::Hash,
@@ -16,7 +20,18 @@ impl Entry {
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
+/// VERSION is the version of the leaf encoding and the tree the proofs are built
+/// with. It must be bumped whenever either changes so that verifiers can reject
+/// proofs of another version instead of failing to verify them.
+pub const VERSION: u8 = 2;
+
+// COMPACT_FLAG_LENGTH is the length of the sibling side flag
+// preceding each hash in the compact encoding.
+const COMPACT_FLAG_LENGTH: usize = 1;
+
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Proof(Vec<Entry>);
 
 impl Proof {
@@ -27,23 +42,73 @@ impl Proof {
         })
     }
 
+    /// verify returns true if the proof for data results in root.
+    /// The leaf is truncated to the length of the root so that
+    /// proofs from trees with truncated hashes can be verified.
     pub fn verify<T: AsRef<[u8]>>(&self, data: &T, root: &hash::Hash) -> bool {
-        let initial_hash: hash::Hash = hash::leaf(data.as_ref());
+        self.verify_with_hash_fn(data, root, hash::sha3_256)
+    }
+
+    /// verify_with_hash_fn is the same as verify but hashes with the given hash
+    /// function, the one of the tree the proof is found in, see Tree::new_with_hash_fn.
+    pub fn verify_with_hash_fn<T: AsRef<[u8]>>(
+        &self,
+        data: &T,
+        root: &hash::Hash,
+        hash_fn: hash::HashFn,
+    ) -> bool {
+        let leaf_hash = hash::leaf_with_hash_fn(data.as_ref(), root.get_length(), hash_fn);
+        self.root_from_leaf_hash(&leaf_hash, hash_fn) == Some(*root)
+    }
+
+    /// root returns the root the proof reconstructs for data, so that callers can
+    /// report it, e.g. "the proof reconstructs root X, expected Y", rather than only
+    /// whether it verifies. The leaf is truncated to the length of the hashes of the
+    /// proof. As an empty proof has none, its root is the full length leaf hash.
+    /// Returns None if the hashes of the proof differ in length.
+    pub fn root<T: AsRef<[u8]>>(&self, data: &T) -> Option<hash::Hash> {
+        let length = self
+            .0
+            .first()
+            .map_or(hash::HASH_LENGTH, |entry| entry.hash.get_length());
+        self.root_from_leaf_hash(
+            &hash::leaf_with_length(data.as_ref(), length),
+            hash::sha3_256,
+        )
+    }
+
+    /// verify_any returns the index of the first of the roots the proof for data
+    /// results in, if any. Allows clients that do not know which of several drops
+    /// an entry belongs to to find its drop with a single proof.
+    pub fn verify_any<T: AsRef<[u8]>>(&self, data: &T, roots: &[hash::Hash]) -> Option<usize> {
+        roots.iter().position(|root| self.verify(data, root))
+    }
+
+    /// verify_from_leaf_hash returns true if the proof for the leaf with the given
+    /// hash results in root. Allows callers that hash the leaf themselves, e.g. with
+    /// a custom encoding, to verify without the preimage of the leaf.
+    pub fn verify_from_leaf_hash(&self, leaf_hash: &hash::Hash, root: &hash::Hash) -> bool {
+        self.root_from_leaf_hash(leaf_hash, hash::sha3_256) == Some(*root)
+    }
 
-        let result = self.0.iter().try_fold(initial_hash, |cur_hash, entry| {
+    // root_from_leaf_hash returns the root the proof reconstructs for the leaf with
+    // the given hash, or None if a hash of the proof differs in length from the leaf's.
+    fn root_from_leaf_hash(
+        &self,
+        leaf_hash: &hash::Hash,
+        hash_fn: hash::HashFn,
+    ) -> Option<hash::Hash> {
+        self.0.iter().try_fold(*leaf_hash, |cur_hash, entry| {
+            if entry.hash.get_length() != cur_hash.get_length() {
+                return None;
+            }
             let is_entry_left: bool = entry.is_left_sibling;
             if is_entry_left {
-                Some(hash::branch(&entry.hash, &cur_hash))
+                Some(hash::branch_with_hash_fn(&entry.hash, &cur_hash, hash_fn))
             } else {
-                Some(hash::branch(&cur_hash, &entry.hash))
+                Some(hash::branch_with_hash_fn(&cur_hash, &entry.hash, hash_fn))
             }
-        });
-
-        if result.is_none() {
-            return false;
-        }
-
-        result.unwrap().eq(root)
+        })
     }
 
     pub fn get_entry_at(&self, index: u128) -> &Entry {
@@ -53,6 +118,115 @@ impl Proof {
     pub fn get_num_entries(&self) -> u128 {
         self.0.len() as u128
     }
+
+    /// to_compact_bytes returns the compact binary encoding of the proof.
+    /// Each entry is encoded as a byte that is 1 if the sibling is on
+    /// the left and 0 otherwise, followed by the sibling hash. It is a fraction
+    /// of the size of the json encoding, e.g. to store proofs on chain.
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        for entry in self.0.iter() {
+            bytes.push(entry.is_left_sibling as u8);
+            bytes.extend_from_slice(entry.hash.as_ref());
+        }
+        bytes
+    }
+
+    /// from_compact_bytes decodes a proof with hashes of the given length
+    /// from the compact binary encoding produced by to_compact_bytes.
+    pub fn from_compact_bytes(bytes: &[u8], hash_length: usize) -> Result<Self, String> {
+        hash::validate_length(hash_length)?;
+
+        let entry_length = COMPACT_FLAG_LENGTH + hash_length;
+        if bytes.len() % entry_length != 0 {
+            return Err(format!(
+                "proof length ({}) is not a multiple of entry length ({})",
+                bytes.len(),
+                entry_length
+            ));
+        }
+
+        let mut proof = Proof::default();
+        for chunk in bytes.chunks(entry_length) {
+            let is_left_sibling = match chunk[0] {
+                0 => false,
+                1 => true,
+                flag => return Err(format!("invalid sibling flag ({})", flag)),
+            };
+            proof.push(is_left_sibling, hash::Hash::from(chunk[1..].to_vec()));
+        }
+
+        Ok(proof)
+    }
+
+    /// to_hex_string returns the hex encoding of the compact bytes of the
+    /// proof, see to_compact_bytes.
+    pub fn to_hex_string(&self) -> String {
+        hex::encode(self.to_compact_bytes())
+    }
+
+    /// from_hex_string decodes a proof from the compact hex encoding
+    /// produced by to_hex_string.
+    pub fn from_hex_string(hex_str: &str) -> Result<Self, String> {
+        Proof::from_hex_string_with_length(hex_str, hash::HASH_LENGTH)
+    }
+
+    /// from_hex_string_with_length decodes a proof with hashes
+    /// of the given length from the compact hex encoding.
+    pub fn from_hex_string_with_length(hex_str: &str, hash_length: usize) -> Result<Self, String> {
+        hash::validate_length(hash_length)?;
+
+        let bytes = match hex::decode(hex_str) {
+            Ok(bytes) => bytes,
+            Err(e) => return Err(format!("failed to decode proof hex: {}", e)),
+        };
+
+        Proof::from_compact_bytes(&bytes, hash_length)
+    }
+}
+
+/// LayeredProof proves data in a sub-tree of a layered drop up to the
+/// root combined with Tree::combine_roots. sub_proof proves the data up to
+/// the sub_root and root_proof proves the sub_root in the top tree.
+/// The root_proof of a drop with a single sub-tree is empty.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LayeredProof {
+    pub sub_root: hash::Hash,
+    pub sub_proof: Proof,
+    pub root_proof: Proof,
+}
+
+/// VersionedProof is a proof together with the VERSION and the leaf encoding it was
+/// built with, serialized as {"version", "leaf_encoding", "proof"}. The leaf encoding
+/// of the proofs written before it was recorded is the default, decimal_string.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VersionedProof {
+    pub version: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub leaf_encoding: LeafEncoding,
+    pub proof: Proof,
+}
+
+impl VersionedProof {
+    /// new returns the proof of the leaf encoding with the current VERSION.
+    pub fn new(proof: Proof, leaf_encoding: LeafEncoding) -> Self {
+        VersionedProof {
+            version: VERSION,
+            leaf_encoding,
+            proof,
+        }
+    }
+}
+
+impl LayeredProof {
+    /// verify returns true if the proof for data results in the combined root.
+    pub fn verify<T: AsRef<[u8]>>(&self, data: &T, root: &hash::Hash) -> bool {
+        self.sub_proof.verify(data, &self.sub_root) && self.root_proof.verify(&self.sub_root, root)
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +263,244 @@ mod tests {
             proof.verify(&test_util::USDC, &hash::leaf(test_util::USDC))
         );
     }
+
+    #[test]
+    fn root_matches_tree_root() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mt = Tree::new(&items);
+        let proof = mt.find_proof(&test_util::USDC).unwrap();
+        assert_eq!(mt.get_root(), proof.root(&test_util::USDC));
+
+        // the proof reconstructs another root for other data.
+        let other_root = proof.root(&test_util::OSMO).unwrap();
+        assert_ne!(mt.get_root().unwrap(), other_root);
+        assert_eq!(
+            false,
+            proof.verify(&test_util::OSMO, &mt.get_root().unwrap())
+        );
+
+        // the leaf is truncated to the length of the hashes of the proof.
+        let truncated =
+            Tree::new_with_hash_length(&items, hash::MIN_TRUNCATED_HASH_LENGTH).unwrap();
+        let truncated_proof = truncated.find_proof(&test_util::USDC).unwrap();
+        assert_eq!(truncated.get_root(), truncated_proof.root(&test_util::USDC));
+
+        // the root of the single leaf of a tree is the leaf hash.
+        assert_eq!(
+            Some(hash::leaf(test_util::USDC)),
+            Proof::default().root(&test_util::USDC)
+        );
+
+        // hashes of different lengths do not make a root.
+        let mut mixed = proof.clone();
+        mixed.push(false, truncated.get_root().unwrap());
+        assert_eq!(None, mixed.root(&test_util::USDC));
+    }
+
+    #[test]
+    fn verify_from_leaf_hash_matches_verify() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mt = Tree::new(&items);
+        let tree_root = &mt.get_root().unwrap();
+        let proof = mt.find_proof(&test_util::WETH).unwrap();
+
+        for item in items.iter() {
+            assert_eq!(
+                proof.verify(item, tree_root),
+                proof.verify_from_leaf_hash(&hash::leaf(item), tree_root)
+            );
+        }
+        assert_eq!(
+            true,
+            proof.verify_from_leaf_hash(&hash::leaf(test_util::WETH), tree_root)
+        );
+    }
+
+    #[test]
+    fn verify_any_matches_one_root() {
+        let first = Tree::new(&[test_util::OSMO, test_util::ION, test_util::WETH]);
+        let second = Tree::new(&[test_util::USDC, test_util::AKT, test_util::OSMO]);
+        let roots = [second.get_root().unwrap(), first.get_root().unwrap()];
+
+        // OSMO is in both trees, but its proof is of the first tree only.
+        let proof = first.find_proof(&test_util::OSMO).unwrap();
+        assert_eq!(Some(1), proof.verify_any(&test_util::OSMO, &roots));
+
+        let proof = second.find_proof(&test_util::USDC).unwrap();
+        assert_eq!(Some(0), proof.verify_any(&test_util::USDC, &roots));
+
+        assert_eq!(None, proof.verify_any(&test_util::ION, &roots));
+        assert_eq!(None, proof.verify_any(&test_util::USDC, &[]));
+    }
+
+    #[test]
+    fn hex_string_round_trip() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mt = Tree::new(&items);
+
+        let proof = mt.find_proof(&test_util::ION).unwrap();
+
+        let hex_str = proof.to_hex_string();
+        assert_eq!(
+            proof.get_num_entries() as usize * (COMPACT_FLAG_LENGTH + hash::HASH_LENGTH) * 2,
+            hex_str.len()
+        );
+
+        let decoded = Proof::from_hex_string(&hex_str).unwrap();
+        assert_eq!(proof, decoded);
+        assert_eq!(
+            true,
+            decoded.verify(&test_util::ION, &mt.get_root().unwrap())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn compact_bytes_round_trip_and_size() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mt = Tree::new(&items);
+        let proof = mt.find_proof(&test_util::WETH).unwrap();
+
+        let bytes = proof.to_compact_bytes();
+        assert_eq!(
+            proof.get_num_entries() as usize * (COMPACT_FLAG_LENGTH + hash::HASH_LENGTH),
+            bytes.len()
+        );
+        assert_eq!(
+            proof,
+            Proof::from_compact_bytes(&bytes, hash::HASH_LENGTH).unwrap()
+        );
+
+        // the json encoding spells out the base64 of each hash as numbers.
+        let json = serde_json_wasm::to_string(&proof).unwrap();
+        assert!(
+            bytes.len() * 4 < json.len(),
+            "{} {}",
+            bytes.len(),
+            json.len()
+        );
+
+        assert!(Proof::from_compact_bytes(&bytes[1..], hash::HASH_LENGTH).is_err());
+        assert!(Proof::from_compact_bytes(&bytes, 1).is_err());
+    }
+
+    #[test]
+    fn verify_empty_proof_single_leaf() {
+        let mt = Tree::new(&[test_util::OSMO]);
+        let root = mt.get_root().unwrap();
+
+        // the root of a single leaf tree is the leaf hash,
+        // so the leaf is proven by an empty proof.
+        assert_eq!(hash::leaf(test_util::OSMO), root);
+        assert_eq!(true, Proof::default().verify(&test_util::OSMO, &root));
+        assert_eq!(false, Proof::default().verify(&test_util::ION, &root));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn verify_empty_json_proof_single_leaf() {
+        let mt = Tree::new(&[test_util::OSMO]);
+        let root = mt.get_root().unwrap();
+
+        let proof: Proof = serde_json_wasm::from_str("[]").unwrap();
+        assert_eq!(true, proof.verify(&test_util::OSMO, &root));
+    }
+
+    #[test]
+    fn layered_proof_verify() {
+        let first_items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+        let second_items: Vec<&[u8]> = vec![test_util::USDC, test_util::AKT];
+
+        let first = Tree::new(&first_items);
+        let second = Tree::new(&second_items);
+        let sub_roots = vec![first.get_root().unwrap(), second.get_root().unwrap()];
+
+        let top = Tree::from_roots(&sub_roots).unwrap();
+        let root = Tree::combine_roots(&sub_roots).unwrap();
+        assert_eq!(top.get_root().unwrap(), root);
+
+        for (tree, items) in [(&first, &first_items), (&second, &second_items)] {
+            let sub_root = tree.get_root().unwrap();
+            for item in items.iter() {
+                let layered = LayeredProof {
+                    sub_root,
+                    sub_proof: tree.find_proof(item).unwrap(),
+                    root_proof: top.find_proof(&sub_root).unwrap(),
+                };
+
+                assert_eq!(true, layered.verify(item, &root));
+                // the sub-proof alone does not prove the item in the combined root.
+                assert_eq!(false, layered.sub_proof.verify(item, &root));
+            }
+        }
+
+        // an item of one sub-tree cannot be proven through the other.
+        let layered = LayeredProof {
+            sub_root: second.get_root().unwrap(),
+            sub_proof: first.find_proof(&test_util::OSMO).unwrap(),
+            root_proof: top.find_proof(&second.get_root().unwrap()).unwrap(),
+        };
+        assert_eq!(false, layered.verify(&test_util::OSMO, &root));
+    }
+
+    #[test]
+    fn from_hex_string_invalid() {
+        // not hex
+        assert_eq!(true, Proof::from_hex_string("zz").is_err());
+
+        // not a multiple of the entry length
+        assert_eq!(true, Proof::from_hex_string("0001").is_err());
+
+        // invalid sibling flag
+        let invalid_flag = format!("02{}", hex::encode([0u8; 32]));
+        assert_eq!(true, Proof::from_hex_string(&invalid_flag).is_err());
+    }
+
+    #[test]
+    fn hex_string_truncated_round_trip() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+
+        let mt = Tree::new_with_hash_length(&items, hash::MIN_TRUNCATED_HASH_LENGTH).unwrap();
+
+        let proof = mt.find_proof(&test_util::WETH).unwrap();
+
+        let decoded = Proof::from_hex_string_with_length(
+            &proof.to_hex_string(),
+            hash::MIN_TRUNCATED_HASH_LENGTH,
+        )
+        .unwrap();
+        assert_eq!(proof, decoded);
+        assert_eq!(
+            true,
+            decoded.verify(&test_util::WETH, &mt.get_root().unwrap())
+        );
+    }
 }
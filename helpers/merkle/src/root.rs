@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::hash;
+
+// HEX_PREFIX is the optional prefix of hex encoded roots, as printed by EVM tooling.
+const HEX_PREFIX: &str = "0x";
+
+#[derive(Debug, PartialEq)]
+pub enum RootError {
+    /// The root is neither valid hex nor valid base64.
+    InvalidEncoding(String),
+    /// The decoded root is not a valid hash length, see hash::validate_length.
+    InvalidLength(usize),
+}
+
+impl fmt::Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootError::InvalidEncoding(reason) => write!(f, "invalid root encoding: {}", reason),
+            RootError::InvalidLength(length) => write!(
+                f,
+                "invalid root length ({} bytes), expected {} to {} bytes",
+                length,
+                hash::MIN_TRUNCATED_HASH_LENGTH,
+                hash::HASH_LENGTH
+            ),
+        }
+    }
+}
+
+impl Error for RootError {}
+
+/// parse_root decodes a root given as hex, optionally prefixed with 0x, or as base64,
+/// the encoding printed by the CLI. A root without the prefix is hex only if it is
+/// 64 hex digits, the length of a full hash, since shorter hex strings are valid base64.
+/// Truncated roots in hex must therefore be prefixed.
+pub fn parse_root(root: &str) -> Result<hash::Hash, RootError> {
+    let bytes = match root.strip_prefix(HEX_PREFIX) {
+        Some(hex_root) => decode_hex(hex_root)?,
+        None if is_full_hash_hex(root) => decode_hex(root)?,
+        None => base64::decode(root).map_err(|e| RootError::InvalidEncoding(e.to_string()))?,
+    };
+
+    if hash::validate_length(bytes.len()).is_err() {
+        return Err(RootError::InvalidLength(bytes.len()));
+    }
+    Ok(hash::Hash::from(bytes))
+}
+
+fn is_full_hash_hex(root: &str) -> bool {
+    root.len() == 2 * hash::HASH_LENGTH && root.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn decode_hex(root: &str) -> Result<Vec<u8>, RootError> {
+    hex::decode(root).map_err(|e| RootError::InvalidEncoding(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tree;
+
+    const ROOT_BASE64: &str = "yxPPbj/k6GXAgs6To1emKJBC3jq4hSeSDchhOs9j/t4=";
+
+    #[test]
+    fn parse_root_valid() {
+        let root = hash::Hash::from(base64::decode(ROOT_BASE64).unwrap());
+        let root_hex = hex::encode(root);
+
+        assert_eq!(Ok(root), parse_root(ROOT_BASE64));
+        assert_eq!(Ok(root), parse_root(&root_hex));
+        assert_eq!(Ok(root), parse_root(&root_hex.to_uppercase()));
+    }
+
+    #[test]
+    fn parse_root_prefixed() {
+        let root = hash::Hash::from(base64::decode(ROOT_BASE64).unwrap());
+
+        assert_eq!(Ok(root), parse_root(&format!("0x{}", hex::encode(root))));
+
+        // a truncated root in hex must be prefixed.
+        let items = vec!["osmo", "ion"];
+        let truncated = Tree::new_with_hash_length(&items, hash::MIN_TRUNCATED_HASH_LENGTH)
+            .unwrap()
+            .get_root()
+            .unwrap();
+        assert_eq!(
+            Ok(truncated),
+            parse_root(&format!("0x{}", hex::encode(truncated)))
+        );
+        assert_eq!(Ok(truncated), parse_root(&truncated.to_string()));
+    }
+
+    #[test]
+    fn parse_root_wrong_length() {
+        for length in [
+            0,
+            hash::MIN_TRUNCATED_HASH_LENGTH - 1,
+            hash::HASH_LENGTH + 1,
+        ] {
+            let bytes = vec![1; length];
+
+            assert_eq!(
+                Err(RootError::InvalidLength(length)),
+                parse_root(&format!("0x{}", hex::encode(&bytes)))
+            );
+            assert_eq!(
+                Err(RootError::InvalidLength(length)),
+                parse_root(&base64::encode(&bytes))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_root_not_hex() {
+        for root in ["0xzz", "0x123", "not a root", "new root"] {
+            assert!(
+                matches!(parse_root(root), Err(RootError::InvalidEncoding(_))),
+                "{}",
+                root
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! test_vectors contains small fixed trees with their expected roots and proofs.
+//! Any change to hashing, leaf ordering or proof encoding breaks the tests in
+//! this module. Implementations in other languages (e.g. JS or Go) can use
+//! these vectors to verify that they conform to this crate.
+
+pub struct TestVector {
+    /// leaves the data the tree is built from, in insertion order.
+    pub leaves: &'static [&'static str],
+    /// root the expected root as formatted by the Hash Display implementation.
+    pub root: &'static str,
+    /// proofs the expected proof for each of the leaves, in the same order,
+    /// encoded with Proof::to_hex_string. The proof of a single leaf tree is empty.
+    pub proofs: &'static [&'static str],
+}
+
+pub const ONE_LEAF: TestVector = TestVector {
+    leaves: &["osmo"],
+    root: "m4IzBSVKzd/hiJJyUsGHurz9I2oxjw1CQWvAIMv7DsA=",
+    proofs: &[""],
+};
+
+pub const TWO_LEAVES: TestVector = TestVector {
+    leaves: &["osmo", "ion"],
+    root: "kAcKkLvWQ29g6olgapxk62H9FqwYCIedbdAKlVKt46I=",
+    proofs: &[
+        "00b88e2532a56457d0fb5d453e2070569965b2f1259e6ed369a9a556effb911ea2",
+        "019b823305254acddfe188927252c187babcfd236a318f0d42416bc020cbfb0ec0",
+    ],
+};
+
+pub const THREE_LEAVES: TestVector = TestVector {
+    leaves: &["osmo", "weth", "ion"],
+    root: "scxY+uXafqyYr2bjyAsCC3VqGuHxVPLneprosypMQXw=",
+    proofs: &[
+        "00b88e2532a56457d0fb5d453e2070569965b2f1259e6ed369a9a556effb911ea200973cc07d81297c42c94fb54f3d2dcdd4784d982208c85ce4f36d6ca049313a00",
+        "00f876a08c774b73354e01a3d703897d3f7d545f14324175e8be9e208971e546c20190070a90bbd6436f60ea89606a9c64eb61fd16ac1808879d6dd00a9552ade3a2",
+        "019b823305254acddfe188927252c187babcfd236a318f0d42416bc020cbfb0ec000973cc07d81297c42c94fb54f3d2dcdd4784d982208c85ce4f36d6ca049313a00",
+    ],
+};
+
+pub const FIVE_LEAVES: TestVector = TestVector {
+    leaves: &["osmo", "ion", "weth", "usdc", "akt"],
+    root: "3IFi2u0FnNlMiXP3X2DSPNeZZZl1C05xjM+cWiSJ7Y8=",
+    proofs: &[
+        "00ad97bc0a9da12f9d4a68763600849a9669efe6cb889248e39d6e44c646c22965008cf0e2a0c5d7457efa09ad56214631e9fa2abdc6e5e11451555df83f1889f1960055ac1f32e54168baaf03a193b1b123cdb9993e6ebd46dbad1856dd816ece9eec",
+        "00dee81ca744b45448f0d7a6e87605454669501e9ce941195bd553ff3f809a320501f25b3aaf1c90ba1794bb1baacffc843bbec2e8c468f983a2deffe4d7739018320055ac1f32e54168baaf03a193b1b123cdb9993e6ebd46dbad1856dd816ece9eec",
+        "00f876a08c774b73354e01a3d703897d3f7d545f14324175e8be9e208971e546c200973cc07d81297c42c94fb54f3d2dcdd4784d982208c85ce4f36d6ca049313a00019fbc11c16b4f6f244b2b94b5f2560f05e231b4d1c800191b3a5739f0ad72db31",
+        "019b823305254acddfe188927252c187babcfd236a318f0d42416bc020cbfb0ec0008cf0e2a0c5d7457efa09ad56214631e9fa2abdc6e5e11451555df83f1889f1960055ac1f32e54168baaf03a193b1b123cdb9993e6ebd46dbad1856dd816ece9eec",
+        "01b88e2532a56457d0fb5d453e2070569965b2f1259e6ed369a9a556effb911ea201f25b3aaf1c90ba1794bb1baacffc843bbec2e8c468f983a2deffe4d7739018320055ac1f32e54168baaf03a193b1b123cdb9993e6ebd46dbad1856dd816ece9eec",
+    ],
+};
+
+pub const VECTORS: &[TestVector] = &[ONE_LEAF, TWO_LEAVES, THREE_LEAVES, FIVE_LEAVES];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::Proof;
+    use crate::Tree;
+
+    #[test]
+    fn vectors_root() {
+        for vector in VECTORS {
+            let mt = Tree::new(vector.leaves);
+
+            assert_eq!(vector.root, mt.get_root().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn vectors_find_proof() {
+        for vector in VECTORS {
+            let mt = Tree::new(vector.leaves);
+
+            for (leaf, expected_proof) in vector.leaves.iter().zip(vector.proofs.iter()) {
+                // a single leaf tree has no proof to find.
+                let actual_proof = mt.find_proof(leaf).unwrap_or_default();
+
+                assert_eq!(
+                    *expected_proof,
+                    actual_proof.to_hex_string(),
+                    "leaf {}",
+                    leaf
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn vectors_verify() {
+        for vector in VECTORS {
+            let root = Tree::new(vector.leaves).get_root().unwrap();
+
+            for (leaf, expected_proof) in vector.leaves.iter().zip(vector.proofs.iter()) {
+                let proof = Proof::from_hex_string(expected_proof).unwrap();
+
+                assert_eq!(true, proof.verify(leaf, &root), "leaf {}", leaf);
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! wasm exposes the claim verification to JS so that front-ends can
+//! verify a proof in the browser before submitting a claim.
+//! The verification shares the hashing with the contract and the CLI.
+
+use crate::leaf;
+use wasm_bindgen::prelude::*;
+
+/// verify returns true if the proof in the compact hex encoding proves
+/// that the claim of amount by address is in the tree with the hex encoded root.
+/// Returns false if the root or the proof cannot be decoded.
+#[wasm_bindgen]
+pub fn verify(root_hex: &str, proof_hex: &str, address: &str, amount: &str) -> bool {
+    leaf::verify_claim(root_hex, proof_hex, address, amount).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tree;
+
+    const ADDRESS: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+    const OTHER_ADDRESS: &str = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4";
+
+    // verify_round_trip calls the exported function the same way JS does.
+    #[test]
+    fn verify_round_trip() {
+        let leaf = leaf::encode(ADDRESS, "1421901", None);
+        let items = vec![leaf.clone(), leaf::encode(OTHER_ADDRESS, "10", None)];
+
+        let mt = Tree::new(&items);
+        let root_hex = hex::encode(mt.get_root().unwrap());
+        let proof_hex = mt.find_proof(&leaf).unwrap().to_hex_string();
+
+        assert!(verify(&root_hex, &proof_hex, ADDRESS, "1421901"));
+        assert!(!verify(&root_hex, &proof_hex, ADDRESS, "1421902"));
+        assert!(!verify("not hex", &proof_hex, ADDRESS, "1421901"));
+    }
+}